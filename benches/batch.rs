@@ -0,0 +1,44 @@
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use optiors::prelude::*;
+use rust_decimal::Decimal;
+
+/// One expiry of 400 calls quoted in IV
+fn chain() -> OptionChain<OptionTick> {
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    OptionChain(
+        (0..400)
+            .map(|i| {
+                OptionTick::builder()
+                    .strike(Decimal::from(20000 + 50 * i))
+                    .maturity(now + Duration::days(30))
+                    .asset_price(30000.)
+                    .option_type(OptionType::Call)
+                    .option_value(OptionValue::ImpliedVolatility(0.5 + 0.0005 * i as f64))
+                    .valuation_time(now)
+                    .build()
+            })
+            .collect(),
+    )
+}
+
+fn batch(c: &mut Criterion) {
+    let chain = chain();
+    let market = BatchMarket::from_tick(&chain.0[0]);
+    let strikes: Vec<FloatType> = chain.0.iter().map(|t| t.strike_f64()).collect();
+    let ivs: Vec<FloatType> = chain.0.iter().map(|t| t.iv()).collect();
+    let params: Vec<BsParams> = chain.0.iter().map(|t| t.bs_params()).collect();
+
+    c.bench_function("OptionTick::get_theoretical_price x400", |b| {
+        b.iter(|| chain.map(OptionTick::get_theoretical_price))
+    });
+    c.bench_function("BsParams::theoretical_price x400", |b| {
+        b.iter(|| params.iter().map(|p| p.theoretical_price()).collect::<Vec<_>>())
+    });
+    c.bench_function("price_batch x400", |b| {
+        b.iter(|| price_batch(black_box(&market), OptionType::Call, black_box(&strikes), black_box(&ivs)))
+    });
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
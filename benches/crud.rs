@@ -0,0 +1,69 @@
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use optiors::prelude::*;
+use rust_decimal::Decimal;
+
+fn tick(strike: i64, days: i64, option_type: OptionType, side: OptionSide, price: FloatType) -> OptionTick {
+    OptionTick::builder()
+        .strike(Decimal::from(strike))
+        .maturity(Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap() + Duration::days(days))
+        .asset_price(30000.)
+        .option_type(option_type)
+        .option_value(OptionValue::Price(price))
+        .side(side)
+        .build()
+}
+
+/// 8 expiries x 100 strikes x call/put x bid/ask
+fn ticks() -> Vec<OptionTick> {
+    let mut ticks = Vec::new();
+    for days in (7..=56).step_by(7) {
+        for strike in (20000..40000).step_by(200) {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                ticks.push(tick(strike, days, option_type.clone(), OptionSide::Bid, 100.));
+                ticks.push(tick(strike, days, option_type, OptionSide::Ask, 110.));
+            }
+        }
+    }
+    ticks
+}
+
+fn crud(c: &mut Criterion) {
+    let ticks = ticks();
+    let mut board = OptionBoard::<StrikeBoard>::new();
+    let mut indexed = IndexedBoard::new();
+    for tick in ticks.iter() {
+        board.upsert(tick.clone());
+        indexed.upsert(tick.clone());
+    }
+    let update = tick(30000, 28, OptionType::Call, OptionSide::Bid, 100.);
+
+    c.bench_function("OptionBoard<StrikeBoard>::upsert", |b| {
+        b.iter(|| board.upsert(black_box(update.clone())))
+    });
+    c.bench_function("IndexedBoard::upsert", |b| {
+        b.iter(|| indexed.upsert(black_box(update.clone())))
+    });
+    c.bench_function("OptionBoard<StrikeBoard>::delete + upsert", |b| {
+        b.iter(|| {
+            board.delete(black_box(update.clone()));
+            board.upsert(black_box(update.clone()))
+        })
+    });
+    c.bench_function("OptionBoard<StrikeBoard> build", |b| {
+        b.iter_batched(
+            || ticks.clone(),
+            |ticks| {
+                let mut board = OptionBoard::<StrikeBoard>::new();
+                for tick in ticks {
+                    board.upsert(tick);
+                }
+                board
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, crud);
+criterion_main!(benches);
@@ -0,0 +1,191 @@
+//! Static arbitrage checks on chains and boards.
+//!
+//! * Vertical: call prices must not increase with the strike (put prices must not decrease), and the price difference
+//!   between two strikes must not exceed the discounted strike difference.
+//! * Butterfly: prices must be convex in strike, i.e. every strike must be priced at or below the linear interpolation of its neighbours.
+//! * Calendar (OptionBoard only): total implied variance sigma^2 tau at a given forward moneyness must not decrease with maturity.
+//!
+//! Prices are the theoretical prices of the ticks, so chains quoted in IV are checked as well. Calls and puts are checked separately.
+//! The report lists each violation with the offending strikes and expiries, so that the quotes can be removed before fitting.
+//!
+//! # Example
+//! ```ignore
+//! let report = board.arbitrage_report();
+//! for v in report.violations.iter() {
+//!     println!("{:?} {:?} {:?} {}", v.kind, v.strikes, v.maturities, v.amount);
+//! }
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Violations smaller than this fraction of the asset price are ignored as rounding.
+const TOLERANCE: FloatType = 1e-8;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArbitrageKind {
+    Vertical,
+    Butterfly,
+    Calendar,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArbitrageViolation {
+    pub kind: ArbitrageKind,
+    pub option_type: OptionType,
+    /// Strikes of the legs, in ascending order
+    pub strikes: Vec<DecimalType>,
+    /// Expiries of the legs: one for vertical and butterfly violations, the shorter and the longer for calendar violations
+    pub maturities: Vec<DateTime<Utc>>,
+    /// Size of the violation, in price for vertical and butterfly violations and in total variance for calendar violations
+    pub amount: FloatType,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArbitrageReport {
+    pub violations: Vec<ArbitrageViolation>,
+}
+
+impl ArbitrageReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn of_kind(&self, kind: ArbitrageKind) -> impl Iterator<Item = &ArbitrageViolation> {
+        self.violations.iter().filter(move |v| v.kind == kind)
+    }
+
+    /// (maturity, strike, option type) of every leg involved in a violation
+    pub fn offending_contracts(&self) -> BTreeSet<(DateTime<Utc>, DecimalType, OptionType)> {
+        let mut contracts = BTreeSet::new();
+        for v in self.violations.iter() {
+            for maturity in v.maturities.iter() {
+                for strike in v.strikes.iter() {
+                    contracts.insert((*maturity, *strike, v.option_type.clone()));
+                }
+            }
+        }
+        contracts
+    }
+}
+
+/// (strike, price) of the ticks of one option type, sorted by strike, skipping ticks without a valid price
+fn price_curve(chain: &OptionChain<OptionTick>, option_type: &OptionType) -> Vec<(DecimalType, FloatType)> {
+    let mut curve: Vec<(DecimalType, FloatType)> = chain
+        .0
+        .iter()
+        .filter(|t| t.option_type == *option_type)
+        .map(|t| (t.strike, t.get_theoretical_price().get_value()))
+        .filter(|(_, p)| p.is_finite())
+        .collect();
+    curve.sort_by_key(|(strike, _)| *strike);
+    curve
+}
+
+impl OptionChain<OptionTick> {
+    /// Vertical spread and butterfly violations of the chain.
+    pub fn arbitrage_report(&self) -> ArbitrageReport {
+        let mut report = ArbitrageReport::default();
+        let first = match self.0.first() {
+            Some(first) => first,
+            None => return report,
+        };
+        let tolerance = TOLERANCE * first.asset_price;
+        let discount = first.discount_factor();
+        let maturity = first.maturity;
+
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let curve = price_curve(self, &option_type);
+            let violation = |kind, strikes: Vec<DecimalType>, amount| ArbitrageViolation {
+                kind,
+                option_type: option_type.clone(),
+                strikes,
+                maturities: vec![maturity],
+                amount,
+            };
+            for w in curve.windows(2) {
+                let ((k1, p1), (k2, p2)) = (w[0], w[1]);
+                let width = (k2 - k1).to_f64().unwrap_or(0.);
+                // Price decrease from the cheaper side to the dearer side of the spread
+                let spread = match option_type {
+                    OptionType::Call => p1 - p2,
+                    OptionType::Put => p2 - p1,
+                };
+                let amount = if spread < 0. { -spread } else { spread - width * discount };
+                if amount > tolerance {
+                    report.violations.push(violation(ArbitrageKind::Vertical, vec![k1, k2], amount));
+                }
+            }
+            for w in curve.windows(3) {
+                let ((k1, p1), (k2, p2), (k3, p3)) = (w[0], w[1], w[2]);
+                let (x1, x2, x3) = (k1.to_f64().unwrap(), k2.to_f64().unwrap(), k3.to_f64().unwrap());
+                let weight = (x3 - x2) / (x3 - x1);
+                let amount = p2 - (weight * p1 + (1. - weight) * p3);
+                if amount > tolerance {
+                    report.violations.push(violation(ArbitrageKind::Butterfly, vec![k1, k2, k3], amount));
+                }
+            }
+        }
+        report
+    }
+}
+
+/// (log forward moneyness, total variance, strike) of the OTM ticks of the chain, sorted by moneyness
+fn total_variance_curve(chain: &OptionChain<OptionTick>) -> Vec<(FloatType, FloatType, DecimalType, OptionType)> {
+    let mut curve: Vec<_> = chain
+        .otm()
+        .0
+        .iter()
+        .map(|t| t.get_implied_volatility())
+        .filter(|t| t.get_value().is_finite() && t.get_value() > 0.)
+        .map(|t| {
+            let iv = t.get_value();
+            (t.log_moneyness(), iv * iv * t.tau(), t.strike, t.option_type.clone())
+        })
+        .collect();
+    curve.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    curve
+}
+
+impl OptionBoard<OptionTick> {
+    /// Vertical and butterfly violations of every chain, and calendar violations between consecutive expiries.
+    /// For the calendar check, the total variance of the longer expiry is interpolated linearly in log forward moneyness
+    /// at the moneyness of each OTM strike of the shorter expiry, within the range of its strikes.
+    pub fn arbitrage_report(&self) -> ArbitrageReport {
+        let board = self.sort_by_maturity();
+        let mut report = ArbitrageReport::default();
+        for chain in board.0.iter() {
+            report.violations.extend(chain.arbitrage_report().violations);
+        }
+        for w in board.0.windows(2) {
+            let (near, far) = (total_variance_curve(&w[0]), total_variance_curve(&w[1]));
+            let (near_maturity, far_maturity) = match (w[0].0.first(), w[1].0.first()) {
+                (Some(a), Some(b)) => (a.maturity, b.maturity),
+                _ => continue,
+            };
+            for (m, variance, strike, option_type) in near.iter() {
+                let far_variance = far.windows(2).find(|f| f[0].0 <= *m && *m <= f[1].0).map(|f| {
+                    let weight = if f[1].0 > f[0].0 { (m - f[0].0) / (f[1].0 - f[0].0) } else { 0. };
+                    f[0].1 + weight * (f[1].1 - f[0].1)
+                });
+                if let Some(far_variance) = far_variance {
+                    let amount = variance - far_variance;
+                    if amount > TOLERANCE {
+                        report.violations.push(ArbitrageViolation {
+                            kind: ArbitrageKind::Calendar,
+                            option_type: option_type.clone(),
+                            strikes: vec![*strike],
+                            maturities: vec![near_maturity, far_maturity],
+                            amount,
+                        });
+                    }
+                }
+            }
+        }
+        report
+    }
+}
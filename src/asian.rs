@@ -0,0 +1,125 @@
+//! Average price (Asian) options with continuous averaging from the valuation time to maturity.
+//!
+//! * Geometric average: closed form of Kemna and Vorst. The geometric average of a GBM is lognormal, so the option is
+//!   a Black-Scholes option with volatility sigma / sqrt(3) and cost of carry (b - sigma^2 / 6) / 2.
+//! * Arithmetic average: Turnbull-Wakeman approximation. The first two moments of the arithmetic average are matched by a lognormal,
+//!   which is priced with the Black-Scholes formula. monte_carlo() prices the same payoff by simulation to check the approximation.
+//!
+//! b = r - q is the cost of carry of the tick (0 for Black-76). Greeks are available through NumericalGreeks, on the closed-form prices.
+//!
+//! # Example
+//! ```ignore
+//! let asian = AsianOption::new(tick, AsianAverage::Arithmetic);
+//! let check = asian.monte_carlo(&McPricer::new(McConfig::builder().n_paths(100000).build()))?;
+//! println!("{} vs {} +- {}", asian.price_closed_form()?, check.price, check.std_error);
+//! let option = ExoticOption::Asian(asian);
+//! println!("{}", option.numerical_vega());
+//! ```
+
+use crate::black_scholes::{BlackScholes, BsParams};
+use crate::models::*;
+use crate::monte_carlo::{McPricer, McResult, Payoff};
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// Below this |b T|, the moments of the arithmetic average use their limit at b = 0
+const ZERO_CARRY: FloatType = 1e-8;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AsianAverage {
+    Geometric,
+    Arithmetic,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AsianOption {
+    pub tick: OptionTick,
+    pub average: AsianAverage,
+}
+
+impl AsianOption {
+    pub fn new(tick: OptionTick, average: AsianAverage) -> Self {
+        Self { tick, average }
+    }
+
+    /// Black-Scholes params of the lognormal standing for the average: the spot, strike and rate of the tick
+    /// with the volatility and cost of carry of the average.
+    fn average_params(&self) -> Result<BsParams> {
+        let mut params = self.tick.get_implied_volatility().bs_params();
+        ensure!(params.tau > 0., "The option has expired");
+        ensure!(params.sigma.is_finite() && params.sigma > 0., "Implied volatility is not available");
+        let (t, sigma) = (params.tau, params.sigma);
+        let b = params.rate - params.carry;
+        let (carry_a, sigma_a) = match self.average {
+            AsianAverage::Geometric => (0.5 * (b - sigma * sigma / 6.), sigma / 3f64.sqrt()),
+            AsianAverage::Arithmetic => {
+                let v = sigma * sigma;
+                let (m1, m2) = if (b * t).abs() < ZERO_CARRY {
+                    (1., 2. * ((v * t).exp() - 1. - v * t) / (v * v * t * t))
+                } else {
+                    (
+                        ((b * t).exp() - 1.) / (b * t),
+                        2. * ((2. * b + v) * t).exp() / ((b + v) * (2. * b + v) * t * t)
+                            + 2. / (b * t * t) * (1. / (2. * b + v) - (b * t).exp() / (b + v)),
+                    )
+                };
+                let carry_a = m1.ln() / t;
+                (carry_a, (m2.ln() / t - 2. * carry_a).sqrt())
+            }
+        };
+        params.carry = params.rate - carry_a;
+        params.sigma = sigma_a;
+        params.price = None;
+        Ok(params)
+    }
+
+    /// Kemna-Vorst price for the geometric average, Turnbull-Wakeman approximation for the arithmetic average.
+    pub fn price_closed_form(&self) -> Result<FloatType> {
+        Ok(self.average_params()?.theoretical_price())
+    }
+
+    /// Monte Carlo price of the arithmetic average, monitored at the n_steps dates of the pricer.
+    pub fn monte_carlo(&self, pricer: &McPricer) -> Result<McResult> {
+        ensure!(
+            self.average == AsianAverage::Arithmetic,
+            "Monte Carlo pricing is only available for the arithmetic average"
+        );
+        pricer.price(&self.tick.get_implied_volatility(), &Payoff::Asian)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::McConfig;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, option_type: OptionType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(365))
+            .option_type(option_type)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn arithmetic_matches_monte_carlo() {
+        let tick = tick(dec!(100), OptionType::Call);
+        let arithmetic = AsianOption::new(tick.clone(), AsianAverage::Arithmetic);
+        let geometric = AsianOption::new(tick, AsianAverage::Geometric);
+        let price = arithmetic.price_closed_form().unwrap();
+        // The geometric average is never above the arithmetic one
+        assert!(geometric.price_closed_form().unwrap() < price);
+        let pricer = McPricer::new(McConfig::builder().n_paths(20_000).n_steps(252).seed(7).build());
+        let mc = arithmetic.monte_carlo(&pricer).unwrap();
+        assert_float_absolute_eq!(price, mc.price, 3. * mc.std_error);
+    }
+}
@@ -0,0 +1,263 @@
+//! Bachelier (normal) model, for underlyings that can go negative or very low such as rates and spreads.
+//!
+//! The underlying follows dF = σ_N dW with a normal volatility σ_N in price units (e.g. 0.01 for 100bp a year on a rate).
+//! As with Black-76, asset_price is the forward or futures price and the risk free rate only discounts the payoff:
+//! with d = (F - K) / (σ_N sqrt(tau)),
+//! * call: e^{-r tau} [(F - K) Φ(d) + σ_N sqrt(tau) φ(d)]
+//! * put: e^{-r tau} [(K - F) Φ(-d) + σ_N sqrt(tau) φ(d)]
+//!
+//! A tick is priced with this model when its pricing_model is PricingModel::Bachelier; its OptionValue::ImpliedVolatility is then
+//! the normal volatility, and the greeks of EuropeanGreeks are those of the normal model (vega per unit of normal volatility).
+//! normal_to_lognormal_vol and lognormal_to_normal_vol convert a volatility of one model into the volatility of the other
+//! that gives the same price.
+//!
+//! # Example
+//! ```ignore
+//! let tick = OptionTick::builder().strike(dec!(0.5)).asset_price(0.2).maturity(maturity)
+//!     .option_type(OptionType::Call).option_value(OptionValue::Price(0.05))
+//!     .pricing_model(PricingModel::Bachelier).build();
+//! let normal_vol = tick.get_implied_volatility().iv();
+//! let black_vol = normal_to_lognormal_vol(3.0, 3.2, 0.5, 0.01)?;
+//! ```
+
+use crate::black_scholes::BsParams;
+use crate::greeks::Greek;
+use crate::math::{norm_cdf, norm_pdf};
+use crate::models::*;
+use anyhow::{ensure, Result};
+use rust_decimal::prelude::*;
+
+/// Inputs of the Bachelier formulas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BachelierParams {
+    pub forward: FloatType,
+    pub strike: FloatType,
+    /// Time to maturity in years
+    pub tau: FloatType,
+    pub rate: FloatType,
+    /// Normal volatility, NaN if unknown
+    pub sigma: FloatType,
+    pub option_type: OptionType,
+    /// Premium, None if unknown
+    pub price: Option<FloatType>,
+}
+
+impl BachelierParams {
+    /// e^{-r tau}
+    pub fn discount_factor(&self) -> FloatType {
+        (-self.rate * self.tau).exp()
+    }
+
+    /// Standard deviation of the forward at maturity, σ_N sqrt(tau)
+    fn stdev(&self) -> FloatType {
+        self.sigma * self.tau.sqrt()
+    }
+
+    /// (F - K) / (σ_N sqrt(tau))
+    pub fn d(&self) -> FloatType {
+        (self.forward - self.strike) / self.stdev()
+    }
+
+    pub fn theoretical_price(&self) -> FloatType {
+        let d = self.d();
+        let time_value = self.stdev() * norm_pdf(d);
+        let intrinsic = match self.option_type {
+            OptionType::Call => (self.forward - self.strike) * norm_cdf(d),
+            OptionType::Put => (self.strike - self.forward) * norm_cdf(-d),
+        };
+        self.discount_factor() * (intrinsic + time_value)
+    }
+
+    /// Normal volatility matching the premium of the params, by bisection. NaN if the premium is unknown or below the
+    /// discounted intrinsic value.
+    pub fn implied_volatility(&self) -> FloatType {
+        match self.price {
+            Some(price) => implied_vol(price, |sigma| {
+                Self {
+                    sigma,
+                    ..self.clone()
+                }
+                .theoretical_price()
+            }),
+            None => FloatType::NAN,
+        }
+    }
+
+    /// Value of the greek under the normal model. The forward takes the place of the spot, so epsilon (the sensitivity to the
+    /// carry) is 0, rho only reflects discounting and lambda is the elasticity to the forward.
+    pub fn greek(&self, greek: Greek) -> FloatType {
+        let d = self.d();
+        let df = self.discount_factor();
+        let pdf = norm_pdf(d);
+        let tau = self.tau;
+        let sigma = self.sigma;
+        let delta = match self.option_type {
+            OptionType::Call => df * norm_cdf(d),
+            OptionType::Put => -df * norm_cdf(-d),
+        };
+        let gamma = df * pdf / self.stdev();
+        let vega = df * tau.sqrt() * pdf;
+        let vomma = vega * d * d / sigma;
+        let veta = vega * (1. + d * d) / (2. * tau) - self.rate * vega;
+        match greek {
+            Greek::Delta => delta,
+            Greek::Gamma => gamma,
+            Greek::Theta => {
+                self.rate * self.theoretical_price() - df * sigma * pdf / (2. * tau.sqrt())
+            }
+            Greek::Rho => -tau * self.theoretical_price(),
+            Greek::Vega => vega,
+            Greek::Epsilon => 0.,
+            Greek::Vanna => -df * pdf * d / sigma,
+            Greek::Charm => self.rate * delta + df * pdf * d / (2. * tau),
+            Greek::Vomma => vomma,
+            Greek::Veta => veta,
+            Greek::Speed => -gamma * d / self.stdev(),
+            Greek::Zomma => gamma * (d * d - 1.) / sigma,
+            Greek::Color => -gamma * (self.rate + (1. - d * d) / (2. * tau)),
+            Greek::Ultima => vomma * (d * d - 3.) / sigma,
+            Greek::DualDelta => match self.option_type {
+                OptionType::Call => -df * norm_cdf(d),
+                OptionType::Put => df * norm_cdf(-d),
+            },
+            Greek::DualGamma => gamma,
+            Greek::Lambda => delta * self.forward / self.theoretical_price(),
+            Greek::DvegaDtime => -veta,
+        }
+    }
+}
+
+/// Volatility at which the price function, increasing in the volatility, equals the price.
+fn implied_vol(price: FloatType, price_at: impl Fn(FloatType) -> FloatType) -> FloatType {
+    if !price.is_finite() || price < price_at(0.) {
+        return FloatType::NAN;
+    }
+    let mut high: FloatType = 1.;
+    while price_at(high) < price {
+        high *= 2.;
+        if !high.is_finite() {
+            return FloatType::NAN;
+        }
+    }
+    let mut low = 0.;
+    for _ in 0..200 {
+        let mid = (low + high) / 2.;
+        if price_at(mid) < price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.
+}
+
+impl OptionTick {
+    /// Inputs of the Bachelier formulas: asset_price as the forward, the rate of the tick at its maturity, and its IV or premium.
+    pub fn bachelier_params(&self) -> BachelierParams {
+        let (sigma, price) = match self.option_value {
+            OptionValue::Price(p) => (FloatType::NAN, Some(p)),
+            OptionValue::ImpliedVolatility(iv) => (iv, None),
+        };
+        BachelierParams {
+            forward: self.asset_price,
+            strike: self.strike.to_f64().unwrap(),
+            tau: self.tau(),
+            rate: self.rate(),
+            sigma,
+            option_type: self.option_type.clone(),
+            price,
+        }
+    }
+}
+
+/// Undiscounted call prices of both models, with the forward as the spot of Black-Scholes.
+fn undiscounted_call(
+    forward: FloatType,
+    strike: FloatType,
+    tau: FloatType,
+) -> (BachelierParams, BsParams) {
+    let normal = BachelierParams {
+        forward,
+        strike,
+        tau,
+        rate: 0.,
+        sigma: FloatType::NAN,
+        option_type: OptionType::Call,
+        price: None,
+    };
+    let lognormal = BsParams {
+        spot: forward,
+        strike,
+        tau,
+        rate: 0.,
+        carry: 0.,
+        sigma: FloatType::NAN,
+        option_type: OptionType::Call,
+        price: None,
+    };
+    (normal, lognormal)
+}
+
+/// Black (lognormal) volatility giving the same price as the normal volatility, for a positive forward and strike.
+pub fn normal_to_lognormal_vol(
+    forward: FloatType,
+    strike: FloatType,
+    tau: FloatType,
+    normal_vol: FloatType,
+) -> Result<FloatType> {
+    ensure!(
+        forward > 0. && strike > 0.,
+        "The lognormal model needs a positive forward and strike"
+    );
+    ensure!(tau > 0., "The time to maturity must be positive");
+    let (normal, lognormal) = undiscounted_call(forward, strike, tau);
+    let price = BachelierParams {
+        sigma: normal_vol,
+        ..normal
+    }
+    .theoretical_price();
+    let vol = implied_vol(price, |sigma| {
+        BsParams {
+            sigma,
+            ..lognormal.clone()
+        }
+        .theoretical_price()
+    });
+    ensure!(
+        vol.is_finite(),
+        "No lognormal volatility matches the price of the normal volatility"
+    );
+    Ok(vol)
+}
+
+/// Normal volatility giving the same price as the Black (lognormal) volatility, for a positive forward and strike.
+pub fn lognormal_to_normal_vol(
+    forward: FloatType,
+    strike: FloatType,
+    tau: FloatType,
+    lognormal_vol: FloatType,
+) -> Result<FloatType> {
+    ensure!(
+        forward > 0. && strike > 0.,
+        "The lognormal model needs a positive forward and strike"
+    );
+    ensure!(tau > 0., "The time to maturity must be positive");
+    let (normal, lognormal) = undiscounted_call(forward, strike, tau);
+    let price = BsParams {
+        sigma: lognormal_vol,
+        ..lognormal
+    }
+    .theoretical_price();
+    let vol = implied_vol(price, |sigma| {
+        BachelierParams {
+            sigma,
+            ..normal.clone()
+        }
+        .theoretical_price()
+    });
+    ensure!(
+        vol.is_finite(),
+        "No normal volatility matches the price of the lognormal volatility"
+    );
+    Ok(vol)
+}
@@ -0,0 +1,249 @@
+//! Delta-hedged P&L of an option position over a series of snapshots.
+//!
+//! A DeltaHedger holds `quantity` of one contract (negative to sell it) and trades the underlying to offset its delta.
+//! It walks a TimeSeries of (OptionChain, spot): the contract is marked at each snapshot with the tick of the chain with the same strike
+//! and option type (or the previous tick moved to the new spot if it is not quoted), and the hedge is adjusted according to the RehedgeRule.
+//! Transaction costs are proportional to the traded notional of the underlying.
+//!
+//! The P&L of each step is broken down with the greeks of the previous snapshot:
+//! * delta: (q delta + hedge) dS, the exposure left unhedged
+//! * gamma: q gamma dS^2 / 2
+//! * theta: q theta dt
+//! * vega: q vega dIV
+//! * residual: what the expansion does not explain (higher order terms, rates)
+//!
+//! # Example
+//! ```ignore
+//! let hedger = DeltaHedger::builder()
+//!     .strike(dec!(27000))
+//!     .option_type(OptionType::Call)
+//!     .quantity(-10.)
+//!     .rule(RehedgeRule::Band(0.5))
+//!     .transaction_cost(0.0005)
+//!     .build();
+//! let report = hedger.run(&snapshots)?;
+//! println!("pnl: {:?}, gamma: {:?}", report.pnl(), report.gamma_pnl());
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::hedging::{BandMethod, BandParams};
+use crate::models::*;
+use crate::portfolio::{intrinsic_value, Position};
+use anyhow::{anyhow, ensure, Result};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RehedgeRule {
+    /// Hedge the full delta at every snapshot
+    EveryStep,
+    /// Hedge the full delta every n snapshots
+    Frequency(usize),
+    /// Hedge the full delta when the net delta exceeds this amount of the underlying in absolute value
+    Band(FloatType),
+    /// Trade back to the edge of the utility based no-transaction band of hedging.rs
+    UtilityBand { params: BandParams, method: BandMethod },
+}
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct DeltaHedger {
+    pub strike: DecimalType,
+    pub option_type: OptionType,
+    /// Number of options held, negative for a short position
+    pub quantity: FloatType,
+    #[builder(default = RehedgeRule::EveryStep)]
+    pub rule: RehedgeRule,
+    /// Proportional cost of trading the underlying, as a fraction of the traded notional
+    #[builder(default = 0.)]
+    pub transaction_cost: FloatType,
+}
+
+/// P&L of one step, from the previous snapshot to this one. The first step only records the initial hedge.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HedgeStep {
+    pub spot: FloatType,
+    /// Value of the option position
+    pub option_value: FloatType,
+    /// Units of the underlying held after rehedging
+    pub hedge: FloatType,
+    /// Units of the underlying traded at this snapshot
+    pub traded: FloatType,
+    pub option_pnl: FloatType,
+    pub hedge_pnl: FloatType,
+    pub cost: FloatType,
+    pub delta_pnl: FloatType,
+    pub gamma_pnl: FloatType,
+    pub theta_pnl: FloatType,
+    pub vega_pnl: FloatType,
+    pub residual_pnl: FloatType,
+    /// option_pnl + hedge_pnl - cost
+    pub total_pnl: FloatType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HedgeReport {
+    pub steps: TimeSeries<HedgeStep>,
+}
+
+impl HedgeReport {
+    /// Cumulative P&L
+    pub fn pnl(&self) -> TimeSeries<FloatType> {
+        let mut total = 0.;
+        TimeSeries(
+            self.steps
+                .0
+                .iter()
+                .map(|step| {
+                    total += step.total_pnl;
+                    total
+                })
+                .collect(),
+        )
+    }
+
+    pub fn step_pnl(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.total_pnl)
+    }
+
+    pub fn delta_pnl(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.delta_pnl)
+    }
+
+    pub fn gamma_pnl(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.gamma_pnl)
+    }
+
+    pub fn theta_pnl(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.theta_pnl)
+    }
+
+    pub fn vega_pnl(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.vega_pnl)
+    }
+
+    pub fn costs(&self) -> TimeSeries<FloatType> {
+        self.steps.map(|step| step.cost)
+    }
+}
+
+impl DeltaHedger {
+    /// Tick of the contract in the chain, valued with IV and the given spot
+    fn mark(&self, chain: &OptionChain<OptionTick>, spot: FloatType) -> Option<OptionTick> {
+        chain
+            .0
+            .iter()
+            .find(|t| t.strike == self.strike && t.option_type == self.option_type)
+            .map(|t| {
+                let mut tick = t.get_implied_volatility();
+                tick.asset_price = spot;
+                tick
+            })
+    }
+
+    /// Units of the underlying to hold after the snapshot `index`, given the current hedge.
+    fn rehedge(&self, index: usize, tick: &OptionTick, hedge: FloatType) -> FloatType {
+        let delta = self.quantity * tick.delta();
+        match &self.rule {
+            RehedgeRule::EveryStep => -delta,
+            RehedgeRule::Frequency(n) => {
+                if index.is_multiple_of((*n).max(1)) {
+                    -delta
+                } else {
+                    hedge
+                }
+            }
+            RehedgeRule::Band(width) => {
+                if (delta + hedge).abs() > *width {
+                    -delta
+                } else {
+                    hedge
+                }
+            }
+            RehedgeRule::UtilityBand { params, method } => {
+                // The band is expressed in terms of the hedged delta, which is minus the units held
+                let band = Position::new(tick.clone(), self.quantity).hedging_band(params, method);
+                match band.rebalance(-hedge) {
+                    Some(trade) => hedge - trade,
+                    None => hedge,
+                }
+            }
+        }
+    }
+
+    pub fn run(&self, snapshots: &TimeSeries<(OptionChain<OptionTick>, FloatType)>) -> Result<HedgeReport> {
+        let (first_chain, first_spot) = snapshots.0.first().ok_or_else(|| anyhow!("No snapshots"))?;
+        let mut tick = self
+            .mark(first_chain, *first_spot)
+            .ok_or_else(|| anyhow!("The contract is not quoted in the first snapshot"))?;
+        ensure!(tick.tau() > 0., "The option has expired");
+
+        let hedge = self.rehedge(0, &tick, 0.);
+        let mut previous = HedgeStep {
+            spot: *first_spot,
+            option_value: self.quantity * tick.get_theoretical_price().get_value(),
+            hedge,
+            traded: hedge,
+            cost: self.transaction_cost * hedge.abs() * first_spot,
+            ..Default::default()
+        };
+        previous.total_pnl = -previous.cost;
+        let mut steps = TimeSeries(vec![previous.clone()]);
+
+        for (index, (chain, spot)) in snapshots.0.iter().enumerate().skip(1) {
+            let marked = self.mark(chain, *spot).unwrap_or_else(|| {
+                let mut moved = tick.clone();
+                moved.asset_price = *spot;
+                moved.valuation_time = chain.0.first().and_then(|t| t.valuation_time).or(moved.valuation_time);
+                moved
+            });
+            let expired = marked.tau() <= 0.;
+            let option_value = self.quantity
+                * if expired {
+                    intrinsic_value(&marked, *spot)
+                } else {
+                    marked.get_theoretical_price().get_value()
+                };
+
+            let ds = spot - previous.spot;
+            let dt = tick.tau() - marked.tau();
+            let option_pnl = option_value - previous.option_value;
+            let hedge_pnl = previous.hedge * ds;
+            let delta_pnl = (self.quantity * tick.delta() + previous.hedge) * ds;
+            let gamma_pnl = 0.5 * self.quantity * tick.gamma() * ds * ds;
+            let theta_pnl = self.quantity * tick.theta() * dt;
+            let vega_pnl = if expired {
+                0.
+            } else {
+                self.quantity * tick.vega() * (marked.get_value() - tick.get_value())
+            };
+
+            let hedge = if expired { 0. } else { self.rehedge(index, &marked, previous.hedge) };
+            let traded = hedge - previous.hedge;
+            let cost = self.transaction_cost * traded.abs() * spot;
+            let total_pnl = option_pnl + hedge_pnl - cost;
+            let step = HedgeStep {
+                spot: *spot,
+                option_value,
+                hedge,
+                traded,
+                option_pnl,
+                hedge_pnl,
+                cost,
+                delta_pnl,
+                gamma_pnl,
+                theta_pnl,
+                vega_pnl,
+                residual_pnl: option_pnl + hedge_pnl - delta_pnl - gamma_pnl - theta_pnl - vega_pnl,
+                total_pnl,
+            };
+            steps.push(step.clone());
+            previous = step;
+            tick = marked;
+            if expired {
+                break;
+            }
+        }
+        Ok(HedgeReport { steps })
+    }
+}
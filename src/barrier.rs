@@ -0,0 +1,148 @@
+//! Closed-form prices of single barrier options (Merton, Reiner-Rubinstein).
+//!
+//! Continuously monitored European barrier options on the strike and option_type of a tick, following Haug,
+//! "The Complete Guide to Option Pricing Formulas", section 4.17.1. With cost of carry b = r - q, phi = 1 for calls and -1 for puts,
+//! and eta = 1 for down and -1 for up barriers, each of the eight cases is a combination of the terms A to F
+//! (vanilla, barrier-reflected and rebate terms). A knock-out rebate is paid when the barrier is hit.
+//!
+//! The greeks of a BarrierOption are obtained by bump-and-reprice (NumericalGreeks) on the closed-form price when its
+//! tick uses the analytic engine, and on the finite difference price otherwise.
+//!
+//! # Example
+//! ```ignore
+//! let barrier = BarrierSpec::builder().kind(BarrierKind::DownAndOut).level(90.).build();
+//! let price = tick.barrier_price(&barrier)?;
+//! let option = ExoticOption::Barrier(BarrierOption::new(tick, barrier));
+//! println!("{} {}", option.price(), option.numerical_delta());
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::math::norm_cdf;
+use crate::models::*;
+use crate::numerical_greeks::BarrierOption;
+use anyhow::{ensure, Result};
+use rust_decimal::prelude::*;
+
+impl OptionTick {
+    /// Closed-form price of the European option with a continuous barrier.
+    /// Fails if the barrier has already been hit, since the option is then either dead or a vanilla.
+    pub fn barrier_price(&self, barrier: &BarrierSpec) -> Result<FloatType> {
+        let tick = self.get_implied_volatility();
+        let s = tick.escrowed_spot();
+        let x = tick.strike.to_f64().unwrap();
+        let h = barrier.level;
+        let t = tick.tau();
+        let sigma = tick.get_value();
+        let r = tick.rate();
+        let b = r - tick.carry_yield();
+        ensure!(t > 0., "The option has expired");
+        ensure!(sigma.is_finite() && sigma > 0., "Implied volatility is not available");
+        let down = matches!(barrier.kind, BarrierKind::DownAndOut | BarrierKind::DownAndIn);
+        ensure!(if down { s > h } else { s < h }, "The barrier has already been hit");
+
+        let phi: FloatType = match tick.option_type {
+            OptionType::Call => 1.,
+            OptionType::Put => -1.,
+        };
+        let eta: FloatType = if down { 1. } else { -1. };
+        let vol = sigma * t.sqrt();
+        let mu = (b - sigma * sigma / 2.) / (sigma * sigma);
+        let lambda = (mu * mu + 2. * r / (sigma * sigma)).sqrt();
+        let carry = ((b - r) * t).exp();
+        let discount = (-r * t).exp();
+
+        let x1 = (s / x).ln() / vol + (1. + mu) * vol;
+        let x2 = (s / h).ln() / vol + (1. + mu) * vol;
+        let y1 = (h * h / (s * x)).ln() / vol + (1. + mu) * vol;
+        let y2 = (h / s).ln() / vol + (1. + mu) * vol;
+        let z = (h / s).ln() / vol + lambda * vol;
+        let hs = h / s;
+
+        let a = phi * s * carry * norm_cdf(phi * x1) - phi * x * discount * norm_cdf(phi * x1 - phi * vol);
+        let b_ = phi * s * carry * norm_cdf(phi * x2) - phi * x * discount * norm_cdf(phi * x2 - phi * vol);
+        let c = phi * s * carry * hs.powf(2. * (mu + 1.)) * norm_cdf(eta * y1)
+            - phi * x * discount * hs.powf(2. * mu) * norm_cdf(eta * y1 - eta * vol);
+        let d = phi * s * carry * hs.powf(2. * (mu + 1.)) * norm_cdf(eta * y2)
+            - phi * x * discount * hs.powf(2. * mu) * norm_cdf(eta * y2 - eta * vol);
+        // Knock-out rebate paid at the hit (term F of Haug); knock-in options have no rebate here
+        let f = barrier.rebate
+            * (hs.powf(mu + lambda) * norm_cdf(eta * z) + hs.powf(mu - lambda) * norm_cdf(eta * z - 2. * eta * lambda * vol));
+
+        let above = x > h;
+        let price = match (&barrier.kind, &tick.option_type, above) {
+            (BarrierKind::DownAndIn, OptionType::Call, true) => c,
+            (BarrierKind::DownAndIn, OptionType::Call, false) => a - b_ + d,
+            (BarrierKind::UpAndIn, OptionType::Call, true) => a,
+            (BarrierKind::UpAndIn, OptionType::Call, false) => b_ - c + d,
+            (BarrierKind::DownAndIn, OptionType::Put, true) => b_ - c + d,
+            (BarrierKind::DownAndIn, OptionType::Put, false) => a,
+            (BarrierKind::UpAndIn, OptionType::Put, true) => a - b_ + d,
+            (BarrierKind::UpAndIn, OptionType::Put, false) => c,
+            (BarrierKind::DownAndOut, OptionType::Call, true) => a - c + f,
+            (BarrierKind::DownAndOut, OptionType::Call, false) => b_ - d + f,
+            (BarrierKind::UpAndOut, OptionType::Call, true) => f,
+            (BarrierKind::UpAndOut, OptionType::Call, false) => a - b_ + c - d + f,
+            (BarrierKind::DownAndOut, OptionType::Put, true) => a - b_ + c - d + f,
+            (BarrierKind::DownAndOut, OptionType::Put, false) => f,
+            (BarrierKind::UpAndOut, OptionType::Put, true) => b_ - d + f,
+            (BarrierKind::UpAndOut, OptionType::Put, false) => a - c + f,
+        };
+        Ok(price)
+    }
+}
+
+impl BarrierOption {
+    /// Closed-form price for European options with the analytic engine, finite difference price otherwise.
+    pub fn price_with_engine(&self) -> Result<FloatType> {
+        match (&self.tick.pricing_engine, &self.tick.option_style) {
+            (PricingEngine::Analytic, OptionStyle::European) => self.tick.barrier_price(&self.barrier),
+            _ => Ok(self.tick.finite_difference(Some(&self.barrier))?.price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, option_type: OptionType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(365))
+            .option_type(option_type)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn in_out_parity() {
+        let pairs = [
+            (BarrierKind::DownAndIn, BarrierKind::DownAndOut, 90.),
+            (BarrierKind::UpAndIn, BarrierKind::UpAndOut, 110.),
+        ];
+        for strike in [dec!(80), dec!(100), dec!(120)] {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let tick = tick(strike, option_type);
+                for (knock_in, knock_out, level) in pairs.iter() {
+                    let price = |kind: &BarrierKind| {
+                        tick.barrier_price(&BarrierSpec::builder().kind(kind.clone()).level(*level).build())
+                            .unwrap()
+                    };
+                    assert_float_absolute_eq!(
+                        price(knock_in) + price(knock_out),
+                        tick.get_theoretical_price().get_value(),
+                        1e-10
+                    );
+                }
+            }
+        }
+    }
+}
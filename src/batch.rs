@@ -0,0 +1,237 @@
+//! Struct-of-arrays Black-Scholes pricing of many options sharing the spot, maturity and rates, e.g. the strikes of one expiry
+//! when building an IV surface.
+//!
+//! The strikes and IVs are passed as slices and priced 4 lanes at a time with the f64x4 vectors of the `wide` crate.
+//! The normal CDF is Hart's double precision approximation, whose absolute error is close to machine precision,
+//! so prices agree with BlackScholes::theoretical_price() to well within 1e-7 times the spot.
+//!
+//! # Example
+//! ```ignore
+//! let market = BatchMarket::from_tick(&chain.0[0]);
+//! let prices = price_batch(&market, OptionType::Call, &strikes, &ivs);
+//! ```
+
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use rust_decimal::prelude::*;
+use wide::{f64x4, CmpLt};
+
+/// Inputs shared by every option of a batch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchMarket {
+    pub spot: FloatType,
+    /// Time to maturity in years
+    pub tau: FloatType,
+    pub rate: FloatType,
+    /// Continuous carry yield q
+    pub carry: FloatType,
+}
+
+impl BatchMarket {
+    /// Market of the tick: its escrowed spot, tau, rate and carry yield, as used by BlackScholes.
+    pub fn from_tick(tick: &OptionTick) -> Self {
+        let params = tick.bs_params();
+        Self {
+            spot: params.spot,
+            tau: params.tau,
+            rate: params.rate,
+            carry: params.carry,
+        }
+    }
+}
+
+const LANES: usize = 4;
+
+fn sign(option_type: &OptionType) -> FloatType {
+    match option_type {
+        OptionType::Call => 1.,
+        OptionType::Put => -1.,
+    }
+}
+
+/// Hart's double precision algorithm 5666 in the form of West (2005): a rational function of |x| below 7.07 and a
+/// continued fraction of the tail above, blended lane by lane.
+fn norm_cdf(x: f64x4) -> f64x4 {
+    let z = x.abs();
+    let horner = |coefficients: &[FloatType]| {
+        coefficients
+            .iter()
+            .fold(f64x4::ZERO, |acc, c| acc * z + f64x4::splat(*c))
+    };
+    let density = (f64x4::splat(-0.5) * z * z).exp();
+    let rational = density
+        * horner(&[
+            3.52624965998911e-2,
+            0.700383064443688,
+            6.37396220353165,
+            33.912866078383,
+            112.079291497871,
+            221.213596169931,
+            220.206867912376,
+        ])
+        / horner(&[
+            8.83883476483184e-2,
+            1.75566716318264,
+            16.064177579207,
+            86.7807322029461,
+            296.564248779674,
+            637.333633378831,
+            793.826512519948,
+            440.413735824752,
+        ]);
+    let fraction = [3., 2., 1.]
+        .iter()
+        .fold(z + f64x4::splat(4.) / (z + f64x4::splat(0.65)), |acc, c| {
+            z + f64x4::splat(*c) / acc
+        });
+    let tail = density / fraction / f64x4::splat((2. * std::f64::consts::PI).sqrt());
+    let lower = z.cmp_lt(f64x4::splat(7.07106781186547)).blend(rational, tail);
+    x.cmp_lt(f64x4::ZERO).blend(lower, f64x4::ONE - lower)
+}
+
+/// w (S e^{-q tau} N(w d1) - K e^{-r tau} N(w d2)) with w = 1 for calls and -1 for puts
+fn price_lanes(market: &BatchMarket, w: f64x4, strike: f64x4, sigma: f64x4) -> f64x4 {
+    let spot = f64x4::splat(market.spot);
+    let tau = f64x4::splat(market.tau);
+    let sqrt_tau = f64x4::splat(market.tau.sqrt());
+    let drift = f64x4::splat(market.rate - market.carry);
+    let forward_discount = f64x4::splat(market.spot * (-market.carry * market.tau).exp());
+    let discount = f64x4::splat((-market.rate * market.tau).exp());
+
+    let vol = sigma * sqrt_tau;
+    let d1 = ((spot / strike).ln() + (drift + f64x4::splat(0.5) * sigma * sigma) * tau) / vol;
+    let d2 = d1 - vol;
+    w * (forward_discount * norm_cdf(w * d1) - strike * discount * norm_cdf(w * d2))
+}
+
+fn price_into(market: &BatchMarket, signs: &[FloatType], strikes: &[FloatType], ivs: &[FloatType], out: &mut [FloatType]) {
+    let n = strikes.len();
+    let full = n - n % LANES;
+    let load = |xs: &[FloatType], i: usize| f64x4::from([xs[i], xs[i + 1], xs[i + 2], xs[i + 3]]);
+    for i in (0..full).step_by(LANES) {
+        let prices = price_lanes(market, load(signs, i), load(strikes, i), load(ivs, i));
+        out[i..i + LANES].copy_from_slice(prices.as_array_ref());
+    }
+    if full < n {
+        // Pads the tail with the last option so that every lane has valid inputs
+        let pad = |xs: &[FloatType]| {
+            let mut lanes = [xs[n - 1]; LANES];
+            lanes[..n - full].copy_from_slice(&xs[full..]);
+            f64x4::from(lanes)
+        };
+        let prices = price_lanes(market, pad(signs), pad(strikes), pad(ivs));
+        out[full..].copy_from_slice(&prices.as_array_ref()[..n - full]);
+    }
+}
+
+/// Prices of options of the same type with the given strikes and IVs.
+pub fn price_batch(
+    market: &BatchMarket,
+    option_type: OptionType,
+    strikes: &[FloatType],
+    ivs: &[FloatType],
+) -> Result<Vec<FloatType>> {
+    ensure!(strikes.len() == ivs.len(), "strikes and ivs must have the same length");
+    let signs = vec![sign(&option_type); strikes.len()];
+    let mut prices = vec![0.; strikes.len()];
+    price_into(market, &signs, strikes, ivs, &mut prices);
+    Ok(prices)
+}
+
+/// Whether the ticks have the same inputs to BatchMarket::from_tick().
+fn same_market(a: &OptionTick, b: &OptionTick) -> bool {
+    a.underlying == b.underlying
+        && a.asset_price == b.asset_price
+        && a.maturity == b.maturity
+        && a.valuation_time == b.valuation_time
+        && a.day_count == b.day_count
+        && a.risk_free_rate == b.risk_free_rate
+        && a.dividend_yield == b.dividend_yield
+        && a.pricing_model == b.pricing_model
+        && a.discount_curve == b.discount_curve
+        && a.dividend_curve == b.dividend_curve
+        && a.dividends == b.dividends
+}
+
+impl OptionChain<OptionTick> {
+    /// Theoretical prices of the ticks in one batch, with the market of the first tick.
+    /// Every tick must be quoted in IV and share the market (spot, maturity, rate and carry) of the first one; a chain that
+    /// mixes expiries or underlyings is an error.
+    pub fn batch_theoretical_prices(&self) -> Result<Vec<FloatType>> {
+        let first = match self.0.first() {
+            Some(first) => first,
+            None => return Ok(Vec::new()),
+        };
+        let market = BatchMarket::from_tick(first);
+        let mut ivs = Vec::with_capacity(self.0.len());
+        for tick in self.0.iter() {
+            ensure!(
+                same_market(tick, first),
+                "Batch pricing requires every tick to share the spot, maturity, rate and carry of the first one"
+            );
+            match tick.option_value {
+                OptionValue::ImpliedVolatility(iv) => ivs.push(iv),
+                OptionValue::Price(_) => return Err(anyhow!("Batch pricing requires ticks quoted in IV")),
+            }
+        }
+        let strikes: Vec<FloatType> = self.0.iter().map(|t| t.strike.to_f64().unwrap()).collect();
+        let signs: Vec<FloatType> = self.0.iter().map(|t| sign(&t.option_type)).collect();
+        let mut prices = vec![0.; self.0.len()];
+        price_into(&market, &signs, &strikes, &ivs, &mut prices);
+        Ok(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::black_scholes::BlackScholes;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, option_type: OptionType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(365))
+            .option_type(option_type)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn matches_theoretical_price() {
+        let strikes = [70., 85., 95., 100., 105., 115., 130.];
+        let ivs = [0.3, 0.25, 0.21, 0.2, 0.19, 0.18, 0.2];
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let market = BatchMarket::from_tick(&tick(dec!(100), option_type.clone()));
+            let prices = price_batch(&market, option_type.clone(), &strikes, &ivs).unwrap();
+            for ((strike, iv), price) in strikes.iter().zip(ivs).zip(prices) {
+                let mut tick = tick(Decimal::from_f64(*strike).unwrap(), option_type.clone());
+                tick.option_value = OptionValue::ImpliedVolatility(iv);
+                assert_float_absolute_eq!(price, tick.get_theoretical_price().get_value(), 1e-7 * market.spot);
+            }
+        }
+    }
+
+    #[test]
+    fn normal_cdf() {
+        for x in [-30., -9., -7.5, -3., -0.5, 0., 0.7, 2., 8.] {
+            let lanes = norm_cdf(f64x4::splat(x));
+            assert_float_absolute_eq!(lanes.as_array_ref()[0], crate::math::norm_cdf(x), 1e-15);
+        }
+    }
+
+    #[test]
+    fn rejects_mixed_markets() {
+        let mut later = tick(dec!(100), OptionType::Call);
+        later.maturity += Duration::days(30);
+        let chain = OptionChain(vec![tick(dec!(100), OptionType::Call), later]);
+        assert!(chain.batch_theoretical_prices().is_err());
+    }
+}
@@ -9,7 +9,8 @@
 //! See BlackScholes trait page.
 
 use crate::models::*;
-use probability::prelude::*;
+use num_traits::Float;
+use crate::math::{norm_cdf, norm_pdf};
 use rust_decimal::prelude::*;
 
 #[cfg_attr(doc, katexit::katexit)]
@@ -21,26 +22,26 @@ use rust_decimal::prelude::*;
 /// * r: risk free rate
 /// * q: dividend yield
 /// * $\sigma$: implied volatility
-pub trait BlackScholes {
+pub trait BlackScholes<F: Float = FloatType> {
     /// Returns the d1
     /// # Formula
     /// $$
     /// d_{1}={\frac {\ln(S/K)+\left(r-q+{\frac {1}{2}}\sigma ^{2}\right)\tau }{\sigma {\sqrt {\tau }}}}
     /// $$
-    fn d1(&self) -> FloatType;
+    fn d1(&self) -> F;
     /// Returns the d1
     /// # Formula
     /// $$
     /// d_{2}={\frac {\ln(S/K)+\left(r-q-{\frac {1}{2}}\sigma ^{2}\right)\tau }{\sigma {\sqrt {\tau }}}}=d_{1}-\sigma {\sqrt {\tau }}
     /// $$
-    fn d2(&self) -> FloatType;
+    fn d2(&self) -> F;
 
     /// Returns the phi
     /// # Formula
     /// $$
     /// \phi(x) = \frac{1}{\sqrt{2\pi}}e^{-\frac{x^2}{2}}
     /// $$
-    fn phi(x: &FloatType) -> FloatType;
+    fn phi(x: &F) -> F;
 
     /// Returns the phi
     /// # Formula
@@ -48,7 +49,7 @@ pub trait BlackScholes {
     /// \Phi(x) =  \frac{1}{\sqrt{2\pi}}\int_{-\infty}^{x}e^{-\frac{t^2}{2}}dt
     /// $$
     #[allow(non_snake_case)]
-    fn Phi(x: &FloatType) -> FloatType;
+    fn Phi(x: &F) -> F;
 
     /// Returns a new OptionTick instance with the theoretical price calculated from the implied volatility using the Black-Scholes formula.
     /// # Formula
@@ -94,70 +95,168 @@ pub trait BlackScholes {
     /// # Notes
     ///
     /// * This function assumes that self has valid values for symbol, strike, expiry and option_type fields.
-    /// * This function uses Gaussian::new(0.0, 1.0) to create a standard normal distribution for calculating vega (the derivative of price with respect to volatility).
+    /// * This function uses the standard normal CDF (see the math module) for calculating vega (the derivative of price with respect to volatility).
     fn get_implied_volatility(&self) -> Self;
-    fn _difference(option: &Self, implied_volatility: FloatType) -> FloatType;
+    fn _difference(option: &Self, implied_volatility: F) -> F;
 }
 
-impl BlackScholes for OptionTick {
-    fn d1(&self) -> FloatType {
-        let tau = self.tau();
-        match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(implied_volatility) => {
-                ((self.asset_price / self.strike.to_f64().unwrap()).log(std::f64::consts::E)
-                 + (self.risk_free_rate - self.dividend_yield
-                    + 0.5 * implied_volatility * implied_volatility)
-                 * tau)
-                    / (implied_volatility * tau.sqrt())
+/// Inputs of the Black-Scholes formulas in the numeric type F, e.g. f32 for batch work or f64 (the default FloatType).
+/// OptionTick implements BlackScholes and EuropeanGreeks by converting itself into BsParams<FloatType> (see OptionTick::bs_params()).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BsParams<F: Float = FloatType> {
+    pub spot: F,
+    pub strike: F,
+    /// Time to maturity in years
+    pub tau: F,
+    pub rate: F,
+    /// Continuous carry yield q
+    pub carry: F,
+    /// Implied volatility, NaN if unknown
+    pub sigma: F,
+    pub option_type: OptionType,
+    /// Premium, None if unknown
+    pub price: Option<F>,
+}
+
+impl<F: Float> BsParams<F> {
+    /// e^{-q tau}
+    pub fn carry_discount_factor(&self) -> F {
+        (-self.carry * self.tau).exp()
+    }
+
+    /// e^{-r tau}
+    pub fn discount_factor(&self) -> F {
+        (-self.rate * self.tau).exp()
+    }
+
+    /// Black-Scholes price with the implied volatility of the params.
+    pub fn theoretical_price(&self) -> F {
+        let d1 = self.d1();
+        let d2 = self.d2();
+        match self.option_type {
+            OptionType::Call => {
+                self.carry_discount_factor() * self.spot * Self::Phi(&d1)
+                    - self.strike * self.discount_factor() * Self::Phi(&d2)
+            }
+            OptionType::Put => {
+                self.strike * self.discount_factor() * Self::Phi(&(-d2))
+                    - self.carry_discount_factor() * self.spot * Self::Phi(&(-d1))
             }
         }
     }
-    fn d2(&self) -> FloatType {
-        let tau = self.tau();
-        match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(implied_volatility) => {
-                ((self.asset_price / self.strike.to_f64().unwrap()).log(std::f64::consts::E)
-                 + (self.risk_free_rate
-                    - self.dividend_yield
-                    - 0.5 * implied_volatility * implied_volatility)
-                        * tau)
-                    / (implied_volatility * tau.sqrt())
-            }
+}
+
+impl<F: Float> BlackScholes<F> for BsParams<F> {
+    fn d1(&self) -> F {
+        let half = F::from(0.5).unwrap();
+        ((self.spot / self.strike).ln() + (self.rate - self.carry + half * self.sigma * self.sigma) * self.tau)
+            / (self.sigma * self.tau.sqrt())
+    }
+
+    fn d2(&self) -> F {
+        let half = F::from(0.5).unwrap();
+        ((self.spot / self.strike).ln() + (self.rate - self.carry - half * self.sigma * self.sigma) * self.tau)
+            / (self.sigma * self.tau.sqrt())
+    }
+
+    fn phi(x: &F) -> F {
+        F::from(norm_pdf(x.to_f64().unwrap())).unwrap()
+    }
+
+    fn Phi(x: &F) -> F {
+        F::from(norm_cdf(x.to_f64().unwrap())).unwrap()
+    }
+
+    fn get_theoretical_price(&self) -> Self {
+        Self {
+            price: Some(self.theoretical_price()),
+            ..self.clone()
         }
     }
 
+    fn get_implied_volatility(&self) -> Self {
+        let price = match self.price {
+            Some(price) => price,
+            None => return self.clone(),
+        };
+        let epsilon = F::from(0.0001).unwrap();
+        let max_iter = 5000;
+        let mut sigma = F::from(10.).unwrap();
+        let mut diff = Self::_difference(self, sigma);
+        let mut iter = 0;
+        while diff.abs() > epsilon && iter < max_iter {
+            let with_iv = Self {
+                sigma,
+                ..self.clone()
+            };
+            let vega = self.spot * self.tau.sqrt() * Self::Phi(&with_iv.d1());
+            sigma = sigma - diff / vega;
+            diff = Self::_difference(self, sigma);
+            iter += 1;
+        }
+        Self {
+            sigma,
+            price: Some(price),
+            ..self.clone()
+        }
+    }
+
+    fn _difference(option: &Self, implied_volatility: F) -> F {
+        let with_iv = Self {
+            sigma: implied_volatility,
+            ..option.clone()
+        };
+        // Theoretical price calculated from iv - Current premium
+        with_iv.theoretical_price() - option.price.unwrap_or_else(F::nan)
+    }
+}
+
+impl OptionTick {
+    /// Inputs of the Black-Scholes formulas: the escrowed spot, the rate and carry of the tick at its maturity, and its IV or premium.
+    pub fn bs_params(&self) -> BsParams {
+        let (sigma, price) = match self.option_value {
+            OptionValue::Price(p) => (FloatType::NAN, Some(p)),
+            OptionValue::ImpliedVolatility(iv) => (iv, None),
+        };
+        BsParams {
+            spot: self.escrowed_spot(),
+            strike: self.strike.to_f64().unwrap(),
+            tau: self.tau(),
+            rate: self.rate(),
+            carry: self.carry_yield(),
+            sigma,
+            option_type: self.option_type.clone(),
+            price,
+        }
+    }
+}
+
+impl BlackScholes for OptionTick {
+    fn d1(&self) -> FloatType {
+        self.bs_params().d1()
+    }
+
+    fn d2(&self) -> FloatType {
+        self.bs_params().d2()
+    }
+
     fn phi(x: &FloatType) -> FloatType {
-        (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+        <BsParams as BlackScholes>::phi(x)
     }
 
     fn Phi(x: &FloatType) -> FloatType {
-        let g = Gaussian::new(0.0, 1.0);
-        g.distribution(*x)
+        <BsParams as BlackScholes>::Phi(x)
     }
 
     fn get_theoretical_price(&self) -> Self {
         match self.option_value {
             OptionValue::Price(_) => self.clone(),
             OptionValue::ImpliedVolatility(_) => {
-                let d1 = self.d1();
-                let d2 = self.d2();
-                let tau = self.tau();
-
-                let price = match self.option_type {
-                    OptionType::Call => {
-                        (-self.dividend_yield * tau).exp() * self.asset_price * Self::Phi(&d1)
-                            - self.strike.to_f64().unwrap() * (-self.risk_free_rate * tau).exp() * Self::Phi(&d2)
-                    }
-                    OptionType::Put => {
-                        self.strike.to_f64().unwrap() * (-self.risk_free_rate * tau).exp() * Self::Phi(&(-d2))
-							- (-self.dividend_yield * tau).exp()
-                                * self.asset_price
-                                * Self::Phi(&(-d1))
-                    }
-                };
                 let mut new_option = self.clone();
+                let price = match self.pricing_model {
+                    PricingModel::Bachelier => self.bachelier_params().theoretical_price(),
+                    _ => self.bs_params().theoretical_price(),
+                };
                 new_option.option_value = OptionValue::Price(price);
                 new_option
             }
@@ -165,30 +264,14 @@ impl BlackScholes for OptionTick {
     }
 
     fn get_implied_volatility(&self) -> Self {
-        let sigma_est = 10.;
-        let epsilon = 0.0001;
-        let tau = self.tau();
-
         match self.option_value {
             OptionValue::Price(_) => {
                 let mut option = self.clone();
-                let mut sigma = sigma_est;
-                let mut diff = Self::_difference(&option, sigma);
-                let max_iter = 5000;
-                let mut iter = 0;
-
-                while diff.abs() > epsilon && iter < max_iter {
-                    let mut option_with_iv = option.clone();
-                    option_with_iv.option_value = OptionValue::ImpliedVolatility(sigma);
-                    let d1 = option_with_iv.d1();
-                    let g = Gaussian::new(0.0, 1.0);
-                    let vega = self.asset_price * tau.sqrt() * g.distribution(d1);
-                    sigma -= diff / vega;
-                    diff = Self::_difference(&option, sigma);
-                    iter += 1;
-                }
-                let new_sigma = sigma;
-                option.option_value = OptionValue::ImpliedVolatility(new_sigma);
+                let iv = match self.pricing_model {
+                    PricingModel::Bachelier => self.bachelier_params().implied_volatility(),
+                    _ => self.bs_params().get_implied_volatility().sigma,
+                };
+                option.option_value = OptionValue::ImpliedVolatility(iv);
                 option
             }
             OptionValue::ImpliedVolatility(_) => self.clone(),
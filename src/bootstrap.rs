@@ -0,0 +1,172 @@
+//! Bootstrapped confidence intervals for metrics derived from an option chain.
+//!
+//! Quotes on a StrikeBoard only tell us that the fair value lies somewhere between the best bid and the best ask.
+//! The bootstrap redraws every strike uniformly within its bid/ask, recomputes the metric on the resampled chain and reports the distribution of the results.
+//! This gives error bars for ATM IV, risk reversals, exposures, implied moments and any other metric that can be written as a function of `OptionChain<OptionTick>`.
+//!
+//! # Example
+//! ```ignore
+//! let config = BootstrapConfig::builder().n_samples(500).seed(42).build();
+//! let atm_iv = chain.bootstrap(&config, |c| Ok(c.atm().iv()))?;
+//! println!("{} [{}, {}]", atm_iv.estimate, atm_iv.lower, atm_iv.upper);
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use probability::prelude::*;
+use probability::source::Source;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct BootstrapConfig {
+    /// Number of resampled chains
+    #[builder(default = 1000)]
+    pub n_samples: usize,
+    /// Confidence level of the percentile interval
+    #[builder(default = 0.95)]
+    pub confidence: FloatType,
+    /// Seed of the random number generator, so that results are reproducible
+    #[builder(default = 0)]
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    /// Metric calculated on the mid prices
+    pub estimate: FloatType,
+    pub lower: FloatType,
+    pub upper: FloatType,
+    /// Standard deviation of the bootstrapped metric
+    pub std_error: FloatType,
+    pub confidence: FloatType,
+    /// Number of resamples for which the metric could be calculated
+    pub n_valid: usize,
+}
+
+impl OptionChain<StrikeBoard> {
+    /// Bootstraps the metric by drawing every strike uniformly between its best bid and best ask.
+    /// Strikes with only one side quoted are kept at that quote, and empty StrikeBoards are dropped.
+    /// Resamples for which the metric returns an error or a non-finite value are discarded.
+    pub fn bootstrap<F>(&self, config: &BootstrapConfig, metric: F) -> Result<ConfidenceInterval>
+    where
+        F: Fn(&OptionChain<OptionTick>) -> Result<FloatType>,
+    {
+        let quotes = quote_ranges(self);
+        ensure!(!quotes.is_empty(), "No quotes in the OptionChain");
+
+        let estimate = metric(&draw_chain(&quotes, || 0.5))?;
+        let mut source = source::default(config.seed);
+        let samples: Vec<FloatType> = (0..config.n_samples)
+            .filter_map(|_| metric(&draw_chain(&quotes, || source.read_f64())).ok())
+            .filter(|x| x.is_finite())
+            .collect();
+
+        confidence_interval(estimate, samples, config.confidence)
+    }
+}
+
+impl OptionBoard<StrikeBoard> {
+    /// Bootstraps a metric that depends on several expiries (e.g. term structure or VIX-style index).
+    /// See `OptionChain::<StrikeBoard>::bootstrap`.
+    pub fn bootstrap<F>(&self, config: &BootstrapConfig, metric: F) -> Result<ConfidenceInterval>
+    where
+        F: Fn(&OptionBoard<OptionTick>) -> Result<FloatType>,
+    {
+        let quotes: Vec<Vec<(OptionTick, FloatType, FloatType)>> =
+            self.0.iter().map(quote_ranges).collect();
+        ensure!(
+            quotes.iter().any(|q| !q.is_empty()),
+            "No quotes in the OptionBoard"
+        );
+
+        let draw_board = |u: &mut dyn FnMut() -> FloatType| {
+            OptionBoard(
+                quotes
+                    .iter()
+                    .filter(|q| !q.is_empty())
+                    .map(|q| draw_chain(q, &mut *u))
+                    .collect(),
+            )
+        };
+
+        let estimate = metric(&draw_board(&mut || 0.5))?;
+        let mut source = source::default(config.seed);
+        let samples: Vec<FloatType> = (0..config.n_samples)
+            .filter_map(|_| metric(&draw_board(&mut || source.read_f64())).ok())
+            .filter(|x| x.is_finite())
+            .collect();
+
+        confidence_interval(estimate, samples, config.confidence)
+    }
+}
+
+/// Returns (template tick, lower quote, upper quote) for every non-empty StrikeBoard.
+fn quote_ranges(chain: &OptionChain<StrikeBoard>) -> Vec<(OptionTick, FloatType, FloatType)> {
+    chain
+        .0
+        .iter()
+        .filter_map(|sb| match (sb.best_bid(), sb.best_ask()) {
+            (Ok(bid), Ok(ask)) => {
+                let (b, a) = (bid.get_value(), ask.get_value());
+                Some((bid, b.min(a), b.max(a)))
+            }
+            (Ok(tick), Err(_)) | (Err(_), Ok(tick)) => {
+                let v = tick.get_value();
+                Some((tick, v, v))
+            }
+            (Err(_), Err(_)) => None,
+        })
+        .collect()
+}
+
+/// Builds a chain where each quote is placed at `lower + u * (upper - lower)`.
+fn draw_chain(
+    quotes: &[(OptionTick, FloatType, FloatType)],
+    mut u: impl FnMut() -> FloatType,
+) -> OptionChain<OptionTick> {
+    OptionChain(
+        quotes
+            .iter()
+            .map(|(template, lower, upper)| {
+                let value = lower + u() * (upper - lower);
+                let mut tick = template.clone();
+                tick.option_value = match template.option_value {
+                    OptionValue::Price(_) => OptionValue::Price(value),
+                    OptionValue::ImpliedVolatility(_) => OptionValue::ImpliedVolatility(value),
+                };
+                tick.side = None;
+                tick
+            })
+            .collect(),
+    )
+}
+
+fn confidence_interval(
+    estimate: FloatType,
+    mut samples: Vec<FloatType>,
+    confidence: FloatType,
+) -> Result<ConfidenceInterval> {
+    ensure!(
+        samples.len() >= 2,
+        "The metric could not be calculated on enough resampled chains"
+    );
+    samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let n = samples.len();
+    let mean = samples.iter().sum::<FloatType>() / n as FloatType;
+    let std_error = (samples.iter().map(|x| (x - mean).powi(2)).sum::<FloatType>()
+        / (n - 1) as FloatType)
+        .sqrt();
+    let alpha = (1. - confidence) / 2.;
+    let quantile = |p: FloatType| samples[((p * (n - 1) as FloatType).round() as usize).min(n - 1)];
+
+    Ok(ConfidenceInterval {
+        estimate,
+        lower: quantile(alpha),
+        upper: quantile(1. - alpha),
+        std_error,
+        confidence,
+        n_valid: n,
+    })
+}
@@ -0,0 +1,153 @@
+//! In-memory publish/subscribe bus for analytics derived from a live OptionBoard.
+//!
+//! AnalyticsBus owns the board and applies the upserts and deletes of a feed.
+//! Computations subscribe with a TickFilter (expiry, strike range, option type) and are only re-run when a matching tick changes,
+//! so a dashboard updates the affected analytics instead of recomputing everything on every tick.
+//!
+//! # Example
+//! ```ignore
+//! let mut bus = AnalyticsBus::new(board);
+//! let front = TickFilter::builder().maturity(front_expiry).build();
+//! let atm_iv = bus.register_metric(front, |board| Ok(board.get_front_month().atm().iv()));
+//! bus.subscribe(TickFilter::default(), |_, tick| println!("updated {} {:?}", tick.strike, tick.option_type));
+//! for tick in feed {
+//!     bus.upsert(tick);
+//!     println!("ATM IV: {:?}", bus.metric(atm_iv));
+//! }
+//! ```
+
+use crate::models::*;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use typed_builder::TypedBuilder;
+
+pub type SubscriptionId = usize;
+
+type Callback<T> = Box<dyn FnMut(&OptionBoard<T>, &OptionTick)>;
+type Metric<T> = Box<dyn Fn(&OptionBoard<T>) -> Result<FloatType>>;
+
+/// Selects the ticks a subscription is interested in. Unset fields match everything.
+#[derive(Clone, Debug, Default, TypedBuilder)]
+pub struct TickFilter {
+    #[builder(default, setter(strip_option))]
+    pub maturity: Option<DateTime<Utc>>,
+    #[builder(default, setter(strip_option))]
+    pub min_strike: Option<DecimalType>,
+    #[builder(default, setter(strip_option))]
+    pub max_strike: Option<DecimalType>,
+    #[builder(default, setter(strip_option))]
+    pub option_type: Option<OptionType>,
+}
+
+impl TickFilter {
+    pub fn matches(&self, tick: &OptionTick) -> bool {
+        self.maturity.is_none_or(|m| m == tick.maturity)
+            && self.min_strike.is_none_or(|k| tick.strike >= k)
+            && self.max_strike.is_none_or(|k| tick.strike <= k)
+            && self.option_type.as_ref().is_none_or(|t| *t == tick.option_type)
+    }
+}
+
+enum Handler<T: OptionBase> {
+    Callback(Callback<T>),
+    Metric(Metric<T>, Option<FloatType>),
+}
+
+struct Subscription<T: OptionBase> {
+    filter: TickFilter,
+    handler: Handler<T>,
+}
+
+pub struct AnalyticsBus<T: OptionBase> {
+    board: OptionBoard<T>,
+    subscriptions: BTreeMap<SubscriptionId, Subscription<T>>,
+    next_id: SubscriptionId,
+}
+
+impl<T> AnalyticsBus<T>
+where
+    T: OptionBase,
+    OptionBoard<T>: CRUD,
+{
+    pub fn new(board: OptionBoard<T>) -> Self {
+        Self {
+            board,
+            subscriptions: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn board(&self) -> &OptionBoard<T> {
+        &self.board
+    }
+
+    fn add(&mut self, filter: TickFilter, handler: Handler<T>) -> SubscriptionId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscriptions.insert(id, Subscription { filter, handler });
+        id
+    }
+
+    /// Calls `callback` with the updated board and the tick after every matching upsert or delete.
+    pub fn subscribe(
+        &mut self,
+        filter: TickFilter,
+        callback: impl FnMut(&OptionBoard<T>, &OptionTick) + 'static,
+    ) -> SubscriptionId {
+        self.add(filter, Handler::Callback(Box::new(callback)))
+    }
+
+    /// Registers a metric computed now and recomputed after every matching upsert or delete. Its latest value is read with metric().
+    pub fn register_metric(
+        &mut self,
+        filter: TickFilter,
+        metric: impl Fn(&OptionBoard<T>) -> Result<FloatType> + 'static,
+    ) -> SubscriptionId {
+        let value = metric(&self.board).ok();
+        self.add(filter, Handler::Metric(Box::new(metric), value))
+    }
+
+    /// Latest value of a registered metric. None if it is unknown or its last computation failed.
+    pub fn metric(&self, id: SubscriptionId) -> Option<FloatType> {
+        match self.subscriptions.get(&id).map(|s| &s.handler) {
+            Some(Handler::Metric(_, value)) => *value,
+            _ => None,
+        }
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscriptions.remove(&id).is_some()
+    }
+
+    /// Upserts the tick into the board and notifies the matching subscriptions. Returns the number of subscriptions notified.
+    pub fn upsert(&mut self, tick: OptionTick) -> usize {
+        self.board.upsert(tick.clone());
+        self.notify(&tick)
+    }
+
+    /// Deletes the tick from the board and notifies the matching subscriptions. Returns the number of subscriptions notified,
+    /// which is 0 if the tick was not on the board.
+    pub fn delete(&mut self, tick: OptionTick) -> usize {
+        if self.board.delete(tick.clone()) == Mutation::Unchanged {
+            return 0;
+        }
+        self.notify(&tick)
+    }
+
+    fn notify(&mut self, tick: &OptionTick) -> usize {
+        let board = &self.board;
+        let mut notified = 0;
+        for subscription in self.subscriptions.values_mut() {
+            if !subscription.filter.matches(tick) {
+                continue;
+            }
+            match &mut subscription.handler {
+                Handler::Callback(callback) => callback(board, tick),
+                Handler::Metric(metric, value) => *value = metric(board).ok(),
+            }
+            notified += 1;
+        }
+        notified
+    }
+}
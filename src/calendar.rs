@@ -0,0 +1,209 @@
+//! Exchange calendars: trading sessions, weekends and holidays of an exchange.
+//!
+//! An ExchangeCalendar counts business days and the time spent inside trading sessions, so that the time to maturity
+//! can be measured in trading time with `OptionTick::tau_with(&DayCount::Exchange(calendar), now)`.
+//! This matters for short-dated options, where a weekend is a large part of the remaining life but carries little decay.
+//!
+//! Sessions are given in exchange local time. The offset to UTC is either fixed or follows the US daylight saving rule.
+//! Holidays close the whole local date. Apart from fixed annual closures such as the JPX year-end, they are not built in and are added with `with_holidays`.
+//!
+//! # Example
+//! ```ignore
+//! let cme = ExchangeCalendar::cme().with_holidays(holidays);
+//! let tau = tick.tau_with(&DayCount::Exchange(cme), now);
+//! ```
+
+use crate::models::FloatType;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum UtcOffsetRule {
+    /// Fixed offset from UTC in seconds
+    Fixed(i32),
+    /// US Central time: UTC-6, UTC-5 from the second Sunday of March to the first Sunday of November
+    UsCentral,
+}
+
+impl UtcOffsetRule {
+    /// Offset from UTC in seconds on the given local date.
+    pub fn offset_seconds(&self, date: NaiveDate) -> i32 {
+        match self {
+            UtcOffsetRule::Fixed(offset) => *offset,
+            UtcOffsetRule::UsCentral => {
+                let dst_start = nth_sunday(date.year(), 3, 2);
+                let dst_end = nth_sunday(date.year(), 11, 1);
+                if dst_start <= date && date < dst_end {
+                    -5 * 3600
+                } else {
+                    -6 * 3600
+                }
+            }
+        }
+    }
+}
+
+fn nth_sunday(year: i32, month: u32, n: u32) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Sun, n as u8).unwrap()
+}
+
+/// Trading session on a weekday, in seconds from local midnight. `end` may be 86400 for a session running until midnight.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TradingSession {
+    pub weekday: Weekday,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TradingSession {
+    pub fn new(weekday: Weekday, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            weekday,
+            start: start.num_seconds_from_midnight(),
+            end: match end.num_seconds_from_midnight() {
+                0 => 86400,
+                end => end,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeCalendar {
+    pub name: String,
+    pub utc_offset: UtcOffsetRule,
+    pub sessions: Vec<TradingSession>,
+    /// Weekdays that are not business days
+    pub weekend: Vec<Weekday>,
+    /// Local dates on which the exchange is closed
+    pub holidays: Vec<NaiveDate>,
+    /// (month, day) on which the exchange is closed every year
+    pub annual_holidays: Vec<(u32, u32)>,
+    /// Number of business days in a year, used to convert trading time into a year fraction
+    pub trading_days_per_year: FloatType,
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+const WEEKDAYS: [Weekday; 5] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+
+impl ExchangeCalendar {
+    /// Osaka Exchange day session (8:45-15:45 JST) on weekdays, closed from December 31 to January 3.
+    pub fn jpx() -> Self {
+        Self {
+            name: "JPX".to_string(),
+            utc_offset: UtcOffsetRule::Fixed(9 * 3600),
+            sessions: WEEKDAYS.iter().map(|d| TradingSession::new(*d, hm(8, 45), hm(15, 45))).collect(),
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: Vec::new(),
+            annual_holidays: vec![(12, 31), (1, 1), (1, 2), (1, 3)],
+            trading_days_per_year: 245.,
+        }
+    }
+
+    /// CME Globex: Sunday 17:00 to Friday 16:00 Central time, with a daily halt from 16:00 to 17:00.
+    pub fn cme() -> Self {
+        let mut sessions = vec![TradingSession::new(Weekday::Sun, hm(17, 0), hm(0, 0))];
+        for day in WEEKDAYS.iter() {
+            sessions.push(TradingSession::new(*day, hm(0, 0), hm(16, 0)));
+            if *day != Weekday::Fri {
+                sessions.push(TradingSession::new(*day, hm(17, 0), hm(0, 0)));
+            }
+        }
+        Self {
+            name: "CME".to_string(),
+            utc_offset: UtcOffsetRule::UsCentral,
+            sessions,
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: Vec::new(),
+            annual_holidays: Vec::new(),
+            trading_days_per_year: 252.,
+        }
+    }
+
+    /// Deribit: open around the clock every day.
+    pub fn deribit() -> Self {
+        Self {
+            name: "Deribit".to_string(),
+            utc_offset: UtcOffsetRule::Fixed(0),
+            sessions: WEEKDAYS
+                .iter()
+                .chain([Weekday::Sat, Weekday::Sun].iter())
+                .map(|d| TradingSession::new(*d, hm(0, 0), hm(0, 0)))
+                .collect(),
+            weekend: Vec::new(),
+            holidays: Vec::new(),
+            annual_holidays: Vec::new(),
+            trading_days_per_year: 365.,
+        }
+    }
+
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date) || self.annual_holidays.contains(&(date.month(), date.day()))
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.is_holiday(date)
+    }
+
+    /// Number of business days in [start, end).
+    pub fn business_days_between(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+        start
+            .iter_days()
+            .take_while(|d| *d < end)
+            .filter(|d| self.is_business_day(*d))
+            .count() as i64
+    }
+
+    /// Whether `time` is inside a trading session.
+    pub fn is_open(&self, time: DateTime<Utc>) -> bool {
+        self.trading_seconds(time, time + Duration::seconds(1)) > 0.
+    }
+
+    /// Seconds between start and end that fall inside trading sessions.
+    pub fn trading_seconds(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> FloatType {
+        if end <= start {
+            return 0.;
+        }
+        let mut total = 0.;
+        // Local dates are covered with one day of margin on each side for the UTC offset
+        let mut date = start.date_naive().pred_opt().unwrap();
+        while date <= end.date_naive().succ_opt().unwrap() {
+            if !self.is_holiday(date) {
+                let midnight = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                    - Duration::seconds(self.utc_offset.offset_seconds(date) as i64);
+                for session in self.sessions.iter().filter(|s| s.weekday == date.weekday()) {
+                    let from = (midnight + Duration::seconds(session.start as i64)).max(start);
+                    let to = (midnight + Duration::seconds(session.end as i64)).min(end);
+                    if to > from {
+                        total += (to - from).num_seconds() as FloatType;
+                    }
+                }
+            }
+            date = date.succ_opt().unwrap();
+        }
+        total
+    }
+
+    /// Average trading seconds of a business day, from the weekly sessions.
+    pub fn seconds_per_trading_day(&self) -> FloatType {
+        let weekly: u32 = self.sessions.iter().map(|s| s.end.saturating_sub(s.start)).sum();
+        let business_days = 7 - self.weekend.len();
+        weekly as FloatType / business_days.max(1) as FloatType
+    }
+
+    /// Year fraction between start and end in trading time (negative if end is before start).
+    pub fn year_fraction(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> FloatType {
+        if end < start {
+            return -self.year_fraction(end, start);
+        }
+        self.trading_seconds(start, end) / (self.seconds_per_trading_day() * self.trading_days_per_year)
+    }
+}
@@ -0,0 +1,151 @@
+//! Parameter history of model calibrations run over a TimeSeries of snapshots.
+//!
+//! CalibrationHistory keeps one record per snapshot (fitted parameters, fit error, or the reason the fit failed),
+//! so that the stability of a calibration can be monitored and the results persisted as JSON or exported as CSV.
+//! Each calibration is warm-started from the last successful parameters.
+//!
+//! # Example
+//! ```ignore
+//! let history = boards.heston_history(&HestonParams::default(), &HestonCalibrationConfig::builder().build());
+//! history.save_json("heston.json")?;
+//! let rho = history.parameter(|p| p.rho);
+//! println!("{}", history.to_csv(&[("v0", |p| p.v0), ("rho", |p| p.rho)]));
+//! ```
+
+use crate::heston::*;
+use crate::models::*;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Column name and accessor of a parameter for CSV export
+pub type ParameterColumn<'a, P> = (&'a str, fn(&P) -> FloatType);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalibrationRecord<P> {
+    /// Index of the snapshot in the calibrated TimeSeries
+    pub index: usize,
+    /// None if the calibration failed
+    pub params: Option<P>,
+    /// Fit error reported by the calibration (NaN if it failed)
+    pub rmse: FloatType,
+    pub error: Option<String>,
+}
+
+impl<P> CalibrationRecord<P> {
+    pub fn from_result(index: usize, result: Result<(P, FloatType)>) -> Self {
+        match result {
+            Ok((params, rmse)) => Self {
+                index,
+                params: Some(params),
+                rmse,
+                error: None,
+            },
+            Err(e) => Self {
+                index,
+                params: None,
+                rmse: FloatType::NAN,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalibrationHistory<P>(pub TimeSeries<CalibrationRecord<P>>);
+
+impl<P: Clone + Serialize + DeserializeOwned> CalibrationHistory<P> {
+    /// Runs `calibrate` on every snapshot. It receives the snapshot and the last successful parameters, and returns the parameters and the fit error.
+    pub fn run<T>(
+        snapshots: &TimeSeries<T>,
+        mut calibrate: impl FnMut(&T, Option<&P>) -> Result<(P, FloatType)>,
+    ) -> Self {
+        let mut last: Option<P> = None;
+        let mut records = TimeSeries::default();
+        for (index, snapshot) in snapshots.0.iter().enumerate() {
+            let record = CalibrationRecord::from_result(index, calibrate(snapshot, last.as_ref()));
+            if record.params.is_some() {
+                last = record.params.clone();
+            }
+            records.push(record);
+        }
+        Self(records)
+    }
+
+    pub fn params(&self) -> TimeSeries<Option<P>> {
+        self.0.map(|r| r.params.clone())
+    }
+
+    pub fn rmse(&self) -> TimeSeries<FloatType> {
+        self.0.map(|r| r.rmse)
+    }
+
+    /// Evolution of one parameter. Failed snapshots are NaN.
+    pub fn parameter(&self, f: impl Fn(&P) -> FloatType) -> TimeSeries<FloatType> {
+        self.0.map(|r| r.params.as_ref().map_or(FloatType::NAN, &f))
+    }
+
+    /// Fraction of snapshots for which the calibration succeeded.
+    pub fn success_rate(&self) -> FloatType {
+        let n = self.0 .0.len();
+        if n == 0 {
+            return FloatType::NAN;
+        }
+        self.0 .0.iter().filter(|r| r.params.is_some()).count() as FloatType / n as FloatType
+    }
+
+    /// CSV with a header row, one row per snapshot and the columns index, rmse and the given parameters.
+    pub fn to_csv(&self, columns: &[ParameterColumn<P>]) -> String {
+        let mut csv = String::from("index,rmse");
+        for (name, _) in columns.iter() {
+            csv.push(',');
+            csv.push_str(name);
+        }
+        csv.push('\n');
+        for record in self.0 .0.iter() {
+            csv.push_str(&format!("{},{}", record.index, record.rmse));
+            for (_, f) in columns.iter() {
+                let value = record.params.as_ref().map_or(FloatType::NAN, f);
+                csv.push_str(&format!(",{}", value));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        self.to_versioned_json()
+    }
+
+    /// Reads JSON written by to_json(), including histories written before schema versioning.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::from_versioned_json(json)
+    }
+
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+impl<P: Serialize + DeserializeOwned> Versioned for CalibrationHistory<P> {}
+
+impl TimeSeries<OptionBoard<OptionTick>> {
+    /// Calibrates the Heston model on every board, warm-starting each fit from the previous parameters.
+    pub fn heston_history(
+        &self,
+        initial: &HestonParams,
+        config: &HestonCalibrationConfig,
+    ) -> CalibrationHistory<HestonParams> {
+        CalibrationHistory::run(self, |board, last| {
+            let fit = board.calibrate_heston(last.unwrap_or(initial), config)?;
+            Ok((fit.params, fit.rmse))
+        })
+    }
+}
@@ -0,0 +1,268 @@
+//! Implied forward and cost of carry extracted from option prices.
+//!
+//! Put-call parity gives the forward price implied by each option chain: `F = K + e^(r tau) (C - P)`.
+//!
+//! For index futures options (PricingModel::Black76), comparing the implied forwards of successive expiries gives the implied carry (repo) between futures expiries: `carry(t1, t2) = ln(F2 / F1) / (tau2 - tau1)`.
+//!
+//! The implied forward curve can then be checked against futures prices quoted by the user.
+//!
+//! Box spreads (a bull call spread plus a bear put spread between the same strikes) pay the distance between the strikes
+//! whatever the underlying does, so their prices give the financing rate implied by the board: `r = -ln(box / (K2 - K1)) / tau`.
+
+use crate::black_scholes::BlackScholes;
+use crate::discount_curve::ZeroCurve;
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Implied carry between two consecutive expiries of an OptionBoard.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CarrySegment {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub start_forward: FloatType,
+    pub end_forward: FloatType,
+    /// Annualized continuously compounded carry between start and end.
+    pub carry: FloatType,
+}
+
+/// Long the call and short the put at lower_strike, short the call and long the put at upper_strike.
+/// It pays upper_strike - lower_strike at expiry whatever the underlying does, so its price discounts that amount.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoxSpread {
+    pub lower_strike: FloatType,
+    pub upper_strike: FloatType,
+    pub price: FloatType,
+    /// Continuously compounded rate -ln(price / (upper_strike - lower_strike)) / tau
+    pub rate: FloatType,
+}
+
+/// Result of comparing a quoted futures price with the forward implied by the options.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FuturesConsistency {
+    pub maturity: DateTime<Utc>,
+    pub quoted: FloatType,
+    pub implied: FloatType,
+    /// implied - quoted
+    pub basis: FloatType,
+    /// (implied - quoted) / quoted
+    pub relative_basis: FloatType,
+    pub consistent: bool,
+}
+
+impl OptionChain<OptionTick> {
+    /// Returns the forward price implied by put-call parity.
+    /// The strike where the difference between call and put premium is smallest is used, since it is the most liquid and least sensitive to the rate assumption.
+    /// The premium difference is compounded with the rate() of the ticks, i.e. their discount curve if set.
+    pub fn implied_forward(&self) -> Result<FloatType> {
+        ensure!(!self.0.is_empty(), "OptionChain is empty");
+        let pairs = self.parity_pairs();
+
+        let rate = self.0[0].rate();
+        let tau = self.0[0].tau();
+        ensure!(tau > 0., "OptionChain has already expired");
+
+        pairs
+            .iter()
+            .map(|(strike, call, put)| (*strike, call - put))
+            .min_by(|a, b| a.1.abs().partial_cmp(&b.1.abs()).unwrap())
+            .map(|(strike, diff)| strike + (rate * tau).exp() * diff)
+            .ok_or_else(|| anyhow!("No strike has both a call and a put in the OptionChain"))
+    }
+
+    /// (strike, call premium, put premium) of every strike with both a call and a put, in ascending order of strike.
+    fn parity_pairs(&self) -> Vec<(FloatType, FloatType, FloatType)> {
+        let mut pairs: BTreeMap<DecimalType, (Option<FloatType>, Option<FloatType>)> =
+            BTreeMap::new();
+        for tick in self.0.iter() {
+            let premium = tick.get_theoretical_price().get_value();
+            if !premium.is_finite() {
+                continue;
+            }
+            let entry = pairs.entry(tick.strike).or_insert((None, None));
+            match tick.option_type {
+                OptionType::Call => entry.0 = Some(premium),
+                OptionType::Put => entry.1 = Some(premium),
+            }
+        }
+        pairs
+            .into_iter()
+            .filter_map(|(strike, pair)| match pair {
+                (Some(call), Some(put)) => Some((strike.to_f64().unwrap(), call, put)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Box spreads between consecutive strikes with both a call and a put.
+    pub fn box_spreads(&self) -> Result<Vec<BoxSpread>> {
+        ensure!(!self.0.is_empty(), "OptionChain is empty");
+        let tau = self.0[0].tau();
+        ensure!(tau > 0., "OptionChain has already expired");
+        Ok(self
+            .parity_pairs()
+            .windows(2)
+            .map(|w| {
+                let (lower_strike, lower_call, lower_put) = w[0];
+                let (upper_strike, upper_call, upper_put) = w[1];
+                let price = lower_call - upper_call + upper_put - lower_put;
+                BoxSpread {
+                    lower_strike,
+                    upper_strike,
+                    price,
+                    rate: -(price / (upper_strike - lower_strike)).ln() / tau,
+                }
+            })
+            .collect())
+    }
+
+    /// Financing rate implied by the box spreads of the chain, so that risk_free_rate can be read from the board.
+    /// By put-call parity C - P = e^{-r tau} (F - K), so the slope of C - P against the strike is -e^{-r tau}; it is fitted by
+    /// least squares over every strike with both a call and a put, which weights the boxes by their width.
+    /// The premiums should be market prices (OptionValue::Price); premiums computed from IVs only echo risk_free_rate.
+    pub fn implied_rate_from_boxes(&self) -> Result<FloatType> {
+        ensure!(!self.0.is_empty(), "OptionChain is empty");
+        let tau = self.0[0].tau();
+        ensure!(tau > 0., "OptionChain has already expired");
+        let pairs = self.parity_pairs();
+        ensure!(pairs.len() >= 2, "At least two strikes with both a call and a put are required");
+
+        let n = pairs.len() as FloatType;
+        let mean_strike = pairs.iter().map(|(k, _, _)| k).sum::<FloatType>() / n;
+        let mean_diff = pairs.iter().map(|(_, c, p)| c - p).sum::<FloatType>() / n;
+        let (covariance, variance) = pairs.iter().fold((0., 0.), |(cov, var), (k, c, p)| {
+            let dk = k - mean_strike;
+            (cov + dk * (c - p - mean_diff), var + dk * dk)
+        });
+        let discount_factor = -covariance / variance;
+        ensure!(
+            discount_factor > 0.,
+            "The box spreads imply a non-positive discount factor"
+        );
+        Ok(-discount_factor.ln() / tau)
+    }
+
+    /// Returns the annualized cost of carry ln(F / S) / tau implied by the options.
+    /// For spot underlyings this is the implied r - q (repo-adjusted financing); for Black-76 chains it measures how far the options price away from the quoted futures.
+    pub fn implied_carry(&self) -> Result<FloatType> {
+        let forward = self.implied_forward()?;
+        let tau = self.0[0].tau();
+        Ok((forward / self.asset_price()?).ln() / tau)
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Zero curve bootstrapped from the box spreads of every chain (see OptionChain::implied_rate_from_boxes()).
+    /// Chains whose rate cannot be implied are skipped.
+    pub fn implied_zero_curve(&self) -> Result<ZeroCurve> {
+        let mut pillars: Vec<(FloatType, FloatType)> = self
+            .0
+            .iter()
+            .filter(|chain| !chain.0.is_empty())
+            .filter_map(|chain| Some((chain.0[0].tau(), chain.implied_rate_from_boxes().ok()?)))
+            .collect();
+        ensure!(!pillars.is_empty(), "No chain of the OptionBoard implies a rate");
+        pillars.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        pillars.dedup_by(|a, b| a.0 == b.0);
+        ZeroCurve::new(
+            pillars.iter().map(|(tau, _)| *tau).collect(),
+            pillars.iter().map(|(_, rate)| *rate).collect(),
+        )
+    }
+
+    /// Returns (maturity, implied forward) for every chain, sorted by maturity.
+    /// Chains whose forward cannot be implied are skipped.
+    pub fn implied_forward_curve(&self) -> Vec<(DateTime<Utc>, FloatType)> {
+        self.forwards_with_day_count()
+            .into_iter()
+            .map(|(maturity, forward, _)| (maturity, forward))
+            .collect()
+    }
+
+    /// implied_forward_curve() with the day count of the ticks of each chain.
+    fn forwards_with_day_count(&self) -> Vec<(DateTime<Utc>, FloatType, DayCount)> {
+        self.sort_by_maturity()
+            .0
+            .iter()
+            .filter_map(|chain| {
+                Some((chain.maturity().ok()?, chain.implied_forward().ok()?, chain.0.first()?.day_count.clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the implied carry between each pair of consecutive expiries, annualized with the day count of the later expiry.
+    pub fn implied_carry_curve(&self) -> Result<Vec<CarrySegment>> {
+        let curve = self.forwards_with_day_count();
+        ensure!(
+            curve.len() >= 2,
+            "At least two expiries with an implied forward are required"
+        );
+
+        Ok(curve
+            .windows(2)
+            .map(|w| {
+                let (start, start_forward, _) = w[0];
+                let (end, end_forward, ref day_count) = w[1];
+                let dt = day_count.year_fraction(start, end);
+                CarrySegment {
+                    start,
+                    end,
+                    start_forward,
+                    end_forward,
+                    carry: (end_forward / start_forward).ln() / dt,
+                }
+            })
+            .collect())
+    }
+
+    /// Compares quoted futures prices with the implied forward curve.
+    /// The implied forward at each futures maturity is interpolated log-linearly between the bracketing option expiries; futures outside the range of the option expiries are skipped.
+    /// A futures price is consistent when the relative basis is within `tolerance`.
+    pub fn check_futures_consistency(
+        &self,
+        futures: &[(DateTime<Utc>, FloatType)],
+        tolerance: FloatType,
+    ) -> Result<Vec<FuturesConsistency>> {
+        let curve = self.implied_forward_curve();
+        ensure!(!curve.is_empty(), "No implied forward is available on the OptionBoard");
+
+        let mut result = Vec::new();
+        for (maturity, quoted) in futures.iter() {
+            let implied = match interpolate_forward(&curve, maturity) {
+                Some(f) => f,
+                None => continue,
+            };
+            let basis = implied - quoted;
+            let relative_basis = basis / quoted;
+            result.push(FuturesConsistency {
+                maturity: *maturity,
+                quoted: *quoted,
+                implied,
+                basis,
+                relative_basis,
+                consistent: relative_basis.abs() <= tolerance,
+            });
+        }
+        Ok(result)
+    }
+}
+
+fn interpolate_forward(
+    curve: &[(DateTime<Utc>, FloatType)],
+    maturity: &DateTime<Utc>,
+) -> Option<FloatType> {
+    if let Some((_, f)) = curve.iter().find(|(m, _)| m == maturity) {
+        return Some(*f);
+    }
+    let upper = curve.iter().position(|(m, _)| m > maturity)?;
+    if upper == 0 {
+        return None;
+    }
+    let (m1, f1) = curve[upper - 1];
+    let (m2, f2) = curve[upper];
+    let w = (*maturity - m1).num_seconds() as FloatType / (m2 - m1).num_seconds() as FloatType;
+    Some((f1.ln() + w * (f2.ln() - f1.ln())).exp())
+}
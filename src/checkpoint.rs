@@ -0,0 +1,132 @@
+//! Checkpoint and restore for long-running pipelines over a TimeSeries.
+//!
+//! CheckpointedRun processes the snapshots in order and periodically writes a JSON checkpoint holding the progress cursor,
+//! the base seed and the partial results. When started again with the same file, it resumes from the cursor instead of from zero.
+//!
+//! Randomness is made resumable by deriving an independent seed for each snapshot from the base seed (`step_seed`),
+//! so a resumed run draws the same numbers as an uninterrupted one.
+//!
+//! # Example
+//! ```ignore
+//! let run = CheckpointedRun::new("heston.checkpoint.json", 10);
+//! let history = CalibrationHistory::run_checkpointed(&boards, &run, |board, last| {
+//!     let fit = board.calibrate_heston(last.unwrap_or(&initial), &config)?;
+//!     Ok((fit.params, fit.rmse))
+//! })?;
+//! ```
+
+use crate::calibration::*;
+use crate::models::*;
+use anyhow::{ensure, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint<R> {
+    /// Number of snapshots already processed
+    pub cursor: usize,
+    /// Number of snapshots of the whole run, used to check that a checkpoint belongs to the same input
+    pub total: usize,
+    pub seed: u64,
+    pub results: Vec<R>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckpointedRun {
+    pub path: PathBuf,
+    /// A checkpoint is written every `every` snapshots and at the end of the run.
+    pub every: usize,
+}
+
+/// Seed for the snapshot at `index`, derived from the base seed with SplitMix64.
+pub fn step_seed(seed: u64, index: usize) -> u64 {
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+impl<R: Serialize + DeserializeOwned> Versioned for Checkpoint<R> {}
+
+impl CheckpointedRun {
+    pub fn new(path: impl Into<PathBuf>, every: usize) -> Self {
+        Self {
+            path: path.into(),
+            every: every.max(1),
+        }
+    }
+
+    /// Loads the checkpoint if the file exists.
+    pub fn load<R: Serialize + DeserializeOwned>(&self) -> Result<Option<Checkpoint<R>>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Checkpoint::load_versioned(&self.path)?))
+    }
+
+    /// Writes the checkpoint through a temporary file, so an interruption while writing leaves the previous checkpoint intact.
+    pub fn save<R: Serialize + DeserializeOwned>(&self, checkpoint: &Checkpoint<R>) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(&checkpoint.to_versioned_value()?)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Applies `f` to every snapshot, resuming from the checkpoint if there is one.
+    /// `f` receives the index, the snapshot, the seed of the step and the results so far.
+    pub fn run<T, R>(
+        &self,
+        snapshots: &TimeSeries<T>,
+        seed: u64,
+        mut f: impl FnMut(usize, &T, u64, &[R]) -> R,
+    ) -> Result<TimeSeries<R>>
+    where
+        R: Serialize + DeserializeOwned,
+    {
+        let total = snapshots.0.len();
+        let mut checkpoint = match self.load::<R>()? {
+            Some(checkpoint) => {
+                ensure!(
+                    checkpoint.total == total && checkpoint.seed == seed,
+                    "The checkpoint at {:?} belongs to a different run",
+                    self.path
+                );
+                ensure!(checkpoint.cursor == checkpoint.results.len(), "The checkpoint is corrupted");
+                checkpoint
+            }
+            None => Checkpoint {
+                cursor: 0,
+                total,
+                seed,
+                results: Vec::new(),
+            },
+        };
+
+        for index in checkpoint.cursor..total {
+            let result = f(index, &snapshots.0[index], step_seed(seed, index), &checkpoint.results);
+            checkpoint.results.push(result);
+            checkpoint.cursor = index + 1;
+            if checkpoint.cursor % self.every == 0 || checkpoint.cursor == total {
+                self.save(&checkpoint)?;
+            }
+        }
+        Ok(TimeSeries(checkpoint.results))
+    }
+}
+
+impl<P: Clone + Serialize + DeserializeOwned> CalibrationHistory<P> {
+    /// Same as run(), but resumable through a checkpoint file.
+    pub fn run_checkpointed<T>(
+        snapshots: &TimeSeries<T>,
+        run: &CheckpointedRun,
+        mut calibrate: impl FnMut(&T, Option<&P>) -> Result<(P, FloatType)>,
+    ) -> Result<Self> {
+        let records = run.run(snapshots, 0, |index, snapshot, _, done: &[CalibrationRecord<P>]| {
+            let last = done.iter().rev().find_map(|r| r.params.as_ref());
+            CalibrationRecord::from_result(index, calibrate(snapshot, last))
+        })?;
+        Ok(Self(records))
+    }
+}
@@ -0,0 +1,205 @@
+//! Data quality filters applied to raw vendor quotes before analytics.
+//!
+//! Quotes with no time value make the IV solver fail, so a single bad quote turns into a NaN in smile_curve and the greeks.
+//! A ChainCleaner removes such quotes according to the filters that are set:
+//! * min_volume / min_open_interest: ticks whose AdditionalOptionData is below the minimum (missing data counts as 0)
+//! * max_quote_age: ticks whose valuation_time is older than `now - max_quote_age` (ticks without a valuation_time are kept)
+//! * remove_below_intrinsic: prices at or below the no-arbitrage lower bound, and non-positive or non-finite IVs
+//! * max_spread: strikes whose top of book spread exceeds this fraction of the mid (one-sided strikes are kept)
+//! * remove_crossed: strikes whose best bid is above the best ask
+//!
+//! The tick filters are applied first; the book filters then look at the remaining quotes of each StrikeBoard.
+//!
+//! A chain assembled from several snapshots can hold the same contract more than once, and analytics that sum over the
+//! ticks (open interest, exposures) then count it twice. OptionChain::dedupe() keeps one tick per contract and side, and
+//! normalize_strikes() snaps strikes parsed from floats (e.g. 27999.999999) to the strike grid so that they match.
+//!
+//! # Example
+//! ```ignore
+//! let cleaner = ChainCleaner::builder()
+//!     .min_open_interest(10.)
+//!     .max_spread(0.2)
+//!     .max_quote_age(Duration::minutes(5))
+//!     .build();
+//! let board = cleaner.clean_board(&raw_board, Utc::now());
+//! let chain = raw_chain.normalize_strikes(dec!(0.5))?.dedupe();
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rejection {
+    Volume,
+    OpenInterest,
+    Stale,
+    BelowIntrinsic,
+    Spread,
+    Crossed,
+}
+
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct ChainCleaner {
+    #[builder(setter(strip_option))]
+    pub min_volume: Option<FloatType>,
+    #[builder(setter(strip_option))]
+    pub min_open_interest: Option<FloatType>,
+    /// Maximum (ask - bid) / mid of the top of book
+    #[builder(setter(strip_option))]
+    pub max_spread: Option<FloatType>,
+    #[builder(setter(strip_option))]
+    pub max_quote_age: Option<Duration>,
+    pub remove_below_intrinsic: bool,
+    pub remove_crossed: bool,
+}
+
+/// Lower bound of the price of the option: the intrinsic value on the spot for American options,
+/// max(S e^{-q tau} - K e^{-r tau}, 0) (or the put equivalent) on the escrowed spot for European options.
+fn price_lower_bound(tick: &OptionTick) -> FloatType {
+    let strike = tick.strike.to_f64().unwrap();
+    let (spot, strike) = match tick.option_style {
+        OptionStyle::American => (tick.asset_price, strike),
+        OptionStyle::European => (
+            tick.escrowed_spot() * tick.carry_discount_factor(),
+            strike * tick.discount_factor(),
+        ),
+    };
+    match tick.option_type {
+        OptionType::Call => (spot - strike).max(0.),
+        OptionType::Put => (strike - spot).max(0.),
+    }
+}
+
+impl ChainCleaner {
+    /// The first tick filter the tick fails, or None if it passes all of them.
+    pub fn rejection(&self, tick: &OptionTick, now: DateTime<Utc>) -> Option<Rejection> {
+        let data = tick.additional_data.as_ref();
+        let volume = data.and_then(|d| d.volume).unwrap_or(0.);
+        let open_interest = data.and_then(|d| d.open_interest).unwrap_or(0.);
+        if self.min_volume.is_some_and(|min| volume < min) {
+            return Some(Rejection::Volume);
+        }
+        if self.min_open_interest.is_some_and(|min| open_interest < min) {
+            return Some(Rejection::OpenInterest);
+        }
+        if let (Some(age), Some(time)) = (self.max_quote_age, tick.valuation_time) {
+            if time < now - age {
+                return Some(Rejection::Stale);
+            }
+        }
+        if self.remove_below_intrinsic {
+            let valid = match tick.option_value {
+                OptionValue::Price(p) => p.is_finite() && p > price_lower_bound(tick),
+                OptionValue::ImpliedVolatility(v) => v.is_finite() && v > 0.,
+            };
+            if !valid {
+                return Some(Rejection::BelowIntrinsic);
+            }
+        }
+        None
+    }
+
+    /// The book filter the quotes of the strike fail, or None if they pass.
+    pub fn book_rejection(&self, board: &StrikeBoard) -> Option<Rejection> {
+        let ladder = board.ladder();
+        let (bid, ask) = match (ladder.bids.first(), ladder.asks.first()) {
+            (Some(bid), Some(ask)) => (bid.price, ask.price),
+            _ => return None,
+        };
+        if self.remove_crossed && bid > ask {
+            return Some(Rejection::Crossed);
+        }
+        if let Some(max_spread) = self.max_spread {
+            let mid = (bid + ask) / 2.;
+            if mid <= 0. || (ask - bid) / mid > max_spread {
+                return Some(Rejection::Spread);
+            }
+        }
+        None
+    }
+
+    pub fn clean_ticks(&self, chain: &OptionChain<OptionTick>, now: DateTime<Utc>) -> OptionChain<OptionTick> {
+        OptionChain(
+            chain
+                .0
+                .iter()
+                .filter(|t| self.rejection(t, now).is_none())
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The quotes of the strike that pass the tick filters, or None if none do or the remaining book fails a book filter.
+    pub fn clean_strike_board(&self, board: &StrikeBoard, now: DateTime<Utc>) -> Option<StrikeBoard> {
+        let board = StrikeBoard(
+            board
+                .0
+                .iter()
+                .filter(|t| self.rejection(t, now).is_none())
+                .cloned()
+                .collect(),
+        );
+        (!board.0.is_empty() && self.book_rejection(&board).is_none()).then_some(board)
+    }
+
+    pub fn clean_chain(&self, chain: &OptionChain<StrikeBoard>, now: DateTime<Utc>) -> OptionChain<StrikeBoard> {
+        OptionChain(
+            chain
+                .0
+                .iter()
+                .filter_map(|board| self.clean_strike_board(board, now))
+                .collect(),
+        )
+    }
+
+    /// Cleans every chain of the board, dropping the chains left empty.
+    pub fn clean_board(&self, board: &OptionBoard<StrikeBoard>, now: DateTime<Utc>) -> OptionBoard<StrikeBoard> {
+        OptionBoard(
+            board
+                .0
+                .iter()
+                .map(|chain| self.clean_chain(chain, now))
+                .filter(|chain| !chain.0.is_empty())
+                .collect(),
+        )
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Removes the ticks with the same strike, option type and side as another one, keeping the latest by valuation_time
+    /// (the last one in the chain among equal times). The remaining ticks keep their order.
+    pub fn dedupe(&self) -> Self {
+        let is_latest = |i: usize, tick: &OptionTick| {
+            !self.0.iter().enumerate().any(|(j, other)| {
+                j != i
+                    && other.strike == tick.strike
+                    && other.option_type == tick.option_type
+                    && other.side == tick.side
+                    && (other.valuation_time, j) > (tick.valuation_time, i)
+            })
+        };
+        OptionChain(
+            self.0
+                .iter()
+                .enumerate()
+                .filter(|(i, tick)| is_latest(*i, tick))
+                .map(|(_, tick)| tick.clone())
+                .collect(),
+        )
+    }
+
+    /// Rounds every strike to the nearest multiple of tick_size.
+    pub fn normalize_strikes(&self, tick_size: DecimalType) -> Result<Self> {
+        ensure!(tick_size > DecimalType::ZERO, "The tick size must be positive");
+        Ok(self.map(|tick| {
+            let mut tick = tick.clone();
+            tick.strike = ((tick.strike / tick_size).round() * tick_size).normalize();
+            tick
+        }))
+    }
+}
@@ -0,0 +1,226 @@
+//! Construction of option structures that meet a target greek profile.
+//!
+//! Given the quotes of an OptionChain<StrikeBoard> and targets on delta, gamma, vega and theta,
+//! `construct` searches integer contract quantities with a greedy local search:
+//! starting from an empty structure, it repeatedly applies the single-leg quantity change that reduces the objective the most,
+//! with step sizes halving from `max_contracts` down to one contract.
+//!
+//! The objective is the sum of squared target violations, each normalized by the largest greek of a single contract on the chain,
+//! plus `cost_weight` times the half-spread paid. Buying requires an ask and selling requires a bid.
+//!
+//! # Example
+//! ```ignore
+//! let profile = TargetProfile::builder()
+//!     .vega(Target::Exactly(10000.))
+//!     .delta(Target::Between(-50., 50.))
+//!     .gamma(Target::AtMost(0.))
+//!     .build();
+//! let structure = chain.construct(&profile)?;
+//! for (tick, quantity) in structure.legs.iter() {
+//!     println!("{:?} {} x {}", tick.option_type, tick.strike, quantity);
+//! }
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use crate::portfolio::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Target {
+    Free,
+    Exactly(FloatType),
+    AtLeast(FloatType),
+    AtMost(FloatType),
+    Between(FloatType, FloatType),
+}
+
+impl Target {
+    /// Amount by which `value` misses the target (0 when it is met).
+    fn violation(&self, value: FloatType) -> FloatType {
+        match *self {
+            Target::Free => 0.,
+            Target::Exactly(target) => value - target,
+            Target::AtLeast(lower) => (lower - value).max(0.),
+            Target::AtMost(upper) => (value - upper).max(0.),
+            Target::Between(lower, upper) => (lower - value).max(0.) + (value - upper).max(0.),
+        }
+    }
+}
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TargetProfile {
+    #[builder(default = Target::Free)]
+    pub delta: Target,
+    #[builder(default = Target::Free)]
+    pub gamma: Target,
+    #[builder(default = Target::Free)]
+    pub vega: Target,
+    #[builder(default = Target::Free)]
+    pub theta: Target,
+    /// Maximum absolute number of contracts per leg
+    #[builder(default = 100)]
+    pub max_contracts: i64,
+    /// Maximum number of legs in the structure
+    #[builder(default = 4)]
+    pub max_legs: usize,
+    /// Weight of the half-spread cost in the objective
+    #[builder(default = 0.)]
+    pub cost_weight: FloatType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SuggestedStructure {
+    /// Mid ticks valued by implied volatility and the signed number of contracts
+    pub legs: Vec<(OptionTick, i64)>,
+    pub delta: FloatType,
+    pub gamma: FloatType,
+    pub vega: FloatType,
+    pub theta: FloatType,
+    /// Half-spread paid to enter the structure
+    pub cost: FloatType,
+    /// True if inequality targets hold and exact targets are met within half of the largest single-contract greek
+    pub satisfied: bool,
+}
+
+impl SuggestedStructure {
+    pub fn to_portfolio(&self) -> Portfolio {
+        let mut portfolio = Portfolio::new();
+        for (tick, quantity) in self.legs.iter() {
+            portfolio.add(tick.clone(), *quantity as FloatType);
+        }
+        portfolio
+    }
+}
+
+struct Candidate {
+    tick: OptionTick,
+    greeks: [FloatType; 4],
+    can_buy: bool,
+    can_sell: bool,
+    half_spread: FloatType,
+}
+
+impl OptionChain<StrikeBoard> {
+    /// Suggests integer quantities of the options on the chain that meet the target profile.
+    pub fn construct(&self, profile: &TargetProfile) -> Result<SuggestedStructure> {
+        ensure!(profile.max_contracts > 0 && profile.max_legs > 0, "max_contracts and max_legs must be positive");
+        let candidates: Vec<Candidate> = self
+            .0
+            .iter()
+            .filter_map(|board| {
+                let tick = board.mid().ok()?.get_implied_volatility();
+                let greeks = [tick.delta(), tick.gamma(), tick.vega(), tick.theta()];
+                if !greeks.iter().all(|g| g.is_finite()) {
+                    return None;
+                }
+                let bid = board.best_bid().ok();
+                let ask = board.best_ask().ok();
+                let half_spread = match (&bid, &ask) {
+                    (Some(b), Some(a)) => 0.5 * (a.get_value() - b.get_value()).max(0.),
+                    _ => 0.,
+                };
+                Some(Candidate {
+                    tick,
+                    greeks,
+                    can_buy: ask.is_some(),
+                    can_sell: bid.is_some(),
+                    half_spread,
+                })
+            })
+            .collect();
+        ensure!(!candidates.is_empty(), "No priceable options on the chain");
+
+        let targets = [&profile.delta, &profile.gamma, &profile.vega, &profile.theta];
+        let units: Vec<FloatType> = (0..4)
+            .map(|g| {
+                let unit = candidates.iter().map(|c| c.greeks[g].abs()).fold(0., FloatType::max);
+                if unit > 0. {
+                    unit
+                } else {
+                    1.
+                }
+            })
+            .collect();
+
+        let totals = |quantities: &[i64]| -> [FloatType; 4] {
+            let mut sum = [0.; 4];
+            for (c, q) in candidates.iter().zip(quantities.iter()) {
+                for (g, s) in sum.iter_mut().enumerate() {
+                    *s += c.greeks[g] * *q as FloatType;
+                }
+            }
+            sum
+        };
+        let cost = |quantities: &[i64]| -> FloatType {
+            candidates
+                .iter()
+                .zip(quantities.iter())
+                .map(|(c, q)| c.half_spread * q.abs() as FloatType)
+                .sum()
+        };
+        let objective = |quantities: &[i64]| -> FloatType {
+            let sum = totals(quantities);
+            (0..4)
+                .map(|g| (targets[g].violation(sum[g]) / units[g]).powi(2))
+                .sum::<FloatType>()
+                + profile.cost_weight * cost(quantities)
+        };
+        let feasible = |quantities: &[i64], i: usize| -> bool {
+            let q = quantities[i];
+            q.abs() <= profile.max_contracts
+                && (q <= 0 || candidates[i].can_buy)
+                && (q >= 0 || candidates[i].can_sell)
+                && quantities.iter().filter(|q| **q != 0).count() <= profile.max_legs
+        };
+
+        let mut quantities = vec![0i64; candidates.len()];
+        let mut best = objective(&quantities);
+        let mut step = 1i64 << (63 - profile.max_contracts.leading_zeros());
+        while step >= 1 {
+            let mut best_move: Option<(usize, i64, FloatType)> = None;
+            for i in 0..candidates.len() {
+                for delta in [step, -step] {
+                    quantities[i] += delta;
+                    if feasible(&quantities, i) {
+                        let value = objective(&quantities);
+                        if value < best - 1e-12 && best_move.is_none_or(|(_, _, v)| value < v) {
+                            best_move = Some((i, delta, value));
+                        }
+                    }
+                    quantities[i] -= delta;
+                }
+            }
+            match best_move {
+                Some((i, delta, value)) => {
+                    quantities[i] += delta;
+                    best = value;
+                }
+                None => step /= 2,
+            }
+        }
+
+        let sum = totals(&quantities);
+        let satisfied = (0..4).all(|g| match targets[g] {
+            Target::Exactly(_) => targets[g].violation(sum[g]).abs() <= 0.5 * units[g],
+            _ => targets[g].violation(sum[g]) <= 0.,
+        });
+        Ok(SuggestedStructure {
+            legs: candidates
+                .iter()
+                .zip(quantities.iter())
+                .filter(|(_, q)| **q != 0)
+                .map(|(c, q)| (c.tick.clone(), *q))
+                .collect(),
+            delta: sum[0],
+            gamma: sum[1],
+            vega: sum[2],
+            theta: sum[3],
+            cost: cost(&quantities),
+            satisfied,
+        })
+    }
+}
@@ -0,0 +1,223 @@
+//! A shared configuration for a whole workflow: valuation time, day count (including exchange calendars), rates, discount curve, dividends and greek scaling.
+//!
+//! Ticks carry their own risk_free_rate, dividend_yield and conventions, with per-tick defaults such as risk_free_rate = 0.001.
+//! An AnalyticsContext overrides them consistently: `with_context` applies it to a tick, chain or board,
+//! after which pricing, greeks, tau and exposures all see the same inputs.
+//! The greeks returned by the context are additionally scaled according to greek_scaling.
+//!
+//! A MarketContext holds only the market data (valuation time, calendar, rate curve, dividend curve and discrete dividends),
+//! so that a snapshot of the market can be built once and attached to every chain and board of the snapshot.
+//! Its fields take precedence over the fields of the ticks when set; the others are left as they are.
+//! Both contexts implement TickContext, which is what with_context takes.
+//!
+//! # Example
+//! ```ignore
+//! let market = MarketContext::builder()
+//!     .valuation_time(snapshot_time)
+//!     .rate_curve(ZeroCurve::new(vec![0.25, 1.], vec![0.052, 0.048])?)
+//!     .dividend_curve(ZeroCurve::flat(0.013))
+//!     .build();
+//! let board = board.with_context(&market);
+//!
+//! let context = AnalyticsContext::builder()
+//!     .valuation_time(snapshot_time)
+//!     .day_count(DayCount::Exchange(ExchangeCalendar::cme()))
+//!     .risk_free_rate(0.05)
+//!     .greek_scaling(GreekScaling::Market)
+//!     .build();
+//! let board = board.with_context(&context);
+//! let vega_per_point = context.greek(Greek::Vega, &tick);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::discount_curve::ZeroCurve;
+use crate::dividend::DividendSchedule;
+use crate::greeks::Greek;
+use crate::models::*;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// Units in which the context reports greeks.
+/// * Raw: derivatives with respect to the volatility, time in years and rates as fractions
+/// * Market: per 1 vol point, per calendar day and per 1% of rate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GreekScaling {
+    #[default]
+    Raw,
+    Market,
+}
+
+impl GreekScaling {
+    /// Factor the raw greek is multiplied by.
+    pub fn factor(&self, greek: Greek) -> FloatType {
+        if *self == GreekScaling::Raw {
+            return 1.;
+        }
+        let (vol, time, rate) = match greek {
+            Greek::Delta
+            | Greek::Gamma
+            | Greek::Speed
+            | Greek::DualDelta
+            | Greek::DualGamma
+            | Greek::Lambda => (0, 0, 0),
+            Greek::Theta | Greek::Charm | Greek::Color => (0, 1, 0),
+            Greek::Rho | Greek::Epsilon => (0, 0, 1),
+            Greek::Vega | Greek::Vanna | Greek::Zomma => (1, 0, 0),
+            Greek::Vomma => (2, 0, 0),
+            Greek::Ultima => (3, 0, 0),
+            Greek::Veta | Greek::DvegaDtime => (1, 1, 0),
+        };
+        0.01_f64.powi(vol) * (1. / 365.0_f64).powi(time) * 0.01_f64.powi(rate)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, TypedBuilder, Serialize, Deserialize)]
+#[builder(field_defaults(default))]
+pub struct AnalyticsContext {
+    /// If set, every tick is valued at this time instead of now
+    #[builder(setter(strip_option))]
+    pub valuation_time: Option<DateTime<Utc>>,
+    /// If set, overrides the day count of every tick
+    #[builder(setter(strip_option))]
+    pub day_count: Option<DayCount>,
+    /// If set, overrides the risk free rate of every tick
+    #[builder(setter(strip_option))]
+    pub risk_free_rate: Option<FloatType>,
+    /// If set, overrides the discount curve of every tick
+    #[builder(setter(strip_option))]
+    pub discount_curve: Option<ZeroCurve>,
+    /// If set, overrides the dividend yield of every tick
+    #[builder(setter(strip_option))]
+    pub dividend_yield: Option<FloatType>,
+    /// If set, overrides the discrete dividends of every tick
+    #[builder(setter(strip_option))]
+    pub dividends: Option<DividendSchedule>,
+    /// If set, overrides the dividend curve of every tick
+    #[builder(setter(strip_option))]
+    pub dividend_curve: Option<ZeroCurve>,
+    pub greek_scaling: GreekScaling,
+}
+
+/// Market data shared by the ticks of a snapshot, used in preference to the fields of the ticks.
+#[derive(Clone, Debug, Default, PartialEq, TypedBuilder, Serialize, Deserialize)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct MarketContext {
+    /// Time at which the snapshot is valued
+    pub valuation_time: Option<DateTime<Utc>>,
+    /// Day count, e.g. DayCount::Exchange with the calendar of the exchange
+    pub day_count: Option<DayCount>,
+    /// Zero curve used for discounting
+    pub rate_curve: Option<ZeroCurve>,
+    /// Zero curve of the continuous dividend yield of a spot underlying
+    pub dividend_curve: Option<ZeroCurve>,
+    pub dividends: Option<DividendSchedule>,
+}
+
+/// Settings applied to ticks by with_context.
+pub trait TickContext {
+    /// Returns the tick with the settings of the context applied.
+    fn apply(&self, tick: &OptionTick) -> OptionTick;
+}
+
+impl TickContext for MarketContext {
+    fn apply(&self, tick: &OptionTick) -> OptionTick {
+        let mut tick = tick.clone();
+        if self.valuation_time.is_some() {
+            tick.valuation_time = self.valuation_time;
+        }
+        if let Some(day_count) = &self.day_count {
+            tick.day_count = day_count.clone();
+        }
+        if self.rate_curve.is_some() {
+            tick.discount_curve = self.rate_curve.clone();
+        }
+        if self.dividend_curve.is_some() {
+            tick.dividend_curve = self.dividend_curve.clone();
+        }
+        if self.dividends.is_some() {
+            tick.dividends = self.dividends.clone();
+        }
+        tick
+    }
+}
+
+impl TickContext for AnalyticsContext {
+    fn apply(&self, tick: &OptionTick) -> OptionTick {
+        AnalyticsContext::apply(self, tick)
+    }
+}
+
+impl AnalyticsContext {
+    /// Returns the tick with the settings of the context applied.
+    pub fn apply(&self, tick: &OptionTick) -> OptionTick {
+        let mut tick = tick.clone();
+        if self.valuation_time.is_some() {
+            tick.valuation_time = self.valuation_time;
+        }
+        if let Some(r) = self.risk_free_rate {
+            tick.risk_free_rate = r;
+        }
+        if self.discount_curve.is_some() {
+            tick.discount_curve = self.discount_curve.clone();
+        }
+        if let Some(q) = self.dividend_yield {
+            tick.dividend_yield = q;
+        }
+        if self.dividend_curve.is_some() {
+            tick.dividend_curve = self.dividend_curve.clone();
+        }
+        if self.dividends.is_some() {
+            tick.dividends = self.dividends.clone();
+        }
+        if let Some(day_count) = &self.day_count {
+            tick.day_count = day_count.clone();
+        }
+        tick
+    }
+
+    pub fn tau(&self, tick: &OptionTick) -> FloatType {
+        self.apply(tick).tau()
+    }
+
+    /// Theoretical price of the tick under the context.
+    pub fn price(&self, tick: &OptionTick) -> FloatType {
+        self.apply(tick).get_implied_volatility().get_theoretical_price().get_value()
+    }
+
+    /// Greek of the tick under the context, in the units of greek_scaling.
+    pub fn greek(&self, greek: Greek, tick: &OptionTick) -> FloatType {
+        greek.of(&self.apply(tick).get_implied_volatility()) * self.greek_scaling.factor(greek)
+    }
+
+    /// Greek exposure of the chain under the context, in the units of greek_scaling.
+    pub fn exposure(&self, greek: Greek, chain: &OptionChain<OptionTick>) -> Result<FloatType> {
+        let profile = chain.with_context(self).exposure_profile(greek)?;
+        Ok(profile.iter().map(|(_, e)| e).sum::<FloatType>() * self.greek_scaling.factor(greek))
+    }
+}
+
+impl OptionTick {
+    pub fn with_context(&self, context: &impl TickContext) -> Self {
+        context.apply(self)
+    }
+}
+
+impl OptionChain<OptionTick> {
+    pub fn with_context(&self, context: &impl TickContext) -> Self {
+        self.map(|tick| context.apply(tick))
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    pub fn with_context(&self, context: &impl TickContext) -> Self {
+        OptionBoard(self.0.iter().map(|chain| chain.with_context(context)).collect())
+    }
+}
+
+impl TimeSeries<OptionBoard<OptionTick>> {
+    pub fn with_context(&self, context: &impl TickContext) -> Self {
+        self.map(|board| board.with_context(context))
+    }
+}
@@ -0,0 +1,102 @@
+//! Contract specifications and greeks in cash terms.
+//!
+//! The greeks of an OptionTick are per unit of the underlying. A ContractSpec set on the tick gives the contract multiplier
+//! (units of the underlying per contract), the currency and the tick size, and the cash greeks convert the greeks into amounts of that currency per contract:
+//! * cash_delta: delta * S * multiplier, the exposure equivalent to holding the underlying
+//! * cash_gamma_1pct: gamma * S^2 * 0.01 * multiplier, the change of cash delta for a 1% move of the underlying
+//! * cash_vega_1vol: vega * 0.01 * multiplier, the change of value for a 1 vol point move of the IV
+//!
+//! Ticks without a ContractSpec use a multiplier of 1.
+//!
+//! # Example
+//! ```ignore
+//! let spec = ContractSpec::builder().multiplier(1000.).currency("JPY".to_string()).tick_size(5.).build();
+//! let tick = tick.with_contract_spec(spec);
+//! println!("cash delta: {} {}", tick.cash_delta(), tick.currency().unwrap_or("-"));
+//! println!("portfolio cash vega: {}", portfolio.cash_vega_1vol());
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use crate::portfolio::Portfolio;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, PartialEq, TypedBuilder, Serialize, Deserialize)]
+pub struct ContractSpec {
+    /// Units of the underlying delivered per contract
+    #[builder(default = 1.)]
+    pub multiplier: FloatType,
+    /// Currency in which the option is quoted and settled, e.g. "USD"
+    #[builder(default)]
+    pub currency: String,
+    /// Minimum price increment of the option premium. 0 if prices are continuous.
+    #[builder(default = 0.)]
+    pub tick_size: FloatType,
+}
+
+impl ContractSpec {
+    /// Rounds a premium to the nearest tick.
+    pub fn round_to_tick(&self, price: FloatType) -> FloatType {
+        if self.tick_size <= 0. {
+            return price;
+        }
+        (price / self.tick_size).round() * self.tick_size
+    }
+}
+
+impl OptionTick {
+    pub fn with_contract_spec(&self, spec: ContractSpec) -> Self {
+        Self {
+            contract_spec: Some(spec),
+            ..self.clone()
+        }
+    }
+
+    /// Contract multiplier, 1 if no ContractSpec is set.
+    pub fn multiplier(&self) -> FloatType {
+        self.contract_spec.as_ref().map_or(1., |spec| spec.multiplier)
+    }
+
+    pub fn currency(&self) -> Option<&str> {
+        self.contract_spec.as_ref().map(|spec| spec.currency.as_str())
+    }
+
+    /// Value of one contract in currency
+    pub fn cash_value(&self) -> FloatType {
+        self.get_theoretical_price().get_value() * self.multiplier()
+    }
+
+    /// Delta of one contract in currency, i.e. the notional of the underlying with the same exposure.
+    pub fn cash_delta(&self) -> FloatType {
+        self.get_implied_volatility().delta() * self.asset_price * self.multiplier()
+    }
+
+    /// Change of the cash delta of one contract for a 1% move of the underlying.
+    pub fn cash_gamma_1pct(&self) -> FloatType {
+        self.get_implied_volatility().gamma() * self.asset_price * self.asset_price * 0.01 * self.multiplier()
+    }
+
+    /// Change of the value of one contract for a 1 vol point move of the IV.
+    pub fn cash_vega_1vol(&self) -> FloatType {
+        self.get_implied_volatility().vega() * 0.01 * self.multiplier()
+    }
+}
+
+/// Cash greeks of the portfolio, the quantity-weighted sums of the cash greeks of each position.
+/// The positions are assumed to be in the same currency. Underlying positions add their value to the cash delta.
+impl Portfolio {
+    pub fn cash_delta(&self) -> FloatType {
+        let options: FloatType = self.positions.iter().map(|p| p.quantity * p.tick.cash_delta()).sum();
+        options + self.underlyings.iter().map(|p| p.value()).sum::<FloatType>()
+    }
+
+    pub fn cash_gamma_1pct(&self) -> FloatType {
+        self.positions.iter().map(|p| p.quantity * p.tick.cash_gamma_1pct()).sum()
+    }
+
+    pub fn cash_vega_1vol(&self) -> FloatType {
+        self.positions.iter().map(|p| p.quantity * p.tick.cash_vega_1vol()).sum()
+    }
+}
@@ -0,0 +1,102 @@
+//! Payoff diagrams and greek profiles of a Portfolio over a range of spot prices.
+//!
+//! The curves are returned as aligned (spots, values) vectors, ready to be plotted or exported.
+//! payoff_curve() is the P&L at expiry, value_curve() the theoretical value today and greek_curve() the portfolio greek,
+//! each evaluated with the spot of every position replaced by the spot of the grid.
+//! With the `plot` feature, write_png() renders the curves to a PNG file, OptionChain::plot_smile() the smile of an expiry
+//! and OptionBoard::plot_surface() the IV surface as a heatmap.
+//!
+//! # Example
+//! ```ignore
+//! let range = SpotRange::new(80., 120., 81);
+//! let (spots, payoff) = portfolio.payoff_curve(&range);
+//! let (_, delta) = portfolio.greek_curve(Greek::Delta, &range);
+//! write_png("payoff.png", &[(spots, payoff)], (800, 600))?;
+//! board.plot_surface("surface.png", (800, 600))?;
+//! ```
+
+#[cfg(feature = "plot")]
+pub mod png;
+
+#[cfg(feature = "plot")]
+pub use png::*;
+
+use crate::greeks::*;
+use crate::models::*;
+use crate::portfolio::*;
+use serde::{Deserialize, Serialize};
+
+/// Evenly spaced spot prices from `from` to `to` (both included).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpotRange {
+    pub from: FloatType,
+    pub to: FloatType,
+    /// Number of points, at least 2
+    pub steps: usize,
+}
+
+impl SpotRange {
+    pub fn new(from: FloatType, to: FloatType, steps: usize) -> Self {
+        Self {
+            from,
+            to,
+            steps: steps.max(2),
+        }
+    }
+
+    /// Range of `spot * (1 - width)` to `spot * (1 + width)`, e.g. width = 0.2 for ±20%.
+    pub fn around(spot: FloatType, width: FloatType, steps: usize) -> Self {
+        Self::new(spot * (1. - width), spot * (1. + width), steps)
+    }
+
+    pub fn points(&self) -> Vec<FloatType> {
+        let step = (self.to - self.from) / (self.steps - 1) as FloatType;
+        (0..self.steps)
+            .map(|i| self.from + i as FloatType * step)
+            .collect()
+    }
+}
+
+impl Portfolio {
+    fn curve(
+        &self,
+        range: &SpotRange,
+        f: impl Fn(FloatType) -> FloatType,
+    ) -> (Vec<FloatType>, Vec<FloatType>) {
+        let spots = range.points();
+        let values = spots.iter().map(|s| f(*s)).collect();
+        (spots, values)
+    }
+
+    /// P&L at expiry: the payoff minus the current value of the portfolio.
+    pub fn payoff_curve(&self, range: &SpotRange) -> (Vec<FloatType>, Vec<FloatType>) {
+        let value = self.value();
+        self.curve(range, |spot| self.payoff_at_expiry(spot) - value)
+    }
+
+    /// Theoretical value of the portfolio today.
+    pub fn value_curve(&self, range: &SpotRange) -> (Vec<FloatType>, Vec<FloatType>) {
+        self.curve(range, |spot| {
+            self.positions
+                .iter()
+                .map(|p| p.value_at(spot, 0., 0.))
+                .sum::<FloatType>()
+                + self.underlying_value_at(spot)
+        })
+    }
+
+    /// Sum of quantity * greek of the positions today. The underlying positions add their quantity to the delta.
+    pub fn greek_curve(&self, greek: Greek, range: &SpotRange) -> (Vec<FloatType>, Vec<FloatType>) {
+        let underlying = match greek {
+            Greek::Delta => self.underlying_delta(),
+            _ => 0.,
+        };
+        self.curve(range, |spot| {
+            self.positions
+                .iter()
+                .map(|p| p.quantity * greek.of(&shocked_tick(&p.tick, spot, 0., 0.)))
+                .sum::<FloatType>()
+                + underlying
+        })
+    }
+}
@@ -0,0 +1,221 @@
+//! PNG export of curves, smiles and surfaces with plotters, available with the `plot` feature.
+//!
+//! A curve chart has a light grid, a zero line and one line per curve in the order of the palette.
+//! A heatmap colours each cell from blue (lowest value) to red (highest value) and leaves cells without a value blank.
+//! No text is drawn, so the export does not depend on the fonts of the system.
+
+use crate::black_scholes::BlackScholes;
+use crate::math::average_by_x;
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use plotters::prelude::*;
+use std::path::Path;
+
+/// Renders the curves, given as aligned (x, y) vectors, to a PNG file of `size` (width, height) pixels.
+pub fn write_png(
+    path: impl AsRef<Path>,
+    curves: &[(Vec<FloatType>, Vec<FloatType>)],
+    size: (u32, u32),
+) -> Result<()> {
+    ensure!(!curves.is_empty(), "At least one curve is required");
+    ensure!(
+        curves
+            .iter()
+            .all(|(xs, ys)| xs.len() == ys.len() && !xs.is_empty()),
+        "Curves must be non-empty with aligned x and y"
+    );
+    let points = curves.iter().flat_map(|(xs, ys)| xs.iter().zip(ys));
+    let (x_min, x_max, y_min, y_max) = points.fold(
+        (
+            FloatType::MAX,
+            FloatType::MIN,
+            0. as FloatType,
+            0. as FloatType,
+        ),
+        |(x0, x1, y0, y1), (x, y)| (x0.min(*x), x1.max(*x), y0.min(*y), y1.max(*y)),
+    );
+    let margin = ((y_max - y_min) * 0.05).max(1e-9);
+
+    let root = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| anyhow!("{e}"))?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .build_cartesian_2d(x_min..x_max, (y_min - margin)..(y_max + margin))
+        .map_err(|e| anyhow!("{e}"))?;
+    chart
+        .configure_mesh()
+        .x_labels(10)
+        .y_labels(10)
+        .x_label_formatter(&|_| String::new())
+        .y_label_formatter(&|_| String::new())
+        .draw()
+        .map_err(|e| anyhow!("{e}"))?;
+    chart
+        .draw_series(LineSeries::new(vec![(x_min, 0.), (x_max, 0.)], BLACK))
+        .map_err(|e| anyhow!("{e}"))?;
+    for (i, (xs, ys)) in curves.iter().enumerate() {
+        let color = Palette99::pick(i).stroke_width(2);
+        chart
+            .draw_series(LineSeries::new(
+                xs.iter().cloned().zip(ys.iter().cloned()),
+                color,
+            ))
+            .map_err(|e| anyhow!("{e}"))?;
+    }
+    root.present().map_err(|e| anyhow!("{e}"))?;
+    Ok(())
+}
+
+/// Renders `values[i][j]`, the value at (xs[j], ys[i]), as a heatmap to a PNG file of `size` (width, height) pixels.
+/// Each cell extends halfway to its neighbours; NaN values are left blank.
+pub fn write_heatmap_png(
+    path: impl AsRef<Path>,
+    xs: &[FloatType],
+    ys: &[FloatType],
+    values: &[Vec<FloatType>],
+    size: (u32, u32),
+) -> Result<()> {
+    ensure!(
+        !xs.is_empty() && !ys.is_empty(),
+        "The axes must not be empty"
+    );
+    ensure!(
+        values.len() == ys.len() && values.iter().all(|row| row.len() == xs.len()),
+        "values must have one row per y and one column per x"
+    );
+    let finite = values.iter().flatten().filter(|v| v.is_finite());
+    let (z_min, z_max) = finite.fold((FloatType::MAX, FloatType::MIN), |(lo, hi), v| {
+        (lo.min(*v), hi.max(*v))
+    });
+    ensure!(z_min <= z_max, "values has no finite value");
+    let (x_edges, y_edges) = (cell_edges(xs), cell_edges(ys));
+
+    let root = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| anyhow!("{e}"))?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .build_cartesian_2d(x_edges[0]..x_edges[xs.len()], y_edges[0]..y_edges[ys.len()])
+        .map_err(|e| anyhow!("{e}"))?;
+    let cells = values.iter().enumerate().flat_map(|(i, row)| {
+        let (x_edges, y_edges) = (&x_edges, &y_edges);
+        row.iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_finite())
+            .map(move |(j, v)| {
+                let level = if z_max > z_min {
+                    (v - z_min) / (z_max - z_min)
+                } else {
+                    0.5
+                };
+                let color = HSLColor(2. / 3. * (1. - level), 0.8, 0.5);
+                Rectangle::new(
+                    [(x_edges[j], y_edges[i]), (x_edges[j + 1], y_edges[i + 1])],
+                    color.filled(),
+                )
+            })
+    });
+    chart.draw_series(cells).map_err(|e| anyhow!("{e}"))?;
+    root.present().map_err(|e| anyhow!("{e}"))?;
+    Ok(())
+}
+
+/// Edges of the cells centred on the points, which must be in ascending order.
+fn cell_edges(points: &[FloatType]) -> Vec<FloatType> {
+    let n = points.len();
+    if n == 1 {
+        let half = points[0].abs().max(1.) * 0.5;
+        return vec![points[0] - half, points[0] + half];
+    }
+    let mut edges = vec![points[0] - (points[1] - points[0]) / 2.];
+    edges.extend(points.windows(2).map(|w| (w[0] + w[1]) / 2.));
+    edges.push(points[n - 1] + (points[n - 1] - points[n - 2]) / 2.);
+    edges
+}
+
+/// Number of log-moneyness columns of plot_surface.
+const SURFACE_COLUMNS: usize = 60;
+
+impl OptionChain<OptionTick> {
+    /// Renders the IV of the OTM options against the strike to a PNG file.
+    pub fn plot_smile(&self, path: impl AsRef<Path>, size: (u32, u32)) -> Result<()> {
+        write_png(path, &[self.otm().smile_curve()], size)
+    }
+
+    /// Renders the IV of the OTM options against the log moneyness ln(K / F) to a PNG file.
+    pub fn plot_smile_moneyness(&self, path: impl AsRef<Path>, size: (u32, u32)) -> Result<()> {
+        write_png(path, &[smile_by_moneyness(self, SmileSource::OtmOnly)], size)
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Renders the IV surface as a heatmap of log moneyness (x) and time to maturity (y) to a PNG file.
+    /// The OTM smile of each expiry is interpolated linearly in log moneyness and left blank outside its listed range.
+    pub fn plot_surface(&self, path: impl AsRef<Path>, size: (u32, u32)) -> Result<()> {
+        self.plot_surface_with(path, size, SmileSource::OtmOnly)
+    }
+
+    /// Renders the IV surface built from the quotes of the source, see plot_surface().
+    pub fn plot_surface_with(
+        &self,
+        path: impl AsRef<Path>,
+        size: (u32, u32),
+        source: SmileSource,
+    ) -> Result<()> {
+        let board = self.sort_by_maturity();
+        let chains: Vec<&OptionChain<OptionTick>> =
+            board.0.iter().filter(|c| !c.0.is_empty()).collect();
+        ensure!(!chains.is_empty(), "The board has no ticks");
+        let taus: Vec<FloatType> = chains.iter().map(|c| c.0[0].tau()).collect();
+        let smiles: Vec<_> = chains
+            .iter()
+            .map(|c| smile_by_moneyness(c, source))
+            .collect();
+        let all = smiles.iter().flat_map(|(ks, _)| ks.iter());
+        let (k_min, k_max) = all.fold((FloatType::MAX, FloatType::MIN), |(lo, hi), k| {
+            (lo.min(*k), hi.max(*k))
+        });
+        ensure!(
+            k_min < k_max,
+            "The board needs at least two distinct strikes"
+        );
+        let step = (k_max - k_min) / (SURFACE_COLUMNS - 1) as FloatType;
+        let grid: Vec<FloatType> = (0..SURFACE_COLUMNS)
+            .map(|j| k_min + j as FloatType * step)
+            .collect();
+        let values: Vec<Vec<FloatType>> = smiles
+            .iter()
+            .map(|(ks, ivs)| grid.iter().map(|k| interpolate(ks, ivs, *k)).collect())
+            .collect();
+        write_heatmap_png(path, &grid, &taus, &values, size)
+    }
+}
+
+/// (log moneyness, IV) of the ticks of the source in ascending order of log moneyness, one point per strike.
+fn smile_by_moneyness(
+    chain: &OptionChain<OptionTick>,
+    source: SmileSource,
+) -> (Vec<FloatType>, Vec<FloatType>) {
+    let mut points: Vec<(FloatType, FloatType)> = chain
+        .smile_ticks(source)
+        .0
+        .iter()
+        .map(|tick| {
+            (
+                tick.log_moneyness(),
+                tick.get_implied_volatility().get_value(),
+            )
+        })
+        .filter(|(k, iv)| k.is_finite() && iv.is_finite())
+        .collect();
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    average_by_x(&points)
+}
+
+/// Linear interpolation, NaN outside [xs[0], xs[n - 1]].
+fn interpolate(xs: &[FloatType], ys: &[FloatType], x: FloatType) -> FloatType {
+    match xs.iter().position(|v| *v >= x) {
+        Some(0) if xs[0] == x => ys[0],
+        Some(0) | None => FloatType::NAN,
+        Some(i) => ys[i - 1] + (ys[i] - ys[i - 1]) * (x - xs[i - 1]) / (xs[i] - xs[i - 1]),
+    }
+}
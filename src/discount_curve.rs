@@ -0,0 +1,130 @@
+//! Discount curves used by BlackScholes and EuropeanGreeks for discounting and carry.
+//!
+//! An OptionTick discounts with its flat risk_free_rate unless a ZeroCurve is set in its discount_curve field,
+//! in which case the zero rate at the maturity of the tick is used. A ZeroCurve is a piecewise linear curve of continuously
+//! compounded zero rates built from pillars. The same type holds the dividend_curve of a tick; futures prices only give
+//! the carry r - q, so dividend_yield_from_futures() needs a discount curve to turn them into dividend yields.
+//!
+//! # Example
+//! ```ignore
+//! let rates = ZeroCurve::new(vec![0.25, 0.5, 1.], vec![0.05, 0.051, 0.052])?;
+//! let dividends = ZeroCurve::dividend_yield_from_futures(spot, &[(0.25, 101.2), (0.5, 102.3), (1., 104.5)], &rates)?;
+//! let tick = tick.with_discount_curve(rates).with_dividend_curve(dividends);
+//! ```
+
+use crate::models::FloatType;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+pub trait DiscountCurve {
+    /// Continuously compounded zero rate for maturity `tau` in years
+    fn zero_rate(&self, tau: FloatType) -> FloatType;
+
+    fn discount_factor(&self, tau: FloatType) -> FloatType {
+        (-self.zero_rate(tau) * tau).exp()
+    }
+
+    /// Continuously compounded forward rate between t1 and t2
+    fn forward_rate(&self, t1: FloatType, t2: FloatType) -> FloatType {
+        (self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FlatRate(pub FloatType);
+
+impl DiscountCurve for FlatRate {
+    fn zero_rate(&self, _tau: FloatType) -> FloatType {
+        self.0
+    }
+}
+
+/// Zero rates at pillar maturities, linearly interpolated and extrapolated flat.
+/// Deserialization goes through ZeroCurve::new(), so a persisted curve without pillars is rejected.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ZeroCurvePillars")]
+pub struct ZeroCurve {
+    /// Pillar maturities in years, in ascending order
+    pub tenors: Vec<FloatType>,
+    pub rates: Vec<FloatType>,
+}
+
+#[derive(Deserialize)]
+struct ZeroCurvePillars {
+    tenors: Vec<FloatType>,
+    rates: Vec<FloatType>,
+}
+
+impl TryFrom<ZeroCurvePillars> for ZeroCurve {
+    type Error = anyhow::Error;
+
+    fn try_from(pillars: ZeroCurvePillars) -> Result<Self> {
+        Self::new(pillars.tenors, pillars.rates)
+    }
+}
+
+impl ZeroCurve {
+    pub fn new(tenors: Vec<FloatType>, rates: Vec<FloatType>) -> Result<Self> {
+        ensure!(!tenors.is_empty(), "At least one pillar is required");
+        ensure!(tenors.len() == rates.len(), "tenors and rates must have the same length");
+        ensure!(
+            tenors.windows(2).all(|w| w[0] < w[1]),
+            "Tenors must be in strictly ascending order"
+        );
+        Ok(Self { tenors, rates })
+    }
+
+    pub fn flat(rate: FloatType) -> Self {
+        Self {
+            tenors: vec![1.],
+            rates: vec![rate],
+        }
+    }
+
+    /// Dividend yield curve implied by futures prices as (maturity in years, futures price), for the dividend_curve of a tick.
+    /// The futures give the carry b(T) = ln(F(T) / S) / T, the financing rate net of dividends, so the yield at each
+    /// maturity is q(T) = r(T) - b(T) with r(T) the zero rate of `discount`.
+    pub fn dividend_yield_from_futures(
+        spot: FloatType,
+        futures: &[(FloatType, FloatType)],
+        discount: &impl DiscountCurve,
+    ) -> Result<Self> {
+        ensure!(spot > 0., "The spot price must be positive");
+        ensure!(
+            futures.iter().all(|(tau, price)| *tau > 0. && *price > 0.),
+            "Futures maturities and prices must be positive"
+        );
+        let mut futures = futures.to_vec();
+        futures.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Self::new(
+            futures.iter().map(|(tau, _)| *tau).collect(),
+            futures
+                .iter()
+                .map(|(tau, price)| discount.zero_rate(*tau) - (price / spot).ln() / tau)
+                .collect(),
+        )
+    }
+
+    /// The curve with every zero rate shifted by `amount`.
+    pub fn shifted(&self, amount: FloatType) -> Self {
+        Self {
+            tenors: self.tenors.clone(),
+            rates: self.rates.iter().map(|r| r + amount).collect(),
+        }
+    }
+}
+
+impl DiscountCurve for ZeroCurve {
+    fn zero_rate(&self, tau: FloatType) -> FloatType {
+        let n = self.tenors.len();
+        if tau <= self.tenors[0] {
+            return self.rates[0];
+        }
+        if tau >= self.tenors[n - 1] {
+            return self.rates[n - 1];
+        }
+        let i = self.tenors.iter().rposition(|t| *t <= tau).unwrap();
+        let w = (tau - self.tenors[i]) / (self.tenors[i + 1] - self.tenors[i]);
+        self.rates[i] + w * (self.rates[i + 1] - self.rates[i])
+    }
+}
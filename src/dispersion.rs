@@ -0,0 +1,50 @@
+//! Implied correlation of an index from the IVs of the index and of its components.
+//!
+//! If the index is a weighted sum of its components with a common pairwise correlation rho, its variance is
+//! `sigma_I^2 = sum w_i^2 sigma_i^2 + 2 rho sum_{i<j} w_i w_j sigma_i sigma_j`, so the correlation implied by the option prices is
+//! `rho = (sigma_I^2 - sum w_i^2 sigma_i^2) / (2 sum_{i<j} w_i w_j sigma_i sigma_j)`.
+//! A dispersion trade sells index volatility against component volatility when this correlation is high.
+//!
+//! The chains should have the same (or close) maturities; the ATM IV of each chain is used.
+//!
+//! # Example
+//! ```ignore
+//! let rho = implied_correlation(&index_chain, &[aapl_chain, msft_chain, nvda_chain], &[0.4, 0.35, 0.25])?;
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+
+/// Implied correlation from the index IV and the component IVs.
+pub fn implied_correlation_from_ivs(
+    index_iv: FloatType,
+    component_ivs: &[FloatType],
+    weights: &[FloatType],
+) -> Result<FloatType> {
+    ensure!(component_ivs.len() >= 2, "At least two components are required");
+    ensure!(component_ivs.len() == weights.len(), "There must be one weight per component");
+    ensure!(
+        index_iv.is_finite() && component_ivs.iter().all(|iv| iv.is_finite()),
+        "IVs must be finite"
+    );
+    let own: FloatType = weights.iter().zip(component_ivs).map(|(w, iv)| (w * iv).powi(2)).sum();
+    let total: FloatType = weights.iter().zip(component_ivs).map(|(w, iv)| w * iv).sum();
+    // sum_{i<j} w_i w_j sigma_i sigma_j = ((sum w_i sigma_i)^2 - sum (w_i sigma_i)^2) / 2
+    let cross = (total * total - own) / 2.;
+    ensure!(cross != 0., "The cross terms of the components vanish");
+    Ok((index_iv * index_iv - own) / (2. * cross))
+}
+
+/// Implied correlation from the ATM IVs of the index chain and of the component chains.
+pub fn implied_correlation(
+    index_chain: &OptionChain<OptionTick>,
+    component_chains: &[OptionChain<OptionTick>],
+    weights: &[FloatType],
+) -> Result<FloatType> {
+    ensure!(
+        !index_chain.0.is_empty() && component_chains.iter().all(|chain| !chain.0.is_empty()),
+        "Chains must not be empty"
+    );
+    let component_ivs: Vec<FloatType> = component_chains.iter().map(|chain| chain.atm().iv()).collect();
+    implied_correlation_from_ivs(index_chain.atm().iv(), &component_ivs, weights)
+}
@@ -0,0 +1,89 @@
+//! Discrete cash dividends.
+//!
+//! dividend_yield of an OptionTick models a continuous yield, which suits indices but not single stocks paying a few known dividends.
+//! A DividendSchedule lists the ex-dates and cash amounts. When set on a tick, pricing and greeks use the escrowed dividend model:
+//! the spot is reduced by the present value of the dividends going ex before maturity, and the Black-Scholes formulas are applied to
+//! that escrowed spot. The greeks with respect to the spot are unchanged by the adjustment, since the present value does not depend on it.
+//!
+//! # Example
+//! ```ignore
+//! let schedule = DividendSchedule::new(vec![CashDividend::new(ex_date, 0.8)]);
+//! let tick = tick.with_dividends(schedule);
+//! println!("{}", tick.get_theoretical_price().get_value());
+//! ```
+
+use crate::discount_curve::DiscountCurve;
+use crate::models::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CashDividend {
+    pub ex_date: DateTime<Utc>,
+    pub amount: FloatType,
+}
+
+impl CashDividend {
+    pub fn new(ex_date: DateTime<Utc>, amount: FloatType) -> Self {
+        Self { ex_date, amount }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DividendSchedule(pub Vec<CashDividend>);
+
+impl DividendSchedule {
+    pub fn new(mut dividends: Vec<CashDividend>) -> Self {
+        dividends.sort_by_key(|d| d.ex_date);
+        Self(dividends)
+    }
+
+    pub fn push(&mut self, dividend: CashDividend) {
+        self.0.push(dividend);
+        self.0.sort_by_key(|d| d.ex_date);
+    }
+
+    /// Dividends going ex in (from, to]
+    pub fn between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> impl Iterator<Item = &CashDividend> {
+        self.0.iter().filter(move |d| from < d.ex_date && d.ex_date <= to)
+    }
+}
+
+impl OptionTick {
+    pub fn with_dividends(&self, dividends: DividendSchedule) -> Self {
+        Self {
+            dividends: Some(dividends),
+            ..self.clone()
+        }
+    }
+
+    /// Present value of the cash dividends going ex between the valuation time and maturity.
+    /// Each dividend is discounted at the zero rate of discount_curve for its ex-date, or at risk_free_rate.
+    pub fn dividends_present_value(&self) -> FloatType {
+        let schedule = match &self.dividends {
+            Some(schedule) => schedule,
+            None => return 0.,
+        };
+        let now = self.valuation_time.unwrap_or_else(Utc::now);
+        schedule
+            .between(now, self.maturity)
+            .map(|d| {
+                let t = self.day_count.year_fraction(now, d.ex_date);
+                let discount = match &self.discount_curve {
+                    Some(curve) => curve.discount_factor(t),
+                    None => (-self.risk_free_rate * t).exp(),
+                };
+                d.amount * discount
+            })
+            .sum()
+    }
+
+    /// Spot used by the pricing formulas: asset_price minus the present value of the dividends before maturity.
+    /// Under Black-76 and Bachelier the underlying is a futures contract and the dividends are ignored.
+    pub fn escrowed_spot(&self) -> FloatType {
+        match self.pricing_model {
+            PricingModel::BlackScholes => self.asset_price - self.dividends_present_value(),
+            PricingModel::Black76 | PricingModel::Bachelier => self.asset_price,
+        }
+    }
+}
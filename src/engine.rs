@@ -0,0 +1,132 @@
+//! Streaming board with an incremental IV/greeks cache.
+//!
+//! OptionBoardEngine applies the upserts and deletes of a feed to an IndexedBoard and keeps the IV and greeks of each
+//! (maturity, strike, option type) in a cache. An update only invalidates the entry of the strike it touches, and entries are
+//! recomputed lazily from the mid of the StrikeBoard when they are read, so a busy feed does not recompute the whole chain.
+//!
+//! The greeks also depend on the passage of time; call invalidate_all() when the valuation time moves materially.
+//!
+//! # Example
+//! ```ignore
+//! let mut engine = OptionBoardEngine::new();
+//! for tick in feed {
+//!     engine.upsert(tick);
+//! }
+//! let greeks = engine.greeks(maturity, strike, OptionType::Call)?;
+//! println!("iv: {}, delta: {}", greeks.iv, greeks.delta);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub type ContractKey = (DateTime<Utc>, DecimalType, OptionType);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CachedGreeks {
+    pub iv: FloatType,
+    pub delta: FloatType,
+    pub gamma: FloatType,
+    pub vega: FloatType,
+    pub theta: FloatType,
+}
+
+impl CachedGreeks {
+    pub fn from_tick(tick: &OptionTick) -> Result<Self> {
+        let tick = tick.get_implied_volatility();
+        Ok(Self {
+            iv: tick.iv(),
+            delta: tick.try_delta()?,
+            gamma: tick.try_gamma()?,
+            vega: tick.try_vega()?,
+            theta: tick.try_theta()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OptionBoardEngine {
+    board: IndexedBoard,
+    cache: BTreeMap<ContractKey, CachedGreeks>,
+    /// Number of cache entries computed since the engine was created
+    pub recomputations: usize,
+}
+
+fn key(tick: &OptionTick) -> ContractKey {
+    (tick.maturity, tick.strike, tick.option_type.clone())
+}
+
+impl OptionBoardEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn board(&self) -> &IndexedBoard {
+        &self.board
+    }
+
+    pub fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        let key = key(&tick);
+        let mutation = self.board.upsert(tick);
+        if mutation != Mutation::Unchanged {
+            self.cache.remove(&key);
+        }
+        mutation
+    }
+
+    pub fn delete(&mut self, tick: OptionTick) -> Mutation {
+        let key = key(&tick);
+        let mutation = self.board.delete(tick);
+        if mutation != Mutation::Unchanged {
+            self.cache.remove(&key);
+        }
+        mutation
+    }
+
+    pub fn invalidate_all(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn is_cached(&self, maturity: DateTime<Utc>, strike: DecimalType, option_type: OptionType) -> bool {
+        self.cache.contains_key(&(maturity, strike, option_type))
+    }
+
+    /// IV and greeks of the mid of the strike, computed if the cached entry was invalidated.
+    pub fn greeks(
+        &mut self,
+        maturity: DateTime<Utc>,
+        strike: DecimalType,
+        option_type: OptionType,
+    ) -> Result<CachedGreeks> {
+        let key = (maturity, strike, option_type.clone());
+        if let Some(greeks) = self.cache.get(&key) {
+            return Ok(greeks.clone());
+        }
+        let strike_board = self
+            .board
+            .chain(maturity)
+            .and_then(|chain| chain.get(strike, option_type))
+            .ok_or_else(|| anyhow!("No quotes for this contract"))?;
+        let greeks = CachedGreeks::from_tick(&strike_board.mid()?)?;
+        self.recomputations += 1;
+        self.cache.insert(key, greeks.clone());
+        Ok(greeks)
+    }
+
+    /// Greeks of every strike of the expiry in ascending order of strike. Strikes whose greeks cannot be computed are skipped.
+    pub fn chain_greeks(&mut self, maturity: DateTime<Utc>) -> Vec<(DecimalType, OptionType, CachedGreeks)> {
+        let keys: Vec<StrikeKey> = match self.board.chain(maturity) {
+            Some(chain) => chain.0.keys().cloned().collect(),
+            None => return Vec::new(),
+        };
+        keys.into_iter()
+            .filter_map(|(strike, option_type)| {
+                let greeks = self.greeks(maturity, strike, option_type.clone()).ok()?;
+                Some((strike, option_type, greeks))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+//! ATM straddle price and the move it implies by expiry.
+//!
+//! The ATM straddle is the call and the put of the listed strike closest to the asset price. Its price is the market's
+//! estimate of the absolute move of the underlying by expiry: buying it breaks even if the underlying ends outside
+//! strike ± price. The expected move is quoted around the asset price as asset price ± straddle price.
+//!
+//! # Example
+//! ```ignore
+//! let em = chain.expected_move()?;
+//! println!("±{:.0} ({:.1}%), range {:.0} - {:.0}", em.expected_move, em.expected_move_pct() * 100., em.lower, em.upper);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedMove {
+    /// Strike of the ATM straddle
+    pub strike: DecimalType,
+    pub asset_price: FloatType,
+    /// Price of the call plus the price of the put
+    pub straddle_price: FloatType,
+    /// Absolute move implied by the straddle, equal to its price
+    pub expected_move: FloatType,
+    /// asset_price - expected_move
+    pub lower: FloatType,
+    /// asset_price + expected_move
+    pub upper: FloatType,
+    /// strike - straddle_price, below which the long straddle makes money at expiry
+    pub lower_break_even: FloatType,
+    /// strike + straddle_price, above which the long straddle makes money at expiry
+    pub upper_break_even: FloatType,
+}
+
+impl ExpectedMove {
+    /// Expected move relative to the asset price.
+    pub fn expected_move_pct(&self) -> FloatType {
+        self.expected_move / self.asset_price
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Listed strike closest to the asset price with both a call and a put.
+    pub fn atm_straddle_strike(&self) -> Result<DecimalType> {
+        let asset_price = self.asset_price()?;
+        self.call()
+            .0
+            .iter()
+            .map(|t| t.strike)
+            .filter(|k| self.find(*k, OptionType::Put).is_ok())
+            .min_by(|a, b| {
+                let distance = |k: &DecimalType| (k.to_f64().unwrap() - asset_price).abs();
+                distance(a).partial_cmp(&distance(b)).unwrap()
+            })
+            .ok_or_else(|| anyhow!("No strike has both a call and a put"))
+    }
+
+    /// Price of the call plus the price of the put at the ATM straddle strike.
+    pub fn atm_straddle_price(&self) -> Result<FloatType> {
+        let strike = self.atm_straddle_strike()?;
+        let price = |option_type| -> Result<FloatType> {
+            Ok(self
+                .find(strike, option_type)?
+                .get_theoretical_price()
+                .get_value())
+        };
+        Ok(price(OptionType::Call)? + price(OptionType::Put)?)
+    }
+
+    pub fn expected_move(&self) -> Result<ExpectedMove> {
+        let strike = self.atm_straddle_strike()?;
+        let asset_price = self.asset_price()?;
+        let straddle_price = self.atm_straddle_price()?;
+        let k = strike.to_f64().unwrap();
+        Ok(ExpectedMove {
+            strike,
+            asset_price,
+            straddle_price,
+            expected_move: straddle_price,
+            lower: asset_price - straddle_price,
+            upper: asset_price + straddle_price,
+            lower_break_even: k - straddle_price,
+            upper_break_even: k + straddle_price,
+        })
+    }
+}
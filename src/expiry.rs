@@ -0,0 +1,81 @@
+//! Selection of expiries of an OptionBoard by days to expiry and by expiry cycle.
+//!
+//! Days to expiry (DTE) are measured in calendar days from `now` to the maturity of each chain.
+//! Monthly expiries are recognised from the date of the maturity in UTC with an ExpiryCycle: the third Friday of the month for
+//! listed equity and index options, the last Friday of the month for Deribit. Every other expiry is treated as a weekly.
+//!
+//! # Example
+//! ```ignore
+//! let short_dated = board.expiring_within(7., Utc::now());
+//! let one_month = board.nearest_to_dte(30., Utc::now()).unwrap();
+//! let monthlies = board.monthlies_only(ExpiryCycle::LastFriday);
+//! ```
+
+use crate::models::*;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExpiryCycle {
+    /// Monthly options expire on the third Friday of the month
+    ThirdFriday,
+    /// Monthly options expire on the last Friday of the month
+    LastFriday,
+}
+
+impl ExpiryCycle {
+    pub fn is_monthly(&self, date: NaiveDate) -> bool {
+        if date.weekday() != Weekday::Fri {
+            return false;
+        }
+        match self {
+            ExpiryCycle::ThirdFriday => (15..=21).contains(&date.day()),
+            ExpiryCycle::LastFriday => (date + Duration::days(7)).month() != date.month(),
+        }
+    }
+}
+
+/// Days from `now` to `maturity`, fractional.
+pub fn days_to_expiry(maturity: DateTime<Utc>, now: DateTime<Utc>) -> FloatType {
+    (maturity - now).num_seconds() as FloatType / 86400.
+}
+
+impl<T> OptionBoard<T>
+where
+    T: OptionBase + ExtractCommonInfo,
+{
+    fn filter_chains(&self, f: impl Fn(DateTime<Utc>) -> bool) -> Self {
+        OptionBoard(
+            self.sort_by_maturity()
+                .0
+                .into_iter()
+                .filter(|chain| chain.maturity().is_ok_and(&f))
+                .collect(),
+        )
+    }
+
+    /// Chains expiring between `now` and `days` days later (both included), in ascending order of maturity.
+    pub fn expiring_within(&self, days: FloatType, now: DateTime<Utc>) -> Self {
+        self.filter_chains(|maturity| (0. ..=days).contains(&days_to_expiry(maturity, now)))
+    }
+
+    /// Chain whose days to expiry are closest to `days`, among the chains not yet expired. None if there is none.
+    pub fn nearest_to_dte(&self, days: FloatType, now: DateTime<Utc>) -> Option<OptionChain<T>> {
+        self.0
+            .iter()
+            .filter_map(|chain| Some((days_to_expiry(chain.maturity().ok()?, now), chain)))
+            .filter(|(dte, _)| *dte >= 0.)
+            .min_by(|(a, _), (b, _)| (a - days).abs().partial_cmp(&(b - days).abs()).unwrap())
+            .map(|(_, chain)| chain.clone())
+    }
+
+    /// Chains expiring on a monthly date of the cycle, in ascending order of maturity.
+    pub fn monthlies_only(&self, cycle: ExpiryCycle) -> Self {
+        self.filter_chains(|maturity| cycle.is_monthly(maturity.date_naive()))
+    }
+
+    /// Chains not expiring on a monthly date of the cycle, in ascending order of maturity.
+    pub fn weeklies_only(&self, cycle: ExpiryCycle) -> Self {
+        self.filter_chains(|maturity| !cycle.is_monthly(maturity.date_naive()))
+    }
+}
@@ -5,22 +5,69 @@
 //!
 //!     Greeks Exposure = Sum of (Asset Price * Open Interest * Each Greek * (-1 if Put))
 //!
+//! This mixes a per-contract greek with the price of one unit of the underlying. The ExposureUnit of an ExposureConfig
+//! expresses the exposure in contracts, in units of the underlying, in notional (with the contract multiplier), or per 1%
+//! move, the usual convention of GEX:
+//! ```ignore
+//! let config = ExposureConfig::builder().unit(ExposureUnit::PerOnePercent).build();
+//! let gex_per_1pct = chain.exposure_with(Greek::Gamma, &config)?;
+//! ```
+//!
+//! Per-strike profiles (e.g. charm_exposure_profile()) show where the exposure sits, and project_exposure() recomputes an exposure
+//! a number of days forward, showing how dealer positioning shifts into expiry as charm and theta decay the greeks.
+//!
+//! The sign flip of the puts encodes an assumption on who holds the options: dealers long the calls and short the puts.
+//! exposure_with() and exposure_profile_with() take an ExposureConfig whose PositioningAssumption replaces it, e.g. dealers short
+//! every option, or a dealer position inferred per contract from the volume traded on the bid and on the ask.
+//! ```ignore
+//! let config = ExposureConfig::builder().positioning(PositioningAssumption::DealersShortAll).build();
+//! let gex = chain.exposure_with(Greek::Gamma, &config)?;
+//! ```
+//!
+//! The ticks of a contract (its bid, ask and trades) count its open interest once. A contract without open interest fails the
+//! whole chain by default. The MissingOiPolicy of the config can instead leave out its
+//! strike or count it as 0, and the `*_exposure_detailed()` methods and exposure_detailed() return the call and put
+//! contribution of each strike along with the strikes that were left out.
+//! ```ignore
+//! let config = ExposureConfig::builder().missing_oi(MissingOiPolicy::SkipStrike).build();
+//! let detail = chain.exposure_detailed(Greek::Gamma, &config)?;
+//! println!("{} over {} strikes, skipped {:?}", detail.total, detail.by_strike.len(), detail.skipped_strikes);
+//! ```
+//!
+//! expected_hedge_flow() turns the exposures into the underlying that dealers would trade to stay delta neutral after a move:
+//! the dealer delta changes by gamma * dS + vanna * dIV + charm * dt per unit of open interest, and the hedge trades the opposite.
+//! dt is the year fraction that elapses over the given days under the day count of each contract.
+//! The flow is in units of the underlying (open interest times the contract multiplier) whatever the unit of the config.
+//! ```ignore
+//! let flow = chain.expected_hedge_flow(-50., 0.02, 1.)?;
+//! println!("dealers buy {} units ({} from vanna)", flow.total, flow.vanna);
+//! ```
 //!
 //! # Example
 //! A prime example of Greek exposure is also called gamma exposure (GEX), which represents a market maker's gamma risk in their position. By monitoring their Greeks Exposure, market makers can manage the risk associated with their option positions.
 
 use crate::black_scholes::*;
-use crate::greeks::EuropeanGreeks;
+use crate::greeks::Greek;
 use crate::models::*;
-use anyhow::{ensure, Result};
+use chrono::Utc;
 use paste::paste;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use typed_builder::TypedBuilder;
+
+/// The tick if its greeks are well defined.
+fn checked(tick: &OptionTick) -> Result<&OptionTick, OptionError> {
+    tick.check_greeks()?;
+    Ok(tick)
+}
 
 macro_rules! exposure_trait {
 	($($greeks_name:ident),*) => {
 		pub trait GreeksExposure  {
 			$(
 				paste!{
-					fn [<$greeks_name _exposure>](&self) -> Result<FloatType>;
+					fn [<$greeks_name _exposure>](&self) -> Result<FloatType, OptionError>;
+					fn [<$greeks_name _exposure_detailed>](&self) -> Result<ExposureDetail, OptionError>;
 				}
 			)*
 		}
@@ -32,38 +79,12 @@ macro_rules! exposure_impl{
 		impl GreeksExposure for OptionChain<OptionTick>{
 			$(
 				paste!{
-					fn [<$greeks_name _exposure>](&self) -> Result<FloatType> {
-						let mut sum:FloatType = 0.;
-						for data in self.0.iter(){
-							let option_tick = data.to_owned();
-							let additional_data = option_tick.additional_data.clone();
-
-							ensure!(additional_data.is_some(), "No additional data is set. Set a value in the additional_data field of the OptionTick.");
-							let additional_data = additional_data.unwrap();
-
-							ensure!(additional_data.open_interest.is_some(), "No open interest is set. Set a value in the open_interest field of the additional_data.");
-							let oi = additional_data.open_interest.unwrap();
-							let asset_price = option_tick.asset_price().unwrap();
-
-							match data.option_value{
-								OptionValue::Price(_) =>  {
-									match data.option_type{
-										OptionType::Put => sum -= oi * option_tick.get_implied_volatility().$greeks_name() * asset_price,
-										OptionType::Call => sum += oi * option_tick.get_implied_volatility().$greeks_name() * asset_price
-									}
-								}
-
-								OptionValue::ImpliedVolatility(_) => {
-									match data.option_type{
-										OptionType::Put => sum -= oi * option_tick.$greeks_name() * asset_price,
-										OptionType::Call => sum += oi * option_tick.$greeks_name() * asset_price
-									}
-								}
-
-							}
-						}
-						Ok(sum)
+					fn [<$greeks_name _exposure>](&self) -> Result<FloatType, OptionError> {
+						self.exposure_with(Greek::[<$greeks_name:camel>], &ExposureConfig::default())
+					}
 
+					fn [<$greeks_name _exposure_detailed>](&self) -> Result<ExposureDetail, OptionError> {
+						self.exposure_detailed(Greek::[<$greeks_name:camel>], &ExposureConfig::default())
 					}
 				}
 
@@ -82,3 +103,426 @@ exposure_impl!(
     delta, gamma, theta, rho, vega, epsilon, vanna, charm, vomma, veta, speed, zomma, color,
     ultima, dual_delta, dual_gamma
 );
+
+/// Who is assumed to hold the open interest, expressed as the sign of the dealer position in each contract.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositioningAssumption {
+    /// Dealers long the calls and short the puts (customers sell calls and buy puts), the convention of GreeksExposure
+    #[default]
+    DealersLongCallsShortPuts,
+    /// Dealers short every option (customers buy every option)
+    DealersShortAll,
+    /// Dealers long every option (customers sell every option)
+    DealersLongAll,
+    /// Dealer position inferred per contract from the volume of its bid and ask ticks: customers selling on the bid make dealers
+    /// long, customers buying on the ask make them short. The sign is the StrikeBoard::volume_imbalance() of the contract,
+    /// 0 for a contract without sided volume.
+    VolumeBySide,
+}
+
+/// What to do with a contract none of whose ticks has open interest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissingOiPolicy {
+    /// Fail with OptionError::MissingOpenInterest
+    #[default]
+    Error,
+    /// Leave out every contract of the strike, which is reported in ExposureDetail::skipped_strikes
+    SkipStrike,
+    /// Count the contract with an open interest of 0
+    AssumeZero,
+}
+
+/// Unit of the exposure of one contract, with m the contract multiplier (1 without a ContractSpec).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExposureUnit {
+    /// greek * S * OI, the formula of GreeksExposure
+    #[default]
+    AssetPriceWeighted,
+    /// greek * OI, in contracts (e.g. delta exposure as a number of contracts of delta 1)
+    Contracts,
+    /// greek * OI * m, in units of the underlying (e.g. delta exposure as shares)
+    UnderlierUnits,
+    /// greek * OI * m * S, in currency (e.g. delta exposure as the notional of the underlying)
+    Notional,
+    /// greek * OI * m * S^2 * 0.01. For gamma, this is the GEX per 1% move: the change of the notional delta exposure when
+    /// the underlying moves by 1%
+    PerOnePercent,
+}
+
+impl ExposureUnit {
+    /// Factor applied to greek * OI.
+    fn scale(&self, tick: &OptionTick) -> FloatType {
+        let (s, m) = (tick.asset_price, tick.multiplier());
+        match self {
+            ExposureUnit::AssetPriceWeighted => s,
+            ExposureUnit::Contracts => 1.,
+            ExposureUnit::UnderlierUnits => m,
+            ExposureUnit::Notional => m * s,
+            ExposureUnit::PerOnePercent => m * s * s * 0.01,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize, Deserialize)]
+pub struct ExposureConfig {
+    #[builder(default)]
+    pub positioning: PositioningAssumption,
+    #[builder(default)]
+    pub missing_oi: MissingOiPolicy,
+    #[builder(default)]
+    pub unit: ExposureUnit,
+}
+
+impl PositioningAssumption {
+    /// Sign of the dealer position in a contract, given the bid and ask ticks of the contract.
+    fn dealer_sign(&self, contract: &StrikeBoard) -> FloatType {
+        match self {
+            PositioningAssumption::DealersLongCallsShortPuts => match contract.0[0].option_type {
+                OptionType::Call => 1.,
+                OptionType::Put => -1.,
+            },
+            PositioningAssumption::DealersShortAll => -1.,
+            PositioningAssumption::DealersLongAll => 1.,
+            PositioningAssumption::VolumeBySide => contract.volume_imbalance().unwrap_or(0.),
+        }
+    }
+}
+
+/// Underlying units that dealers trade to re-hedge, split by the greek that moves their delta.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HedgeFlow {
+    /// Flow from gamma: the change of delta with the spot move
+    pub gamma: FloatType,
+    /// Flow from vanna: the change of delta with the IV move
+    pub vanna: FloatType,
+    /// Flow from charm: the change of delta with the passage of time
+    pub charm: FloatType,
+    pub total: FloatType,
+    /// Total flow per strike, in ascending order of strike
+    pub by_strike: Vec<(DecimalType, FloatType)>,
+}
+
+/// Exposure of the ticks of one strike, after the dealer sign.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StrikeExposure {
+    pub strike: DecimalType,
+    pub call: FloatType,
+    pub put: FloatType,
+    pub total: FloatType,
+}
+
+/// Exposure of a chain with the contribution of each strike.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExposureDetail {
+    /// In ascending order of strike
+    pub by_strike: Vec<StrikeExposure>,
+    pub total: FloatType,
+    /// Strikes left out by MissingOiPolicy::SkipStrike, in ascending order
+    pub skipped_strikes: Vec<DecimalType>,
+}
+
+/// Open interest of a contract, from the first of its ticks that has one.
+fn open_interest(contract: &StrikeBoard) -> Option<FloatType> {
+    contract
+        .0
+        .iter()
+        .find_map(|tick| tick.additional_data.as_ref().and_then(|d| d.open_interest))
+}
+
+/// Ticks of the chain grouped by contract (maturity, strike, option type), so that the open interest of a contract quoted
+/// on both the bid and the ask counts once. In ascending order of maturity, strike and option type.
+fn contracts(chain: &OptionChain<OptionTick>) -> Vec<StrikeBoard> {
+    let mut contracts: BTreeMap<_, Vec<OptionTick>> = BTreeMap::new();
+    for tick in chain.0.iter() {
+        contracts
+            .entry((tick.maturity, tick.strike, tick.option_type.clone()))
+            .or_default()
+            .push(tick.clone());
+    }
+    contracts.into_values().map(StrikeBoard).collect()
+}
+
+/// Open interest * greek of a contract in the unit, before the dealer sign. The greek is the mean over the ticks of the
+/// contract, so that the bid and the ask weigh equally.
+/// A contract without open interest counts as 0 under MissingOiPolicy::AssumeZero and is an error otherwise.
+fn unsigned_exposure(
+    contract: &StrikeBoard,
+    greek: Greek,
+    policy: MissingOiPolicy,
+    unit: ExposureUnit,
+) -> Result<FloatType, OptionError> {
+    let oi = match (open_interest(contract), policy) {
+        (Some(oi), _) => oi,
+        (None, MissingOiPolicy::AssumeZero) => return Ok(0.),
+        (None, _) => return Err(OptionError::MissingOpenInterest),
+    };
+    let mut value = 0.;
+    for tick in contract.0.iter() {
+        value += greek.of(checked(&tick.get_implied_volatility())?);
+    }
+    value /= contract.0.len() as FloatType;
+    Ok(oi * value * unit.scale(&contract.0[0]))
+}
+
+/// Strikes with a contract without open interest, which MissingOiPolicy::SkipStrike leaves out.
+fn skipped_strikes(contracts: &[StrikeBoard], policy: MissingOiPolicy) -> Vec<DecimalType> {
+    if policy != MissingOiPolicy::SkipStrike {
+        return Vec::new();
+    }
+    let mut strikes: Vec<DecimalType> = contracts
+        .iter()
+        .filter(|contract| open_interest(contract).is_none())
+        .map(|contract| contract.0[0].strike)
+        .collect();
+    strikes.sort();
+    strikes.dedup();
+    strikes
+}
+
+impl OptionChain<OptionTick> {
+    /// Exposure of the greek with the call and put contribution of each strike, under the positioning assumption and the
+    /// missing open interest policy of the config.
+    pub fn exposure_detailed(
+        &self,
+        greek: Greek,
+        config: &ExposureConfig,
+    ) -> Result<ExposureDetail, OptionError> {
+        let contracts = contracts(self);
+        let skipped_strikes = skipped_strikes(&contracts, config.missing_oi);
+        let mut by_strike: BTreeMap<DecimalType, (FloatType, FloatType)> = BTreeMap::new();
+        for contract in contracts.iter() {
+            let tick = &contract.0[0];
+            if skipped_strikes.contains(&tick.strike) {
+                continue;
+            }
+            let sign = config.positioning.dealer_sign(contract);
+            let exposure = sign * unsigned_exposure(contract, greek, config.missing_oi, config.unit)?;
+            let (call, put) = by_strike.entry(tick.strike).or_default();
+            match tick.option_type {
+                OptionType::Call => *call += exposure,
+                OptionType::Put => *put += exposure,
+            }
+        }
+        let by_strike: Vec<StrikeExposure> = by_strike
+            .into_iter()
+            .map(|(strike, (call, put))| StrikeExposure {
+                strike,
+                call,
+                put,
+                total: call + put,
+            })
+            .collect();
+        Ok(ExposureDetail {
+            total: by_strike.iter().map(|s| s.total).sum(),
+            by_strike,
+            skipped_strikes,
+        })
+    }
+
+    /// Exposure of the greek per strike, in ascending order of strike.
+    pub fn exposure_profile(
+        &self,
+        greek: Greek,
+    ) -> Result<Vec<(DecimalType, FloatType)>, OptionError> {
+        self.exposure_profile_with(greek, &ExposureConfig::default())
+    }
+
+    /// Exposure of the greek per strike under the config, in ascending order of strike.
+    pub fn exposure_profile_with(
+        &self,
+        greek: Greek,
+        config: &ExposureConfig,
+    ) -> Result<Vec<(DecimalType, FloatType)>, OptionError> {
+        Ok(self
+            .exposure_detailed(greek, config)?
+            .by_strike
+            .into_iter()
+            .map(|s| (s.strike, s.total))
+            .collect())
+    }
+
+    /// Exposure of the greek under the config.
+    pub fn exposure_with(
+        &self,
+        greek: Greek,
+        config: &ExposureConfig,
+    ) -> Result<FloatType, OptionError> {
+        Ok(self.exposure_detailed(greek, config)?.total)
+    }
+
+    /// Underlying units that dealers buy (positive) or sell (negative) to stay delta neutral after the spot moves by `spot_move`
+    /// (in price), the IVs by `vol_move` (absolute, 0.01 = 1 vol point) and `days` pass, with the default positioning assumption.
+    pub fn expected_hedge_flow(
+        &self,
+        spot_move: FloatType,
+        vol_move: FloatType,
+        days: FloatType,
+    ) -> Result<HedgeFlow, OptionError> {
+        self.expected_hedge_flow_with(spot_move, vol_move, days, &ExposureConfig::default())
+    }
+
+    /// expected_hedge_flow() under the positioning assumption of the config.
+    pub fn expected_hedge_flow_with(
+        &self,
+        spot_move: FloatType,
+        vol_move: FloatType,
+        days: FloatType,
+        config: &ExposureConfig,
+    ) -> Result<HedgeFlow, OptionError> {
+        let mut flow = HedgeFlow::default();
+        let mut by_strike: BTreeMap<DecimalType, FloatType> = BTreeMap::new();
+        let contracts = contracts(self);
+        let skipped_strikes = skipped_strikes(&contracts, config.missing_oi);
+        for contract in contracts.iter() {
+            let tick = &contract.0[0];
+            if skipped_strikes.contains(&tick.strike) {
+                continue;
+            }
+            let sign = config.positioning.dealer_sign(contract);
+            // the flow is in units of the underlying whatever the unit of the config
+            let units = |greek| -> Result<FloatType, OptionError> {
+                Ok(-sign
+                    * unsigned_exposure(contract, greek, config.missing_oi, ExposureUnit::UnderlierUnits)?)
+            };
+            let gamma = units(Greek::Gamma)? * spot_move;
+            let vanna = units(Greek::Vanna)? * vol_move;
+            // dt is the tau that elapses over `days` under the day count of the contract
+            let start = tick.with_valuation_time(tick.valuation_time.unwrap_or_else(Utc::now));
+            let elapsed = start.tau() - start.with_days_elapsed(days).tau();
+            let charm = units(Greek::Charm)? * elapsed;
+            flow.gamma += gamma;
+            flow.vanna += vanna;
+            flow.charm += charm;
+            *by_strike.entry(tick.strike).or_default() += gamma + vanna + charm;
+        }
+        flow.total = flow.gamma + flow.vanna + flow.charm;
+        flow.by_strike = by_strike.into_iter().collect();
+        Ok(flow)
+    }
+
+    /// Charm exposure per strike, i.e. how the delta exposure at each strike drifts as time passes.
+    pub fn charm_exposure_profile(&self) -> Result<Vec<(DecimalType, FloatType)>, OptionError> {
+        self.exposure_profile(Greek::Charm)
+    }
+
+    /// Vanna exposure per strike, i.e. how the delta exposure at each strike moves with IV.
+    pub fn vanna_exposure_profile(&self) -> Result<Vec<(DecimalType, FloatType)>, OptionError> {
+        self.exposure_profile(Greek::Vanna)
+    }
+
+    /// The chain as it would be `days_forward` days later with unchanged spot and IV, i.e. with the valuation time moved forward
+    /// (see OptionTick::with_days_elapsed). Options that have expired by then are removed.
+    pub fn projected(&self, days_forward: FloatType) -> Self {
+        OptionChain(
+            self.0
+                .iter()
+                .map(|tick| tick.get_implied_volatility().with_days_elapsed(days_forward))
+                .filter(|tick| tick.tau() > 0.)
+                .collect(),
+        )
+    }
+
+    /// Exposure of the greek recomputed `days_forward` days later, with unchanged spot, IV and open interest.
+    pub fn project_exposure(
+        &self,
+        greek: Greek,
+        days_forward: FloatType,
+    ) -> Result<FloatType, OptionError> {
+        self.projected(days_forward)
+            .exposure_with(greek, &ExposureConfig::default())
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Exposure of the greek over all expiries under the positioning assumption of the config.
+    pub fn exposure_with(
+        &self,
+        greek: Greek,
+        config: &ExposureConfig,
+    ) -> Result<FloatType, OptionError> {
+        self.0
+            .iter()
+            .map(|chain| chain.exposure_with(greek, config))
+            .sum()
+    }
+
+    /// Exposure of the greek over all expiries recomputed `days_forward` days later. Expiries that have passed by then no longer contribute.
+    pub fn project_exposure(
+        &self,
+        greek: Greek,
+        days_forward: FloatType,
+    ) -> Result<FloatType, OptionError> {
+        self.0
+            .iter()
+            .map(|chain| chain.project_exposure(greek, days_forward))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn tick(side: OptionSide, iv: FloatType, volume: FloatType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(dec!(100))
+            .asset_price(100.)
+            .option_value(OptionValue::ImpliedVolatility(iv))
+            .maturity(now + Duration::days(30))
+            .option_type(OptionType::Call)
+            .side(side)
+            .additional_data(AdditionalOptionData::builder().open_interest(1000.).volume(volume).build())
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn open_interest_counts_once_per_contract() {
+        let mid = OptionChain(vec![tick(OptionSide::Bid, 0.2, 0.)]);
+        let quoted = OptionChain(vec![tick(OptionSide::Bid, 0.19, 0.), tick(OptionSide::Ask, 0.21, 0.)]);
+        let config = ExposureConfig::default();
+        let (single, both) = (
+            mid.exposure_with(Greek::Gamma, &config).unwrap(),
+            quoted.exposure_with(Greek::Gamma, &config).unwrap(),
+        );
+        assert_float_relative_eq!(single, both, 1e-2);
+        let flow = quoted.expected_hedge_flow(1., 0., 0.).unwrap();
+        assert_float_relative_eq!(flow.gamma, -single / 100., 1e-2);
+    }
+
+    #[test]
+    fn volume_by_side_sign() {
+        let chain = OptionChain(vec![tick(OptionSide::Bid, 0.2, 300.), tick(OptionSide::Ask, 0.2, 100.)]);
+        let long = chain.exposure_with(Greek::Gamma, &ExposureConfig::default()).unwrap();
+        let config = ExposureConfig::builder().positioning(PositioningAssumption::VolumeBySide).build();
+        assert_float_relative_eq!(chain.exposure_with(Greek::Gamma, &config).unwrap(), 0.5 * long, 1e-12);
+    }
+
+    #[test]
+    fn projection_advances_valuation_time() {
+        let chain = OptionChain(vec![tick(OptionSide::Bid, 0.2, 0.)]);
+        let projected = chain.projected(10.);
+        assert_eq!(projected.0[0].maturity, chain.0[0].maturity);
+        assert_eq!(projected.0[0].valuation_time, chain.0[0].valuation_time.map(|t| t + Duration::days(10)));
+        assert_float_relative_eq!(projected.0[0].tau(), 20. / 365., 1e-12);
+        assert!(chain.projected(31.).0.is_empty());
+    }
+
+    #[test]
+    fn charm_flow_follows_day_count() {
+        let act365 = OptionChain(vec![tick(OptionSide::Bid, 0.2, 0.)]);
+        let act360 = OptionChain(vec![OptionTick {
+            day_count: DayCount::Act360,
+            ..tick(OptionSide::Bid, 0.2, 0.)
+        }]);
+        let charm = |chain: &OptionChain<OptionTick>| {
+            chain.exposure_with(Greek::Charm, &ExposureConfig::default()).unwrap() / 100.
+        };
+        assert_float_relative_eq!(act365.expected_hedge_flow(0., 0., 1.).unwrap().charm, -charm(&act365) / 365., 1e-9);
+        assert_float_relative_eq!(act360.expected_hedge_flow(0., 0., 1.).unwrap().charm, -charm(&act360) / 360., 1e-9);
+    }
+}
@@ -0,0 +1,68 @@
+//! Live tick feeds.
+//!
+//! A TickSource is an async stream of FeedEvents, i.e. upserts and deletes of OptionTicks, which run_feed() applies to any CRUD
+//! container such as OptionBoard<StrikeBoard> or IndexedBoard. Any Stream of Result<FeedEvent> is a TickSource, so replayed data
+//! and exchange adapters are consumed the same way.
+//!
+//! A reference adapter for the Deribit websocket API is available with the `deribit` feature.
+//!
+//! # Example
+//! ```ignore
+//! let mut source = DeribitFeed::new(vec!["BTC-27DEC24-50000-C".into()]).connect().await?;
+//! let mut board = OptionBoard::<StrikeBoard>::new();
+//! run_feed(&mut source, &mut board).await?;
+//! ```
+
+use crate::models::*;
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+#[cfg(feature = "deribit")]
+mod deribit;
+#[cfg(feature = "deribit")]
+pub use deribit::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum FeedEvent {
+    Upsert(OptionTick),
+    Delete(OptionTick),
+}
+
+impl FeedEvent {
+    pub fn tick(&self) -> &OptionTick {
+        match self {
+            FeedEvent::Upsert(tick) | FeedEvent::Delete(tick) => tick,
+        }
+    }
+
+    pub fn apply<C: CRUD>(self, target: &mut C) -> Mutation {
+        match self {
+            FeedEvent::Upsert(tick) => target.upsert(tick),
+            FeedEvent::Delete(tick) => target.delete(tick),
+        }
+    }
+}
+
+pub trait TickSource: Stream<Item = Result<FeedEvent>> + Unpin {}
+
+impl<S: Stream<Item = Result<FeedEvent>> + Unpin> TickSource for S {}
+
+pub type BoxTickSource = Pin<Box<dyn Stream<Item = Result<FeedEvent>> + Send>>;
+
+/// Source upserting the given ticks in order, e.g. to replay recorded data through the same code path as a live feed.
+pub fn replay_source(ticks: impl IntoIterator<Item = OptionTick>) -> impl TickSource {
+    futures::stream::iter(ticks.into_iter().map(|tick| Ok(FeedEvent::Upsert(tick))))
+}
+
+/// Applies the events of the source to target until the source ends, and returns the number of events applied.
+/// Stops at the first error of the source.
+pub async fn run_feed<S: TickSource, C: CRUD>(source: &mut S, target: &mut C) -> Result<usize> {
+    let mut count = 0;
+    while let Some(event) = source.next().await {
+        event?.apply(target);
+        count += 1;
+    }
+    Ok(count)
+}
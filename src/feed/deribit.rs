@@ -0,0 +1,196 @@
+use super::{BoxTickSource, FeedEvent};
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::{SinkExt, StreamExt};
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+pub const DERIBIT_WS_URL: &str = "wss://www.deribit.com/ws/api/v2";
+
+/// Parses a Deribit option instrument name such as "BTC-27DEC24-50000-C" into (maturity, strike, option type).
+/// Deribit options expire at 08:00 UTC; a "d" in the strike is a decimal point, e.g. "XRP_USDC-27DEC24-0d5-C".
+pub fn parse_instrument(name: &str) -> Result<(DateTime<Utc>, DecimalType, OptionType)> {
+    let parts: Vec<&str> = name.split('-').collect();
+    ensure!(parts.len() == 4, "{} is not an option instrument", name);
+    let date = NaiveDate::parse_from_str(parts[1], "%d%b%y")?;
+    let maturity = date.and_hms_opt(8, 0, 0).unwrap().and_utc();
+    let strike = DecimalType::from_str(&parts[2].replace('d', "."))?;
+    let option_type = match parts[3] {
+        "C" => OptionType::Call,
+        "P" => OptionType::Put,
+        other => return Err(anyhow!("Unknown option type {}", other)),
+    };
+    Ok((maturity, strike, option_type))
+}
+
+/// `data` of a notification of the ticker.{instrument}.{interval} channel.
+/// IVs are in percent and amounts in contracts.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeribitTicker {
+    pub instrument_name: String,
+    /// Milliseconds since the epoch
+    pub timestamp: i64,
+    pub underlying_price: FloatType,
+    #[serde(default)]
+    pub interest_rate: FloatType,
+    #[serde(default)]
+    pub bid_iv: FloatType,
+    #[serde(default)]
+    pub ask_iv: FloatType,
+    #[serde(default)]
+    pub mark_iv: FloatType,
+    #[serde(default)]
+    pub best_bid_amount: FloatType,
+    #[serde(default)]
+    pub best_ask_amount: FloatType,
+    pub open_interest: Option<FloatType>,
+}
+
+impl DeribitTicker {
+    /// Best bid and ask as IV quoted ticks, priced with Black-76 on the underlying futures price.
+    /// The amount at the best price is stored as the volume of the tick. A side without a quote is None.
+    pub fn to_ticks(&self) -> Result<(Option<OptionTick>, Option<OptionTick>)> {
+        Ok((
+            self.tick(self.bid_iv, self.best_bid_amount, OptionSide::Bid)?,
+            self.tick(self.ask_iv, self.best_ask_amount, OptionSide::Ask)?,
+        ))
+    }
+
+    /// Mark IV published by Deribit as a tick with OptionSide::MarkPrice. None if the ticker has no mark.
+    pub fn mark_tick(&self) -> Result<Option<OptionTick>> {
+        self.tick(self.mark_iv, 0., OptionSide::MarkPrice)
+    }
+
+    fn tick(&self, iv: FloatType, amount: FloatType, side: OptionSide) -> Result<Option<OptionTick>> {
+        if iv <= 0. {
+            return Ok(None);
+        }
+        let (maturity, strike, option_type) = parse_instrument(&self.instrument_name)?;
+        let underlying = self.instrument_name.split('-').next().unwrap_or_default();
+        let valuation_time = DateTime::from_timestamp_millis(self.timestamp)
+            .ok_or_else(|| anyhow!("Invalid timestamp {}", self.timestamp))?;
+        let mut data = AdditionalOptionData::builder().volume(amount).build();
+        data.open_interest = self.open_interest;
+        let tick = OptionTick::builder()
+            .underlying(underlying)
+            .strike(strike)
+            .maturity(maturity)
+            .asset_price(self.underlying_price)
+            .risk_free_rate(self.interest_rate)
+            .option_type(option_type)
+            .option_value(OptionValue::ImpliedVolatility(iv / 100.))
+            .side(side)
+            .additional_data(data)
+            .pricing_model(PricingModel::Black76)
+            .valuation_time(valuation_time)
+            .build()
+            .validated()
+            .map_err(|e| anyhow!("Invalid ticker of {}: {}", self.instrument_name, e))?;
+        Ok(Some(tick))
+    }
+}
+
+/// Deribit websocket adapter streaming the best bid and ask and the mark of option instruments.
+///
+/// A StrikeBoard keeps one tick per quoted value and side, so when the best quote of a side moves the adapter emits
+/// a Delete of the previous quote followed by an Upsert of the new one, and the board always holds the current top of book.
+#[derive(Clone, Debug)]
+pub struct DeribitFeed {
+    pub url: String,
+    pub instruments: Vec<String>,
+    /// Notification interval of the ticker channel: "100ms", "agg2" or "raw" (raw requires authentication)
+    pub interval: String,
+}
+
+impl DeribitFeed {
+    pub fn new(instruments: Vec<String>) -> Self {
+        Self {
+            url: DERIBIT_WS_URL.to_string(),
+            instruments,
+            interval: "100ms".to_string(),
+        }
+    }
+
+    pub fn subscribe_request(&self) -> Value {
+        let channels: Vec<String> = self
+            .instruments
+            .iter()
+            .map(|instrument| format!("ticker.{}.{}", instrument, self.interval))
+            .collect();
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "public/subscribe",
+            "params": { "channels": channels },
+        })
+    }
+
+    /// Connects, subscribes to the ticker channels and returns the stream of events.
+    /// Messages other than ticker notifications are skipped.
+    pub async fn connect(&self) -> Result<BoxTickSource> {
+        let (mut ws, _) = connect_async(self.url.as_str()).await?;
+        ws.send(Message::Text(self.subscribe_request().to_string())).await?;
+        let mut quotes = QuoteState::default();
+        let events = ws.flat_map(move |message| {
+            let events = match message {
+                Ok(Message::Text(text)) => quotes.events(&text),
+                Ok(_) => Vec::new(),
+                Err(e) => vec![Err(e.into())],
+            };
+            futures::stream::iter(events)
+        });
+        Ok(Box::pin(events))
+    }
+}
+
+/// Last quote of each (instrument, side), used to delete quotes which are no longer the best.
+#[derive(Default)]
+struct QuoteState(HashMap<(String, OptionSide), OptionTick>);
+
+impl QuoteState {
+    fn events(&mut self, text: &str) -> Vec<Result<FeedEvent>> {
+        let message: Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => return vec![Err(e.into())],
+        };
+        if message["method"] != "subscription" {
+            return Vec::new();
+        }
+        let ticker = match DeribitTicker::deserialize(&message["params"]["data"]) {
+            Ok(ticker) => ticker,
+            Err(e) => return vec![Err(e.into())],
+        };
+        let ticks = ticker
+            .to_ticks()
+            .and_then(|(bid, ask)| Ok((bid, ask, ticker.mark_tick()?)));
+        let (bid, ask, mark) = match ticks {
+            Ok(ticks) => ticks,
+            Err(e) => return vec![Err(e)],
+        };
+        let mut events = Vec::new();
+        let quotes = [
+            (OptionSide::Bid, bid),
+            (OptionSide::Ask, ask),
+            (OptionSide::MarkPrice, mark),
+        ];
+        for (side, quote) in quotes {
+            let key = (ticker.instrument_name.clone(), side);
+            let previous = self.0.remove(&key);
+            if let Some(previous) = previous {
+                let unchanged = quote.as_ref().is_some_and(|q| q.option_value == previous.option_value);
+                if !unchanged {
+                    events.push(Ok(FeedEvent::Delete(previous)));
+                }
+            }
+            if let Some(quote) = quote {
+                events.push(Ok(FeedEvent::Upsert(quote.clone())));
+                self.0.insert(key, quote);
+            }
+        }
+        events
+    }
+}
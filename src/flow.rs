@@ -0,0 +1,217 @@
+//! Option volume profile and large-trade detection.
+//!
+//! Volume and open interest reported in AdditionalOptionData are cumulative, so the traded volume between two snapshots is the difference per contract.
+//! LargeTradeDetector compares the volume traded in each interval with the trailing distribution of that contract and reports spikes,
+//! flagging whether open interest grew accordingly (i.e. the block was likely opening new positions).
+//!
+//! Delta-adjusted volume indicators aggregate volume weighted by the delta of each contract, e.g. NOPE (net options pricing effect),
+//! `NOPE = sum(volume * delta) / underlying volume`, the share of the underlying volume that hedging of the day's option flow would represent.
+//!
+//! # Example
+//! ```ignore
+//! let detector = LargeTradeDetector::builder().lookback(20).z_threshold(4.).build();
+//! for event in detector.detect(&snapshots) {
+//!     println!("{:?}", event);
+//! }
+//! let nope = snapshots.map(|board| board.get_front_month().nope(underlying_volume));
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use typed_builder::TypedBuilder;
+
+type ContractKey = (DateTime<Utc>, DecimalType, OptionType);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrikeVolumeDelta {
+    pub maturity: DateTime<Utc>,
+    pub strike: DecimalType,
+    pub option_type: OptionType,
+    /// Volume traded since the previous snapshot.
+    pub volume: FloatType,
+    /// Change in open interest since the previous snapshot. None if open interest is not reported.
+    pub open_interest_change: Option<FloatType>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LargeTradeEvent {
+    /// Index of the snapshot in which the trade was detected.
+    pub index: usize,
+    pub maturity: DateTime<Utc>,
+    pub strike: DecimalType,
+    pub option_type: OptionType,
+    pub volume: FloatType,
+    /// Number of standard deviations above the trailing mean of the interval volume.
+    pub z_score: FloatType,
+    pub open_interest_change: Option<FloatType>,
+    /// True if open interest increased by at least `min_oi_ratio` of the volume.
+    pub oi_confirmed: bool,
+}
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct LargeTradeDetector {
+    /// Number of past intervals forming the trailing distribution.
+    #[builder(default = 20)]
+    pub lookback: usize,
+    #[builder(default = 3.)]
+    pub z_threshold: FloatType,
+    /// Minimum volume for an interval to be considered at all.
+    #[builder(default = 0.)]
+    pub min_volume: FloatType,
+    #[builder(default = 0.5)]
+    pub min_oi_ratio: FloatType,
+    /// If true, only events confirmed by open interest are reported.
+    #[builder(default = false)]
+    pub require_oi_confirmation: bool,
+}
+
+/// Volume and open interest per contract. When a contract is quoted on both sides, the first tick is used.
+fn contracts<'a>(
+    ticks: impl Iterator<Item = &'a OptionTick>,
+) -> BTreeMap<ContractKey, (OptionTick, AdditionalOptionData)> {
+    let mut map = BTreeMap::new();
+    for tick in ticks {
+        if let Some(data) = &tick.additional_data {
+            map.entry((tick.maturity, tick.strike, tick.option_type.clone()))
+                .or_insert_with(|| (tick.clone(), data.clone()));
+        }
+    }
+    map
+}
+
+fn board_ticks(board: &OptionBoard<OptionTick>) -> impl Iterator<Item = &OptionTick> {
+    board.0.iter().flat_map(|chain| chain.0.iter())
+}
+
+/// Sum of volume * delta per option type as (calls, puts).
+fn delta_volume<'a>(ticks: impl Iterator<Item = &'a OptionTick>) -> (FloatType, FloatType) {
+    contracts(ticks)
+        .values()
+        .filter_map(|(tick, data)| Some((tick, data.volume? * tick.get_implied_volatility().delta())))
+        .filter(|(_, v)| v.is_finite())
+        .fold((0., 0.), |(calls, puts), (tick, v)| match tick.option_type {
+            OptionType::Call => (calls + v, puts),
+            OptionType::Put => (calls, puts + v),
+        })
+}
+
+impl OptionChain<OptionTick> {
+    /// Delta-adjusted volume of the calls and puts as (calls, puts). The put value is negative.
+    pub fn delta_adjusted_volume(&self) -> (FloatType, FloatType) {
+        delta_volume(self.0.iter())
+    }
+
+    /// Net delta-adjusted volume: sum of volume * delta over all contracts.
+    pub fn net_delta_volume(&self) -> FloatType {
+        let (calls, puts) = self.delta_adjusted_volume();
+        calls + puts
+    }
+
+    /// Net options pricing effect: net delta-adjusted volume divided by the volume of the underlying.
+    /// Option volume multiplied by delta must be in the same unit as `underlying_volume` (scale by the contract multiplier beforehand if needed).
+    pub fn nope(&self, underlying_volume: FloatType) -> FloatType {
+        self.net_delta_volume() / underlying_volume
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Volume traded per contract since `previous`.
+    /// A decrease in the cumulative volume is treated as a session reset, in which case the current volume is used.
+    pub fn volume_delta(&self, previous: &Self) -> Vec<StrikeVolumeDelta> {
+        let before = contracts(board_ticks(previous));
+        contracts(board_ticks(self))
+            .into_iter()
+            .filter_map(|(key, (_, data))| {
+                let volume = data.volume?;
+                let prev = before.get(&key).map(|(_, d)| d);
+                let prev_volume = prev.and_then(|d| d.volume).unwrap_or(0.);
+                let open_interest_change = match (data.open_interest, prev.and_then(|d| d.open_interest)) {
+                    (Some(now), Some(then)) => Some(now - then),
+                    _ => None,
+                };
+                Some(StrikeVolumeDelta {
+                    maturity: key.0,
+                    strike: key.1,
+                    option_type: key.2,
+                    volume: if volume >= prev_volume { volume - prev_volume } else { volume },
+                    open_interest_change,
+                })
+            })
+            .collect()
+    }
+
+    /// Net delta-adjusted volume over all expiries.
+    pub fn net_delta_volume(&self) -> FloatType {
+        let (calls, puts) = delta_volume(board_ticks(self));
+        calls + puts
+    }
+
+    /// NOPE over all expiries.
+    pub fn nope(&self, underlying_volume: FloatType) -> FloatType {
+        self.net_delta_volume() / underlying_volume
+    }
+}
+
+impl TimeSeries<OptionBoard<OptionTick>> {
+    /// Volume deltas between consecutive snapshots. The i-th element corresponds to the interval ending at snapshot i + 1.
+    pub fn volume_profile(&self) -> TimeSeries<Vec<StrikeVolumeDelta>> {
+        TimeSeries(
+            self.0
+                .windows(2)
+                .map(|w| w[1].volume_delta(&w[0]))
+                .collect(),
+        )
+    }
+}
+
+impl LargeTradeDetector {
+    /// Scans a series of snapshots and returns the large trades in chronological order.
+    /// A contract needs `lookback` past intervals before it can be flagged.
+    pub fn detect(&self, snapshots: &TimeSeries<OptionBoard<OptionTick>>) -> Vec<LargeTradeEvent> {
+        let mut history: BTreeMap<ContractKey, Vec<FloatType>> = BTreeMap::new();
+        let mut events = Vec::new();
+
+        for (i, deltas) in snapshots.volume_profile().0.into_iter().enumerate() {
+            for delta in deltas {
+                let key = (delta.maturity, delta.strike, delta.option_type.clone());
+                let past = history.entry(key).or_default();
+
+                if past.len() >= self.lookback.max(2) && delta.volume >= self.min_volume {
+                    let window = &past[past.len() - self.lookback.max(2)..];
+                    let n = window.len() as FloatType;
+                    let mean = window.iter().sum::<FloatType>() / n;
+                    let std = (window.iter().map(|v| (v - mean).powi(2)).sum::<FloatType>() / (n - 1.)).sqrt();
+                    let z_score = if std > 0. {
+                        (delta.volume - mean) / std
+                    } else if delta.volume > mean {
+                        FloatType::INFINITY
+                    } else {
+                        0.
+                    };
+                    let oi_confirmed = delta
+                        .open_interest_change
+                        .is_some_and(|oi| oi >= self.min_oi_ratio * delta.volume);
+
+                    if z_score >= self.z_threshold && (oi_confirmed || !self.require_oi_confirmation) {
+                        events.push(LargeTradeEvent {
+                            index: i + 1,
+                            maturity: delta.maturity,
+                            strike: delta.strike,
+                            option_type: delta.option_type.clone(),
+                            volume: delta.volume,
+                            z_score,
+                            open_interest_change: delta.open_interest_change,
+                            oi_confirmed,
+                        });
+                    }
+                }
+                past.push(delta.volume);
+            }
+        }
+        events
+    }
+}
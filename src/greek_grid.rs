@@ -0,0 +1,110 @@
+//! Greeks of a board on a maturity × strike grid.
+//!
+//! greek_grid() evaluates one greek for every listed (maturity, strike) of an OptionBoard, using the OTM option of each strike
+//! so that every cell holds a single value. Strikes not listed for an expiry are NaN.
+//! The grid can be exported to CSV (one row per maturity), and to Parquet in long format with the `parquet` feature.
+//!
+//! # Example
+//! ```ignore
+//! let grid = board.greek_grid(Greek::Gamma);
+//! println!("gamma of the front month at the second strike: {}", grid.values[0][1]);
+//! std::fs::write("gamma.csv", grid.to_csv())?;
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::*;
+use crate::models::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GreekGrid {
+    pub greek: Greek,
+    /// Maturities in ascending order (rows)
+    pub maturities: Vec<DateTime<Utc>>,
+    /// Union of the strikes of every expiry in ascending order (columns)
+    pub strikes: Vec<FloatType>,
+    /// values[i][j] is the greek at maturities[i] and strikes[j], NaN if the strike is not listed
+    pub values: Vec<Vec<FloatType>>,
+}
+
+impl GreekGrid {
+    /// Value at the maturity and strike, None if either is not on the grid.
+    pub fn get(&self, maturity: DateTime<Utc>, strike: FloatType) -> Option<FloatType> {
+        let i = self.maturities.iter().position(|m| *m == maturity)?;
+        let j = self.strikes.iter().position(|k| *k == strike)?;
+        Some(self.values[i][j])
+    }
+
+    /// CSV with a header row `maturity,<strike>,...` and one row per maturity in RFC 3339.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("maturity");
+        for strike in self.strikes.iter() {
+            csv.push_str(&format!(",{}", strike));
+        }
+        csv.push('\n');
+        for (maturity, row) in self.maturities.iter().zip(self.values.iter()) {
+            csv.push_str(&maturity.to_rfc3339());
+            for value in row.iter() {
+                csv.push_str(&format!(",{}", value));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// (maturity, strike, value) of every cell with a value, row by row.
+    pub fn cells(&self) -> Vec<(DateTime<Utc>, FloatType, FloatType)> {
+        self.maturities
+            .iter()
+            .zip(self.values.iter())
+            .flat_map(|(maturity, row)| {
+                self.strikes
+                    .iter()
+                    .zip(row.iter())
+                    .filter(|(_, v)| !v.is_nan())
+                    .map(move |(k, v)| (*maturity, *k, *v))
+            })
+            .collect()
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    pub fn greek_grid(&self, greek: Greek) -> GreekGrid {
+        let board = self.sort_by_maturity();
+        let chains: Vec<OptionChain<OptionTick>> = board
+            .0
+            .iter()
+            .filter(|c| !c.0.is_empty())
+            .map(|c| c.otm())
+            .collect();
+        let mut strikes: Vec<FloatType> = chains
+            .iter()
+            .flat_map(|c| c.0.iter().map(|t| t.strike.to_f64().unwrap()))
+            .collect();
+        strikes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        strikes.dedup();
+
+        let maturities = chains.iter().map(|c| c.0[0].maturity).collect();
+        let values = chains
+            .iter()
+            .map(|chain| {
+                let mut row = vec![FloatType::NAN; strikes.len()];
+                for tick in chain.0.iter() {
+                    let strike = tick.strike.to_f64().unwrap();
+                    if let Some(j) = strikes.iter().position(|k| *k == strike) {
+                        row[j] = greek.of(&tick.get_implied_volatility());
+                    }
+                }
+                row
+            })
+            .collect();
+        GreekGrid {
+            greek,
+            maturities,
+            strikes,
+            values,
+        }
+    }
+}
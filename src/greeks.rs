@@ -11,9 +11,23 @@
 //! ```
 //! # Formula
 //! See EuropeanGreeks trait page.
+//!
+//! # Dispatch
+//! The greeks of an OptionTick follow its option_style, pricing_engine and pricing_model (see the Greeks trait):
+//! European options with the Analytic engine use the closed forms of their pricing model, while American options and
+//! the FiniteDifference engine use bump-and-reprice on the finite difference pricer, so `tick.delta()` of an American
+//! put includes its early exercise premium. Greeks that no engine computes for the tick are NaN, and GreeksError::NoEngine
+//! from Greeks::greek() and the try_* greeks, which go through it.
 
 use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+use paste::paste;
+use num_traits::Float;
+use std::fmt;
 use crate::black_scholes::*;
+use crate::numerical_greeks::has_numerical_engine;
 use crate::models::*;
 
 #[cfg_attr(doc, katexit::katexit)]
@@ -25,7 +39,7 @@ use crate::models::*;
 /// * r: risk free rate
 /// * q: dividend yield
 /// * $\sigma$: implied volatility
-pub trait EuropeanGreeks: BlackScholes {
+pub trait EuropeanGreeks<F: Float = FloatType>: BlackScholes<F> {
     /// Returns the delta of the option
     /// # Formula
     /// $$
@@ -34,14 +48,14 @@ pub trait EuropeanGreeks: BlackScholes {
     /// $$
     /// \Delta_p = e^{-q\tau }\Phi(-d_1)
     /// $$
-    fn delta(&self) -> FloatType;
+    fn delta(&self) -> F;
 
     /// Returns the gamma of the option
     /// # Formula
     /// $$
     /// \Gamma = \frac{e^{-q\tau }\phi(d_1)}{S_t\sigma\sqrt{\tau}}
     /// $$
-    fn gamma(&self) -> FloatType;
+    fn gamma(&self) -> F;
 
     /// Returns the theta of the option
     /// # Formula
@@ -51,7 +65,7 @@ pub trait EuropeanGreeks: BlackScholes {
     /// $$
     /// \Theta_p = -\frac{S_t\sigma e^{-q\tau }\phi(d_1)}{2\sqrt{\tau}} + rKe^{-r\tau }\Phi(-d_1) - qS_te^{-q\tau }\Phi(-d_1)
     /// $$
-    fn theta(&self) -> FloatType;
+    fn theta(&self) -> F;
 
     /// Returns the rho of the option
     /// # Formula
@@ -61,14 +75,14 @@ pub trait EuropeanGreeks: BlackScholes {
     /// $$
     /// \rho_p = -K\tau e^{-r\tau }\Phi(-d_2)
     /// $$
-    fn rho(&self) -> FloatType;
+    fn rho(&self) -> F;
 
     /// Returns the vega of the option
     /// # Formula
     /// $$
     /// \kappa = S_t\sqrt{\tau}e^{-q\tau }\phi(d_1)
     /// $$
-    fn vega(&self) -> FloatType;
+    fn vega(&self) -> F;
 
     /// Returns the epsilon of the option
     /// # Formula
@@ -78,14 +92,14 @@ pub trait EuropeanGreeks: BlackScholes {
     /// $$
     /// \epsilon_p = S_t \tau e^{-q\tau }\Phi(-d_2)
     /// $$
-    fn epsilon(&self) -> FloatType;
+    fn epsilon(&self) -> F;
 
     /// Returns the vanna of the option
     /// # Formula
     /// $$
     /// -e^{-q\tau} \phi(d_1) \frac{d_2}{d\sigma}
     /// $$
-    fn vanna(&self) -> FloatType;
+    fn vanna(&self) -> F;
 
     /// Returns the charm of the option
     /// # Formula
@@ -95,49 +109,49 @@ pub trait EuropeanGreeks: BlackScholes {
     /// $$
     /// p : -qe^{-q\tau}\Phi(-d_1) - e^{-q\tau} \phi(d_1) \frac{2(r-q)\tau - d_2\sigma\sqrt{\tau}}{2\tau\sigma\sqrt{\tau}}
     /// $$
-    fn charm(&self) -> FloatType;
+    fn charm(&self) -> F;
 
     /// Returns the vomma of the option
     /// # Formula
     /// $$
     /// S_te^{-q\tau}\phi(d_1)\sqrt{\tau} \frac{d_1*d_2}{\sigma}
     /// $$
-    fn vomma(&self) -> FloatType;
+    fn vomma(&self) -> F;
 
     /// Returns the veta of the option
     /// # Formula
     /// $$
     ///-Se^{-q\tau }\phi (d_{1}){\sqrt {\tau }}\left[q+{\frac {\left(r-q\right)d_{1}}{\sigma {\sqrt {\tau }}}}-{\frac {1+d_{1}d_{2}}{2\tau }}\right]
     /// $$
-    fn veta(&self) -> FloatType;
+    fn veta(&self) -> F;
 
     /// Returns the speed of the option
     /// # Formula
     /// $$
     /// -e^{-q\tau }{\frac {\phi (d_{1})}{S^{2}\sigma {\sqrt {\tau }}}}\left({\frac {d_{1}}{\sigma {\sqrt {\tau }}}}+1\right)=-{\frac {\Gamma }{S}}\left({\frac {d_{1}}{\sigma {\sqrt {\tau }}}}+1\right)
     /// $$
-    fn speed(&self) -> FloatType;
+    fn speed(&self) -> F;
 
     /// Returns the zomma of the option
     /// # Formula
     /// $$
     /// e^{-q\tau }{\frac {\phi (d_{1})\left(d_{1}d_{2}-1\right)}{S\sigma ^{2}{\sqrt {\tau }}}}=\Gamma {\frac {d_{1}d_{2}-1}{\sigma }}
     /// $$
-    fn zomma(&self) -> FloatType;
+    fn zomma(&self) -> F;
 
     /// Returns the color of the option
     /// # Formula
     /// $$
     ///  -e^{-q \tau} \frac{\phi(d_1)}{2S\tau \sigma \sqrt{\tau}} \left[2q\tau + 1 + \frac{2(r-q) \tau - d_2 \sigma \sqrt{\tau}}{\sigma \sqrt{\tau}}d_1 \right]
     /// $$
-    fn color(&self) -> FloatType;
+    fn color(&self) -> F;
 
     /// Returns the ultima of the option
     /// # Formula
     /// $$
     /// {\frac {-{\mathcal {V}}}{\sigma ^{2}}}\left[d_{1}d_{2}(1-d_{1}d_{2})+d_{1}^{2}+d_{2}^{2}\right]
     /// $$
-    fn ultima(&self) -> FloatType;
+    fn ultima(&self) -> F;
 
     /// Returns the dual_delta of the option
     /// # Formula
@@ -145,283 +159,554 @@ pub trait EuropeanGreeks: BlackScholes {
     /// c:  -e^{-r \tau} \Phi(d_2)   \\\\
     /// p:  e^{-r \tau} \Phi(-d_2)
     /// $$
-    fn dual_delta(&self) -> FloatType;
+    fn dual_delta(&self) -> F;
 
     /// Returns the dual_gamma of the option
     /// # Formula
     /// $$
     ///  e^{-r \tau} \frac{\phi(d_2)}{K\sigma\sqrt{\tau}}
     /// $$
-    fn dual_gamma(&self) -> FloatType;
+    fn dual_gamma(&self) -> F;
+
+    /// Returns the lambda of the option, also called omega or elasticity: the percentage change of the value of the option
+    /// per percentage change of the spot
+    /// # Formula
+    /// $$
+    /// \lambda = \Delta \frac{S_t}{V}
+    /// $$
+    fn lambda(&self) -> F;
+
+    /// Returns the change of vega as time passes (per year), i.e. minus veta
+    /// # Formula
+    /// $$
+    /// \frac{\partial \kappa}{\partial t} = -\frac{\partial \kappa}{\partial \tau}
+    /// $$
+    fn dvega_dtime(&self) -> F;
+
+    /// Returns the theta of the option in the time unit of the convention, e.g. the decay per calendar day
+    fn theta_with(&self, convention: ThetaConvention) -> F {
+        self.theta() * c::<F>(convention.factor())
+    }
 }
 
-impl EuropeanGreeks for OptionTick {
-    fn delta(&self) -> FloatType {
+/// Time unit of theta. EuropeanGreeks::theta() is per year.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThetaConvention {
+    #[default]
+    PerYear,
+    /// Per day of a 365-day year
+    PerCalendarDay,
+    /// Per day of a 252-day trading year
+    PerTradingDay,
+}
+
+impl ThetaConvention {
+    /// Factor the theta per year is multiplied by.
+    pub fn factor(&self) -> f64 {
+        match self {
+            ThetaConvention::PerYear => 1.,
+            ThetaConvention::PerCalendarDay => 1. / 365.,
+            ThetaConvention::PerTradingDay => 1. / 252.,
+        }
+    }
+}
+
+/// Converts a constant of the formulas into F.
+fn c<F: Float>(x: f64) -> F {
+    F::from(x).unwrap()
+}
+
+impl<F: Float> EuropeanGreeks<F> for BsParams<F> {
+    fn delta(&self) -> F {
         let d1 = self.d1();
         match self.option_type {
-            OptionType::Call => (-self.dividend_yield * self.risk_free_rate).exp() * Self::Phi(&d1),
-            OptionType::Put => {
-                -(-self.dividend_yield * self.risk_free_rate).exp() * Self::Phi(&(-d1))
-            }
+            OptionType::Call => self.carry_discount_factor() * Self::Phi(&d1),
+            OptionType::Put => -self.carry_discount_factor() * Self::Phi(&(-d1)),
         }
     }
 
-    fn gamma(&self) -> FloatType {
+    fn gamma(&self) -> F {
         let d1 = self.d1();
-        let tau = self.tau();
-        let implied_volatility: FloatType = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
-
-        (-self.dividend_yield * self.risk_free_rate).exp() * Self::phi(&d1)
-            / (self.asset_price * implied_volatility * tau.sqrt())
+        self.carry_discount_factor() * Self::phi(&d1) / (self.spot * self.sigma * self.tau.sqrt())
     }
 
-    fn theta(&self) -> FloatType {
+    fn theta(&self) -> F {
         let d1 = self.d1();
         let d2 = self.d2();
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
-
+        let decay = -self.carry_discount_factor() * self.spot * Self::phi(&d1) * self.sigma
+            / (c::<F>(2.) * self.tau.sqrt());
         match self.option_type {
             OptionType::Call => {
-                -(-self.dividend_yield * self.risk_free_rate).exp()
-                    * self.asset_price
-                    * Self::phi(&d1)
-                    * implied_volatility
-                    / (2.0 * tau.sqrt())
-                    - self.risk_free_rate
-                    * self.strike.to_f64().unwrap()
-                    * (-self.risk_free_rate * tau).exp()
-                    * Self::Phi(&d2)
-                    + self.dividend_yield
-                        * self.asset_price
-                        * (-self.dividend_yield * tau).exp()
-                        * Self::Phi(&d1)
+                decay - self.rate * self.strike * self.discount_factor() * Self::Phi(&d2)
+                    + self.carry * self.spot * self.carry_discount_factor() * Self::Phi(&d1)
             }
             OptionType::Put => {
-                -(-self.dividend_yield * self.risk_free_rate).exp()
-                    * self.asset_price
-                    * Self::phi(&d1)
-                    * implied_volatility
-                    / (2.0 * tau.sqrt())
-                    + self.risk_free_rate
-                    * self.strike.to_f64().unwrap()
-                    * (-self.risk_free_rate * tau).exp()
-                    * Self::Phi(&(-d2))
-                    - self.dividend_yield
-                        * self.asset_price
-                        * (-self.dividend_yield * tau).exp()
-                        * Self::Phi(&(-d1))
+                decay + self.rate * self.strike * self.discount_factor() * Self::Phi(&(-d2))
+                    - self.carry * self.spot * self.carry_discount_factor() * Self::Phi(&(-d1))
             }
         }
     }
 
-    fn rho(&self) -> FloatType {
+    fn rho(&self) -> F {
         let d2 = self.d2();
-        let tau = self.tau();
         match self.option_type {
-            OptionType::Call => {
-                tau * self.strike.to_f64().unwrap() * (-self.risk_free_rate * tau).exp() * Self::Phi(&d2)
-            }
-            OptionType::Put => {
-                -tau * self.strike.to_f64().unwrap() * (-self.risk_free_rate * tau).exp() * Self::Phi(&(-d2))
-            }
+            OptionType::Call => self.tau * self.strike * self.discount_factor() * Self::Phi(&d2),
+            OptionType::Put => -self.tau * self.strike * self.discount_factor() * Self::Phi(&(-d2)),
         }
     }
 
-    fn vega(&self) -> FloatType {
+    fn vega(&self) -> F {
         let d1 = self.d1();
-
-        let tau = self.tau();
-
-        (-self.dividend_yield * self.risk_free_rate).exp()
-            * self.asset_price
-            * Self::phi(&d1)
-            * tau.sqrt()
+        self.carry_discount_factor() * self.spot * Self::phi(&d1) * self.tau.sqrt()
     }
 
-    fn veta(&self) -> FloatType {
+    fn veta(&self) -> F {
         let d1 = self.d1();
         let d2 = self.d2();
-
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
-
-        -self.asset_price
-            * (-self.dividend_yield * tau).exp()
-            * Self::Phi(&d1)
-            * tau.sqrt()
-            * (self.dividend_yield
-                + (self.risk_free_rate - self.dividend_yield) * d1
-                    / (implied_volatility * tau.sqrt())
-                - (1. + d1 * d2) / (2. * tau))
+        let sqrt_tau = self.tau.sqrt();
+        -self.spot
+            * self.carry_discount_factor()
+            * Self::phi(&d1)
+            * sqrt_tau
+            * (self.carry + (self.rate - self.carry) * d1 / (self.sigma * sqrt_tau)
+                - (F::one() + d1 * d2) / (c::<F>(2.) * self.tau))
     }
 
-    fn vanna(&self) -> FloatType {
+    fn vanna(&self) -> F {
         let d1 = self.d1();
         let d2 = self.d2();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
-
-        -(-self.dividend_yield * self.risk_free_rate).exp() * Self::phi(&d1) * d2
-            / implied_volatility
+        -self.carry_discount_factor() * Self::phi(&d1) * d2 / self.sigma
     }
 
-    fn charm(&self) -> FloatType {
+    fn charm(&self) -> F {
         let d1 = self.d1();
         let d2 = self.d2();
-
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
-
+        let sqrt_tau = self.tau.sqrt();
+        let drift = (c::<F>(2.) * (self.rate - self.carry) * self.tau - d2 * self.sigma * sqrt_tau)
+            / (c::<F>(2.) * self.tau * self.sigma * sqrt_tau);
         match self.option_type {
             OptionType::Call => {
-                self.dividend_yield * (-self.dividend_yield * tau).exp() * Self::Phi(&d1)
-                    - (-self.dividend_yield * self.risk_free_rate).exp()
-                        * Self::phi(&d1)
-                        * (2. * (self.risk_free_rate - self.dividend_yield) * tau
-                            - d2 * implied_volatility * tau.sqrt())
-                        / (2. * tau * implied_volatility * tau.sqrt())
+                self.carry * self.carry_discount_factor() * Self::Phi(&d1)
+                    - self.carry_discount_factor() * Self::phi(&d1) * drift
             }
             OptionType::Put => {
-                -self.dividend_yield * (-self.dividend_yield * tau).exp() * Self::Phi(&(-d1))
-                    - (-self.dividend_yield * self.risk_free_rate).exp()
-                        * Self::phi(&(-d1))
-                        * (2. * (self.risk_free_rate - self.dividend_yield) * tau
-                            - d2 * implied_volatility * tau.sqrt())
-                        / (2. * tau * implied_volatility * tau.sqrt())
+                -self.carry * self.carry_discount_factor() * Self::Phi(&(-d1))
+                    - self.carry_discount_factor() * Self::phi(&(-d1)) * drift
             }
         }
     }
 
-    fn vomma(&self) -> FloatType {
+    fn vomma(&self) -> F {
         let d1 = self.d1();
         let d2 = self.d2();
+        self.carry_discount_factor() * self.spot * Self::phi(&d1) * self.tau.sqrt() * d1 * d2
+            / self.sigma
+    }
 
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
+    fn speed(&self) -> F {
+        let d1 = self.d1();
+        -self.gamma() / self.spot * (d1 / (self.sigma * self.tau.sqrt()) + F::one())
+    }
 
-        (-self.dividend_yield * self.risk_free_rate).exp()
-            * self.asset_price
-            * Self::phi(&d1)
-            * tau.sqrt()
-            * d1
-            * d2
-            / implied_volatility
+    fn zomma(&self) -> F {
+        let d1 = self.d1();
+        let d2 = self.d2();
+        self.gamma() * (d1 * d2 - F::one()) / self.sigma
     }
 
-    fn speed(&self) -> FloatType {
+    fn color(&self) -> F {
         let d1 = self.d1();
+        let d2 = self.d2();
+        let two = c::<F>(2.);
+        let sqrt_tau = self.tau.sqrt();
+        -self.carry_discount_factor() * Self::phi(&d1)
+            / (two * self.spot * self.sigma * self.tau * sqrt_tau)
+            * (two * self.carry * self.tau
+                + F::one()
+                + d1 * (two * (self.rate - self.carry) * self.tau - d2 * self.sigma * sqrt_tau)
+                    / (self.sigma * sqrt_tau))
+    }
 
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
+    fn ultima(&self) -> F {
+        let d1 = self.d1();
+        let d2 = self.d2();
+        -self.vega() / (self.sigma * self.sigma)
+            * (d1 * d2 * (F::one() - d1 * d2) + d1 * d1 + d2 * d2)
+    }
 
-        let gamma = self.gamma();
+    fn epsilon(&self) -> F {
+        let d1 = self.d1();
+        match self.option_type {
+            OptionType::Call => -self.spot * self.tau * self.carry_discount_factor() * Self::Phi(&d1),
+            OptionType::Put => self.spot * self.tau * self.carry_discount_factor() * Self::Phi(&(-d1)),
+        }
+    }
 
-        -gamma / self.asset_price * (d1 / (implied_volatility * tau.sqrt()) + 1.)
+    fn dual_delta(&self) -> F {
+        let d2 = self.d2();
+        match self.option_type {
+            OptionType::Call => -self.discount_factor() * Self::Phi(&d2),
+            OptionType::Put => self.discount_factor() * Self::Phi(&(-d2)),
+        }
     }
 
-    fn zomma(&self) -> FloatType {
-        let d1 = self.d1();
+    fn dual_gamma(&self) -> F {
         let d2 = self.d2();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
+        self.discount_factor() * Self::phi(&d2) / (self.strike * self.sigma * self.tau.sqrt())
+    }
 
-        let gamma = self.gamma();
+    fn lambda(&self) -> F {
+        self.delta() * self.spot / self.theoretical_price()
+    }
 
-        gamma * (d1 * d2 - 1.) / implied_volatility
+    fn dvega_dtime(&self) -> F {
+        -self.veta()
     }
+}
 
-    fn color(&self) -> FloatType {
-        let d1 = self.d1();
-        let d2 = self.d2();
+macro_rules! tick_greeks {
+    ($(($greeks_name:ident, $greek:ident)),*) => {
+        /// The greeks of a tick are computed by the method of greeks_method(): those of its BsParams (BachelierParams
+        /// under PricingModel::Bachelier) when analytic, by bump-and-reprice when numerical. Near expiry their limits are returned.
+        impl EuropeanGreeks for OptionTick {
+            $(
+                fn $greeks_name(&self) -> FloatType {
+                    if let Some(limit) = self.expiry_limit(Greek::$greek) {
+                        return limit;
+                    }
+                    match self.greeks_method() {
+                        Ok(GreeksMethod::Analytic) if self.pricing_model == PricingModel::Bachelier => {
+                            self.bachelier_params().greek(Greek::$greek)
+                        }
+                        Ok(GreeksMethod::Analytic) => self.bs_params().$greeks_name(),
+                        Ok(GreeksMethod::Numerical) => {
+                            self.numerical_greek(Greek::$greek).unwrap_or(FloatType::NAN)
+                        }
+                        Err(_) => FloatType::NAN,
+                    }
+                }
+            )*
+        }
+    };
+}
 
-        let tau = self.tau();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
+tick_greeks!(
+    (delta, Delta),
+    (gamma, Gamma),
+    (theta, Theta),
+    (rho, Rho),
+    (vega, Vega),
+    (epsilon, Epsilon),
+    (vanna, Vanna),
+    (charm, Charm),
+    (vomma, Vomma),
+    (veta, Veta),
+    (speed, Speed),
+    (zomma, Zomma),
+    (color, Color),
+    (ultima, Ultima),
+    (dual_delta, DualDelta),
+    (dual_gamma, DualGamma),
+    (lambda, Lambda),
+    (dvega_dtime, DvegaDtime)
+);
+
+/// Identifies one of the greeks of EuropeanGreeks, e.g. to select the greek of a ladder.
+/// A Greek can also be parsed from its name (see Greek::name), so that ladders and grids can be configured by name:
+/// `"vanna".parse::<Greek>()?`. Parsing ignores case, underscores and dashes, and accepts the aliases volga, omega,
+/// elasticity and psi.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Greek {
+    Delta,
+    Gamma,
+    Theta,
+    Rho,
+    Vega,
+    Epsilon,
+    Vanna,
+    Charm,
+    /// Also called volga
+    Vomma,
+    Veta,
+    Speed,
+    Zomma,
+    Color,
+    Ultima,
+    DualDelta,
+    DualGamma,
+    /// Also called omega or elasticity
+    Lambda,
+    DvegaDtime,
+}
 
-        -(-self.dividend_yield * self.risk_free_rate).exp() * Self::phi(&d1)
-            / (2. * self.asset_price * implied_volatility * tau * tau.sqrt())
-            * (2. * self.risk_free_rate * tau
-                + 1.
-                + d1 * (2. * (self.risk_free_rate - self.dividend_yield) * tau
-                    - d2 * implied_volatility * tau.sqrt())
-                    / (implied_volatility * tau.sqrt()))
+impl Greek {
+    pub const ALL: [Greek; 18] = [
+        Greek::Delta,
+        Greek::Gamma,
+        Greek::Theta,
+        Greek::Rho,
+        Greek::Vega,
+        Greek::Epsilon,
+        Greek::Vanna,
+        Greek::Charm,
+        Greek::Vomma,
+        Greek::Veta,
+        Greek::Speed,
+        Greek::Zomma,
+        Greek::Color,
+        Greek::Ultima,
+        Greek::DualDelta,
+        Greek::DualGamma,
+        Greek::Lambda,
+        Greek::DvegaDtime,
+    ];
+
+    /// Name of the greek, the name of its method in EuropeanGreeks.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Greek::Delta => "delta",
+            Greek::Gamma => "gamma",
+            Greek::Theta => "theta",
+            Greek::Rho => "rho",
+            Greek::Vega => "vega",
+            Greek::Epsilon => "epsilon",
+            Greek::Vanna => "vanna",
+            Greek::Charm => "charm",
+            Greek::Vomma => "vomma",
+            Greek::Veta => "veta",
+            Greek::Speed => "speed",
+            Greek::Zomma => "zomma",
+            Greek::Color => "color",
+            Greek::Ultima => "ultima",
+            Greek::DualDelta => "dual_delta",
+            Greek::DualGamma => "dual_gamma",
+            Greek::Lambda => "lambda",
+            Greek::DvegaDtime => "dvega_dtime",
+        }
     }
 
-    fn ultima(&self) -> FloatType {
-        let d1 = self.d1();
-        let d2 = self.d2();
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
+    /// Returns the value of this greek for the option.
+    pub fn of<T: EuropeanGreeks>(&self, option: &T) -> FloatType {
+        match self {
+            Greek::Delta => option.delta(),
+            Greek::Gamma => option.gamma(),
+            Greek::Theta => option.theta(),
+            Greek::Rho => option.rho(),
+            Greek::Vega => option.vega(),
+            Greek::Epsilon => option.epsilon(),
+            Greek::Vanna => option.vanna(),
+            Greek::Charm => option.charm(),
+            Greek::Vomma => option.vomma(),
+            Greek::Veta => option.veta(),
+            Greek::Speed => option.speed(),
+            Greek::Zomma => option.zomma(),
+            Greek::Color => option.color(),
+            Greek::Ultima => option.ultima(),
+            Greek::DualDelta => option.dual_delta(),
+            Greek::DualGamma => option.dual_gamma(),
+            Greek::Lambda => option.lambda(),
+            Greek::DvegaDtime => option.dvega_dtime(),
+        }
+    }
+}
+
+impl fmt::Display for Greek {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Greek {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let key = |name: &str| -> String {
+            name.chars()
+                .filter(|c| !matches!(c, '_' | '-' | ' '))
+                .collect::<String>()
+                .to_lowercase()
         };
+        let key_s = key(s);
+        match key_s.as_str() {
+            "volga" => return Ok(Greek::Vomma),
+            "omega" | "elasticity" => return Ok(Greek::Lambda),
+            "psi" => return Ok(Greek::Epsilon),
+            _ => {}
+        }
+        Greek::ALL
+            .iter()
+            .find(|greek| key(greek.name()) == key_s)
+            .copied()
+            .ok_or_else(|| anyhow!("Unknown greek: {}", s))
+    }
+}
 
-        let vega = self.vega();
+/// Below this time to maturity in years (one second), the greeks return their limits at expiry.
+pub const EXPIRY_TAU: FloatType = 1. / 31536000.;
 
-        -vega / (implied_volatility * implied_volatility)
-            * (d1 * d2 * (1. - d1 * d2) + d1 * d1 + d2 * d2)
+impl OptionTick {
+    /// Limit of the greek at expiry when the option is (about to be) expired, None otherwise.
+    /// Delta and dual delta become the exercise indicator (1/2 at the money); every other greek is 0.
+    fn expiry_limit(&self, greek: Greek) -> Option<FloatType> {
+        if self.tau() > EXPIRY_TAU {
+            return None;
+        }
+        let strike = self.strike.to_f64().unwrap();
+        let in_the_money = match (self.asset_price - strike).partial_cmp(&0.)? {
+            std::cmp::Ordering::Greater => 1.,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.,
+        };
+        let exercised = match self.option_type {
+            OptionType::Call => in_the_money == 1.,
+            OptionType::Put => in_the_money == 0.,
+        };
+        Some(match (greek, &self.option_type) {
+            // the value is the intrinsic value, with a delta of 1 (call) or -1 (put) in the money
+            (Greek::Lambda, _) if exercised => self.asset_price / (self.asset_price - strike),
+            (Greek::Delta, OptionType::Call) => in_the_money,
+            (Greek::Delta, OptionType::Put) => in_the_money - 1.,
+            (Greek::DualDelta, OptionType::Call) => -in_the_money,
+            (Greek::DualDelta, OptionType::Put) => 1. - in_the_money,
+            _ => 0.,
+        })
     }
 
-    fn epsilon(&self) -> FloatType {
-        let d1 = self.d1();
+    /// Hours to expiry measured with the day count of the tick, i.e. trading hours for DayCount::Exchange.
+    pub fn hours_to_expiry(&self) -> FloatType {
+        self.tau() * self.day_count.hours_per_year()
+    }
+
+    /// Theta per hour measured with the day count of the tick, i.e. decay per trading hour for DayCount::Exchange.
+    /// Useful for weeklies and 0DTE options, where the decay per day hides how it accelerates within the session.
+    pub fn intraday_theta(&self) -> FloatType {
+        self.theta() / self.day_count.hours_per_year()
+    }
+}
 
-        let tau = self.tau();
+/// Reason a greek cannot be computed, returned by the try_* greeks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GreeksError {
+    /// option_value holds a price; convert it with get_implied_volatility() first
+    MissingIv,
+    /// The option has reached its maturity
+    ExpiredOption,
+    /// The implied volatility is zero, negative or NaN
+    NonPositiveVol,
+    /// No engine computes the greek for the option style and pricing model of the option
+    NoEngine,
+}
 
-        match self.option_type {
-            OptionType::Call => {
-                -self.asset_price * tau * (-self.dividend_yield * tau).exp() * Self::Phi(&d1)
-            }
-            OptionType::Put => {
-                self.asset_price * tau * (-self.dividend_yield * tau).exp() * Self::Phi(&(-d1))
+impl fmt::Display for GreeksError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GreeksError::MissingIv => write!(f, "The option value is a price, not an implied volatility."),
+            GreeksError::ExpiredOption => write!(f, "The option has expired."),
+            GreeksError::NonPositiveVol => write!(f, "The implied volatility is not positive."),
+            GreeksError::NoEngine => {
+                write!(f, "No engine computes this greek for the option style and pricing model.")
             }
         }
     }
+}
 
-    fn dual_delta(&self) -> FloatType {
-        let tau = self.tau();
-        let d2 = self.d2();
+impl std::error::Error for GreeksError {}
 
-        match self.option_type {
-            OptionType::Call => -(-self.risk_free_rate * tau).exp() * Self::Phi(&d2),
-            OptionType::Put => (-self.risk_free_rate * tau).exp() * Self::Phi(&(-d2)),
+impl OptionTick {
+    /// Checks that the greeks of the tick are well defined.
+    pub fn check_greeks(&self) -> Result<(), GreeksError> {
+        let iv = match self.option_value {
+            OptionValue::Price(_) => return Err(GreeksError::MissingIv),
+            OptionValue::ImpliedVolatility(iv) => iv,
+        };
+        if iv.is_nan() || iv <= 0. {
+            return Err(GreeksError::NonPositiveVol);
         }
+        if self.tau() <= 0. {
+            return Err(GreeksError::ExpiredOption);
+        }
+        Ok(())
     }
+}
 
-    fn dual_gamma(&self) -> FloatType {
-        let implied_volatility = match self.option_value {
-            OptionValue::Price(_) => FloatType::NAN,
-            OptionValue::ImpliedVolatility(iv) => iv,
-        };
+macro_rules! try_greeks {
+    ($(($greeks_name:ident, $greek:ident)),*) => {
+        impl OptionTick {
+            $(
+                paste! {
+                    /// Same as the greek of EuropeanGreeks, but returns a GreeksError instead of a NaN (see Greeks::greek()).
+                    pub fn [<try_ $greeks_name>](&self) -> Result<FloatType, GreeksError> {
+                        self.greek(Greek::$greek)
+                    }
+                }
+            )*
+        }
+    };
+}
 
-        let d2 = self.d2();
+try_greeks!(
+    (delta, Delta),
+    (gamma, Gamma),
+    (theta, Theta),
+    (rho, Rho),
+    (vega, Vega),
+    (epsilon, Epsilon),
+    (vanna, Vanna),
+    (charm, Charm),
+    (vomma, Vomma),
+    (veta, Veta),
+    (speed, Speed),
+    (zomma, Zomma),
+    (color, Color),
+    (ultima, Ultima),
+    (dual_delta, DualDelta),
+    (dual_gamma, DualGamma),
+    (lambda, Lambda),
+    (dvega_dtime, DvegaDtime)
+);
+
+impl Greek {
+    /// Same as of(), but returns a GreeksError instead of a NaN.
+    pub fn try_of(&self, tick: &OptionTick) -> Result<FloatType, GreeksError> {
+        tick.greek(*self)
+    }
+}
+
+/// How the greeks of an option are computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GreeksMethod {
+    /// Closed forms of the pricing model
+    Analytic,
+    /// Bump-and-reprice with the pricing engine, see OptionTick::numerical_greek()
+    Numerical,
+}
+
+/// Greeks of an option computed with the method its style, engine and model call for.
+pub trait Greeks {
+    fn greeks_method(&self) -> Result<GreeksMethod, GreeksError>;
+
+    /// Value of the greek, or the reason it cannot be computed.
+    fn greek(&self, greek: Greek) -> Result<FloatType, GreeksError>;
+}
+
+impl Greeks for OptionTick {
+    /// Analytic for European options with the Analytic engine, numerical on the finite difference pricer otherwise.
+    /// The finite difference pricer is lognormal, so an American or finite difference tick under PricingModel::Bachelier
+    /// has no engine.
+    fn greeks_method(&self) -> Result<GreeksMethod, GreeksError> {
+        match (&self.option_style, &self.pricing_engine, &self.pricing_model) {
+            (OptionStyle::European, PricingEngine::Analytic, _) => Ok(GreeksMethod::Analytic),
+            (_, _, PricingModel::Bachelier) => Err(GreeksError::NoEngine),
+            _ => Ok(GreeksMethod::Numerical),
+        }
+    }
 
-        let tau = self.tau();
-        (-self.risk_free_rate * tau).exp() * Self::phi(&d2)
-            / (self.strike.to_f64().unwrap() * implied_volatility * tau.sqrt())
+    fn greek(&self, greek: Greek) -> Result<FloatType, GreeksError> {
+        self.check_greeks()?;
+        if self.greeks_method()? == GreeksMethod::Numerical && !has_numerical_engine(greek) {
+            return Err(GreeksError::NoEngine);
+        }
+        Ok(greek.of(self))
     }
 }
 
@@ -469,4 +754,68 @@ mod tests {
         assert_float_relative_eq!(option.rho(), -19.7285, 0.001);
         assert_float_relative_eq!(option.vega(), 6.151, 0.001);
     }
+
+    #[test]
+    fn greeks_with_dividend_yield_match_finite_differences() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let option = OptionTick::builder()
+            .strike(dec!(110))
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.03)
+            .option_value(OptionValue::ImpliedVolatility(0.25))
+            .maturity(now + chrono::Duration::days(180))
+            .option_type(OptionType::Put)
+            .valuation_time(now)
+            .build();
+        let price = |tick: &OptionTick| tick.get_theoretical_price().get_value();
+        let bumped = |f: &dyn Fn(&mut OptionTick)| {
+            let mut tick = option.clone();
+            f(&mut tick);
+            tick
+        };
+        let spot = |h: FloatType| bumped(&|t| t.asset_price += h);
+        let vol = |h: FloatType| bumped(&|t| t.option_value = OptionValue::ImpliedVolatility(0.25 + h));
+        let rate = |h: FloatType| bumped(&|t| t.risk_free_rate += h);
+        let yield_ = |h: FloatType| bumped(&|t| t.dividend_yield += h);
+        let days = |d: i64| bumped(&|t| t.maturity += chrono::Duration::days(d));
+        let (h, dtau) = (1e-4, days(1).tau() - option.tau());
+
+        assert_float_relative_eq!(option.delta(), (price(&spot(h)) - price(&spot(-h))) / (2. * h), 1e-6);
+        assert_float_relative_eq!(
+            option.gamma(),
+            (price(&spot(0.01)) - 2. * price(&option) + price(&spot(-0.01))) / 1e-4,
+            1e-4
+        );
+        assert_float_relative_eq!(option.vega(), (price(&vol(h)) - price(&vol(-h))) / (2. * h), 1e-6);
+        assert_float_relative_eq!(option.rho(), (price(&rate(h)) - price(&rate(-h))) / (2. * h), 1e-6);
+        assert_float_relative_eq!(option.epsilon(), (price(&yield_(h)) - price(&yield_(-h))) / (2. * h), 1e-6);
+        assert_float_relative_eq!(option.theta(), -(price(&days(1)) - price(&days(-1))) / (2. * dtau), 1e-4);
+        assert_float_relative_eq!(option.vanna(), (vol(h).delta() - vol(-h).delta()) / (2. * h), 1e-5);
+        assert_float_relative_eq!(option.speed(), (spot(0.01).gamma() - spot(-0.01).gamma()) / 0.02, 1e-4);
+        assert_float_relative_eq!(option.charm(), -(days(1).delta() - days(-1).delta()) / (2. * dtau), 1e-3);
+        assert_float_relative_eq!(option.color(), (days(1).gamma() - days(-1).gamma()) / (2. * dtau), 1e-3);
+    }
+
+    #[test]
+    fn try_greeks_follow_greeks_method() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let mut option = OptionTick::builder()
+            .strike(dec!(100))
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + chrono::Duration::days(90))
+            .option_type(OptionType::Put)
+            .option_style(OptionStyle::American)
+            .valuation_time(now)
+            .build();
+        assert_eq!(option.try_vomma(), Err(GreeksError::NoEngine));
+        assert_eq!(Greek::Vomma.try_of(&option), Err(GreeksError::NoEngine));
+        assert!(option.try_delta().unwrap().is_finite());
+
+        option.pricing_model = PricingModel::Bachelier;
+        option.option_value = OptionValue::ImpliedVolatility(20.);
+        assert_eq!(option.try_delta(), Err(GreeksError::NoEngine));
+    }
 }
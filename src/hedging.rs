@@ -0,0 +1,150 @@
+//! No-transaction bands for delta hedging with proportional transaction costs.
+//!
+//! Under proportional costs, rebalancing to the Black-Scholes delta at every step is suboptimal.
+//! Utility-based hedging instead keeps the hedge ratio inside a band around a target and, when it leaves the band, trades only back to the nearest edge.
+//! * Whalley-Wilmott: asymptotic band `delta +- (3/2 * e^{-r tau} * lambda * S * Gamma^2 / gamma)^{1/3}`
+//! * Zakamouline: band around the delta at a modified volatility, with a half width `H_0 + H_w` fitted to the numerical optimum
+//!
+//! Here lambda is the proportional cost (fraction of the traded notional) and gamma is the absolute risk aversion.
+//! Bands are expressed in terms of the delta being hedged (signed by the quantity of the positions); the hedge holds the opposite amount of the underlying.
+//!
+//! # Example
+//! ```ignore
+//! let params = BandParams::builder().cost(0.001).risk_aversion(1.).build();
+//! let band = portfolio.hedging_band(&params, &BandMethod::Zakamouline);
+//! if let Some(trade) = band.rebalance(current_hedge) {
+//!     println!("trade {} to reach {}", trade, current_hedge + trade);
+//! }
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use crate::portfolio::*;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BandMethod {
+    WhalleyWilmott,
+    Zakamouline,
+}
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct BandParams {
+    /// Proportional transaction cost lambda (0.001 = 10bp of the traded notional)
+    pub cost: FloatType,
+    /// Absolute risk aversion gamma
+    pub risk_aversion: FloatType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HedgingBand {
+    /// Hedge ratio at the center of the band.
+    pub target: FloatType,
+    pub lower: FloatType,
+    pub upper: FloatType,
+}
+
+impl HedgingBand {
+    pub fn half_width(&self) -> FloatType {
+        0.5 * (self.upper - self.lower)
+    }
+
+    pub fn contains(&self, hedge: FloatType) -> bool {
+        self.lower <= hedge && hedge <= self.upper
+    }
+
+    /// Change of the hedged delta required to bring `hedge` back to the nearest edge of the band, or None if it is inside.
+    pub fn rebalance(&self, hedge: FloatType) -> Option<FloatType> {
+        if hedge < self.lower {
+            Some(self.lower - hedge)
+        } else if hedge > self.upper {
+            Some(self.upper - hedge)
+        } else {
+            None
+        }
+    }
+}
+
+/// Terms of a band for a given set of positions: (target delta, half width).
+fn band_terms(positions: &[Position], params: &BandParams, method: &BandMethod) -> (FloatType, FloatType) {
+    let ticks: Vec<(OptionTick, FloatType)> = positions
+        .iter()
+        .map(|p| (p.tick.get_implied_volatility(), p.quantity))
+        .collect();
+    let Some((first, _)) = ticks.first() else {
+        return (0., 0.);
+    };
+    let s = first.asset_price;
+    let r = first.rate();
+    let lambda = params.cost;
+    let gamma_ra = params.risk_aversion;
+    let net_gamma: FloatType = ticks.iter().map(|(t, q)| q * t.gamma()).sum();
+
+    match method {
+        BandMethod::WhalleyWilmott => {
+            let delta: FloatType = ticks.iter().map(|(t, q)| q * t.delta()).sum();
+            let discount: FloatType = ticks
+                .iter()
+                .map(|(t, q)| (q * t.gamma()).abs() * (-r * t.tau()).exp())
+                .sum::<FloatType>()
+                / ticks.iter().map(|(t, q)| (q * t.gamma()).abs()).sum::<FloatType>().max(FloatType::MIN_POSITIVE);
+            let h = (1.5 * discount * lambda * s * net_gamma.powi(2) / gamma_ra).cbrt();
+            (delta, h)
+        }
+        BandMethod::Zakamouline => {
+            let mut delta = 0.;
+            let mut h0: FloatType = 0.;
+            let mut hw = 0.;
+            for (tick, q) in ticks.iter() {
+                let tau = tick.tau();
+                let sigma = tick.iv();
+                let g = (q * tick.gamma()).abs();
+                let discount = (-r * tau).exp();
+                let k = -4.76 * lambda.powf(0.78) / tau.powf(0.02)
+                    * (discount / sigma).powf(0.25)
+                    * (gamma_ra * s.powi(2) * g).powf(0.15);
+                let mut modified = tick.clone();
+                modified.option_value = OptionValue::ImpliedVolatility(sigma * (1. + k).max(0.).sqrt());
+                delta += q * modified.delta();
+
+                h0 = h0.max(lambda / (gamma_ra * s * sigma.powi(2) * tau));
+                hw += 1.12 * lambda.powf(0.31) * tau.powf(0.05) * (discount / sigma).powf(0.25) * (g / gamma_ra).sqrt();
+            }
+            (delta, h0 + hw)
+        }
+    }
+}
+
+fn band(positions: &[Position], params: &BandParams, method: &BandMethod) -> HedgingBand {
+    let (target, half_width) = band_terms(positions, params, method);
+    HedgingBand {
+        target,
+        lower: target - half_width,
+        upper: target + half_width,
+    }
+}
+
+impl OptionTick {
+    /// No-transaction band for the delta hedge of one long option.
+    pub fn hedging_band(&self, params: &BandParams, method: &BandMethod) -> HedgingBand {
+        band(&[Position::new(self.clone(), 1.)], params, method)
+    }
+}
+
+impl Position {
+    /// No-transaction band for the delta hedge of the position.
+    pub fn hedging_band(&self, params: &BandParams, method: &BandMethod) -> HedgingBand {
+        band(std::slice::from_ref(self), params, method)
+    }
+}
+
+impl Portfolio {
+    /// No-transaction band for the delta hedge of the whole portfolio, which is assumed to share one underlying.
+    /// With Whalley-Wilmott the width depends on the net gamma, so offsetting positions narrow the band.
+    /// With Zakamouline the gamma-dependent widths of the positions are added, which is conservative.
+    pub fn hedging_band(&self, params: &BandParams, method: &BandMethod) -> HedgingBand {
+        band(&self.positions, params, method)
+    }
+}
@@ -0,0 +1,272 @@
+//! Heston stochastic volatility model.
+//!
+//! `dS = (r - q) S dt + sqrt(v) S dW_1`, `dv = kappa (theta - v) dt + sigma sqrt(v) dW_2`, `d<W_1, W_2> = rho dt`
+//!
+//! European options are priced with the COS method (Fang & Oosterlee, 2008) using the "little Heston trap" form of the characteristic function.
+//! The calibration fits the five parameters to the implied volatilities of an OptionBoard by minimizing vega-weighted price errors.
+//!
+//! # Example
+//! ```ignore
+//! let fit = board.calibrate_heston(&HestonParams::default(), &HestonCalibrationConfig::builder().build())?;
+//! println!("{:?} rmse: {}", fit.params, fit.rmse);
+//! let model_iv = fit.params.implied_volatility(&tick);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use crate::optimize::nelder_mead;
+use anyhow::{ensure, Result};
+use num_complex::Complex64;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, PartialEq, TypedBuilder, Serialize, Deserialize)]
+pub struct HestonParams {
+    /// Initial variance
+    pub v0: FloatType,
+    /// Speed of mean reversion of the variance
+    pub kappa: FloatType,
+    /// Long-run variance
+    pub theta: FloatType,
+    /// Volatility of the variance
+    pub sigma: FloatType,
+    /// Correlation between the asset and its variance
+    pub rho: FloatType,
+}
+
+impl Default for HestonParams {
+    fn default() -> Self {
+        Self {
+            v0: 0.04,
+            kappa: 2.,
+            theta: 0.04,
+            sigma: 0.5,
+            rho: -0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct HestonCalibrationConfig {
+    #[builder(default = 2000)]
+    pub max_iter: usize,
+    #[builder(default = 1e-10)]
+    pub tolerance: FloatType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HestonCalibration {
+    pub params: HestonParams,
+    /// Root mean squared IV error of the fitted model
+    pub rmse: FloatType,
+    pub n_options: usize,
+}
+
+/// Number of terms of the cosine expansion
+const COS_TERMS: usize = 256;
+/// Width of the truncation range in standard deviations
+const COS_RANGE: FloatType = 12.;
+
+impl HestonParams {
+    /// Feller condition `2 kappa theta > sigma^2`, under which the variance stays strictly positive.
+    pub fn feller(&self) -> bool {
+        2. * self.kappa * self.theta > self.sigma.powi(2)
+    }
+
+    /// Characteristic function of ln(S_tau / S_0).
+    fn characteristic_function(&self, u: FloatType, tau: FloatType, drift: FloatType) -> Complex64 {
+        let i = Complex64::i();
+        let iu = i * u;
+        let beta = self.kappa - self.rho * self.sigma * iu;
+        let d = (beta * beta + self.sigma.powi(2) * (iu + u * u)).sqrt();
+        let g = (beta - d) / (beta + d);
+        let e = (-d * tau).exp();
+        let c = self.kappa * self.theta / self.sigma.powi(2)
+            * ((beta - d) * tau - 2. * ((1. - g * e) / (1. - g)).ln());
+        let dd = self.v0 / self.sigma.powi(2) * (beta - d) * (1. - e) / (1. - g * e);
+        (iu * drift * tau + c + dd).exp()
+    }
+
+    /// First two cumulants of ln(S_tau / S_0).
+    fn cumulants(&self, tau: FloatType, drift: FloatType) -> (FloatType, FloatType) {
+        let (v0, k, th, s, r) = (self.v0, self.kappa, self.theta, self.sigma, self.rho);
+        let e = (-k * tau).exp();
+        let c1 = drift * tau + (1. - e) * (th - v0) / (2. * k) - 0.5 * th * tau;
+        let c2 = 1. / (8. * k.powi(3))
+            * (s * tau * k * e * (v0 - th) * (8. * k * r - 4. * s)
+                + k * r * s * (1. - e) * (16. * th - 8. * v0)
+                + 2. * th * k * tau * (-4. * k * r * s + s * s + 4. * k * k)
+                + s * s * ((th - 2. * v0) * e * e + th * (6. * e - 7.) + 2. * v0)
+                + 8. * k * k * (v0 - th) * (1. - e));
+        (c1, c2.abs())
+    }
+
+    /// Price of a European option under the Heston model, using the strike, asset price, rates, maturity and option type of the tick.
+    pub fn price(&self, tick: &OptionTick) -> FloatType {
+        let tau = tick.tau();
+        let s = tick.asset_price;
+        let k = tick.strike.to_f64().unwrap();
+        let r = tick.rate();
+        let q = tick.carry_yield();
+        let drift = r - q;
+
+        let x = (s / k).ln();
+        let (c1, c2) = self.cumulants(tau, drift);
+        let half_width = COS_RANGE * c2.sqrt();
+        let a = (x + c1 - half_width).min(-1e-6);
+        let b = (x + c1 + half_width).max(1e-6);
+        let width = b - a;
+
+        // Cosine coefficients of the put payoff K (1 - e^y)^+, which is supported on [a, 0]
+        let chi = |w: FloatType| -> FloatType {
+            ((-w * a).cos() - a.exp() + w * (-w * a).sin()) / (1. + w * w)
+        };
+        let psi = |n: usize, w: FloatType| -> FloatType {
+            if n == 0 {
+                -a
+            } else {
+                (-w * a).sin() / w
+            }
+        };
+
+        let put: FloatType = (0..COS_TERMS)
+            .map(|n| {
+                let w = n as FloatType * PI / width;
+                let v = 2. / width * k * (psi(n, w) - chi(w));
+                let phi = self.characteristic_function(w, tau, drift);
+                let term = (phi * Complex64::new(0., w * (x - a)).exp()).re * v;
+                if n == 0 {
+                    0.5 * term
+                } else {
+                    term
+                }
+            })
+            .sum::<FloatType>()
+            * (-r * tau).exp();
+        let put = put.max(0.);
+
+        match tick.option_type {
+            OptionType::Put => put,
+            OptionType::Call => put + s * (-q * tau).exp() - k * (-r * tau).exp(),
+        }
+    }
+
+    /// Black-Scholes implied volatility of the Heston price.
+    pub fn implied_volatility(&self, tick: &OptionTick) -> FloatType {
+        let mut priced = tick.clone();
+        priced.option_value = OptionValue::Price(self.price(tick));
+        priced.iv()
+    }
+
+    fn to_unconstrained(&self) -> Vec<FloatType> {
+        vec![
+            self.v0.ln(),
+            self.kappa.ln(),
+            self.theta.ln(),
+            self.sigma.ln(),
+            self.rho.clamp(-0.999, 0.999).atanh(),
+        ]
+    }
+
+    fn from_unconstrained(x: &[FloatType]) -> Self {
+        Self {
+            v0: x[0].exp(),
+            kappa: x[1].exp(),
+            theta: x[2].exp(),
+            sigma: x[3].exp(),
+            rho: x[4].tanh(),
+        }
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Fits Heston parameters to the implied volatilities on the board, starting from `initial`.
+    /// Errors are `(model price - market price) / vega`, a first order approximation of the IV error.
+    /// Ticks whose IV or vega is not finite are skipped. Passing an out-of-the-money board is recommended.
+    pub fn calibrate_heston(
+        &self,
+        initial: &HestonParams,
+        config: &HestonCalibrationConfig,
+    ) -> Result<HestonCalibration> {
+        let quotes: Vec<(OptionTick, FloatType, FloatType)> = self
+            .0
+            .iter()
+            .flat_map(|chain| chain.0.iter())
+            .filter(|tick| tick.tau() > 0.)
+            .map(|tick| {
+                let with_iv = tick.get_implied_volatility();
+                let price = tick.get_theoretical_price().get_value();
+                let vega = with_iv.vega();
+                (with_iv, price, vega)
+            })
+            .filter(|(tick, price, vega)| tick.get_value().is_finite() && price.is_finite() && *vega > 1e-8)
+            .collect();
+        ensure!(quotes.len() >= 5, "At least five valid options are required to calibrate Heston");
+
+        let objective = |x: &[FloatType]| -> FloatType {
+            let params = HestonParams::from_unconstrained(x);
+            quotes
+                .iter()
+                .map(|(tick, price, vega)| ((params.price(tick) - price) / vega).powi(2))
+                .sum::<FloatType>()
+                / quotes.len() as FloatType
+        };
+
+        // Restart the simplex from the best point, since Nelder-Mead tends to collapse early on this surface
+        let mut x = initial.to_unconstrained();
+        let mut mse = FloatType::INFINITY;
+        for _ in 0..5 {
+            let (x_new, mse_new) = nelder_mead(objective, &x, &[0.3; 5], config.max_iter, config.tolerance);
+            let converged = mse - mse_new < config.tolerance;
+            x = x_new;
+            mse = mse_new;
+            if converged {
+                break;
+            }
+        }
+        ensure!(mse.is_finite(), "Heston calibration did not converge");
+
+        Ok(HestonCalibration {
+            params: HestonParams::from_unconstrained(&x),
+            rmse: mse.sqrt(),
+            n_options: quotes.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, option_type: OptionType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(365))
+            .option_type(option_type)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn black_scholes_limit() {
+        // Without vol of vol the variance stays at v0 = theta and the price converges to Black-Scholes with sigma = sqrt(v0)
+        let params = HestonParams::builder().v0(0.04).kappa(2.).theta(0.04).sigma(1e-3).rho(0.).build();
+        for strike in [dec!(80), dec!(100), dec!(120)] {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let tick = tick(strike, option_type);
+                assert_float_absolute_eq!(params.price(&tick), tick.get_theoretical_price().get_value(), 1e-5);
+            }
+        }
+    }
+}
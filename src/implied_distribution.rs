@@ -0,0 +1,128 @@
+//! Risk-neutral distribution of the underlying at expiry implied by the option prices (Breeden-Litzenberger).
+//!
+//! The density of the terminal price is the discounted second derivative of the call price in strike,
+//! `f(K) = e^{r tau} d^2C/dK^2`, and its CDF is `F(K) = 1 + e^{r tau} dC/dK`.
+//!
+//! Raw prices are too noisy to differentiate twice, so the call price curve is smoothed first: the IVs of the OTM options
+//! are interpolated with a natural cubic spline in strike and calls are priced from the spline on an equally spaced grid
+//! between the lowest and highest listed strikes. The derivatives are central differences on that grid.
+//! Negative densities are kept as they are, since they reveal butterfly arbitrage in the quotes.
+//!
+//! The summary statistics are computed from the density normalized over the grid, i.e. conditional on the price at expiry
+//! lying within the listed strikes.
+//!
+//! # Example
+//! ```ignore
+//! let dist = chain.implied_distribution(200)?;
+//! println!("mean: {}, skew: {}, kurtosis: {}", dist.mean(), dist.skewness(), dist.kurtosis());
+//! let (strikes, density) = chain.implied_density()?;
+//! ```
+
+use crate::black_scholes::{BlackScholes, BsParams};
+use crate::math::CubicSpline;
+use crate::models::*;
+use anyhow::{ensure, Result};
+
+#[derive(Clone, Debug)]
+pub struct ImpliedDistribution {
+    pub strikes: Vec<FloatType>,
+    pub density: Vec<FloatType>,
+    pub cdf: Vec<FloatType>,
+}
+
+impl ImpliedDistribution {
+    /// Trapezoidal integral of f(K) * density(K) over the grid
+    fn integrate(&self, f: impl Fn(FloatType) -> FloatType) -> FloatType {
+        self.strikes
+            .windows(2)
+            .zip(self.density.windows(2))
+            .map(|(k, d)| (k[1] - k[0]) * (f(k[0]) * d[0] + f(k[1]) * d[1]) / 2.)
+            .sum()
+    }
+
+    /// Probability mass within the grid. Close to 1 when the listed strikes cover the distribution.
+    pub fn mass(&self) -> FloatType {
+        self.integrate(|_| 1.)
+    }
+
+    pub fn mean(&self) -> FloatType {
+        self.integrate(|k| k) / self.mass()
+    }
+
+    fn central_moment(&self, order: i32) -> FloatType {
+        let mean = self.mean();
+        self.integrate(|k| (k - mean).powi(order)) / self.mass()
+    }
+
+    pub fn variance(&self) -> FloatType {
+        self.central_moment(2)
+    }
+
+    pub fn skewness(&self) -> FloatType {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    /// Fourth standardized moment (3 for a normal distribution)
+    pub fn kurtosis(&self) -> FloatType {
+        self.central_moment(4) / self.variance().powi(2)
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Implied distribution on `points` equally spaced strikes between the lowest and highest listed OTM strikes.
+    pub fn implied_distribution(&self, points: usize) -> Result<ImpliedDistribution> {
+        ensure!(points >= 3, "At least three grid points are required");
+        let mut smile: Vec<(FloatType, FloatType, &OptionTick)> = Vec::new();
+        let otm = self.otm().map(OptionTick::get_implied_volatility);
+        for tick in otm.0.iter() {
+            let iv = tick.get_value();
+            if iv.is_finite() && iv > 0. {
+                smile.push((tick.strike_f64(), iv, tick));
+            }
+        }
+        smile.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        smile.dedup_by(|a, b| a.0 == b.0);
+        ensure!(smile.len() >= 3, "At least three strikes with a valid IV are required");
+
+        let spline = CubicSpline::new(smile.iter().map(|s| s.0).collect(), smile.iter().map(|s| s.1).collect())?;
+        let template = smile[0].2.bs_params();
+        let growth = 1. / template.discount_factor();
+        let (low, high) = (smile[0].0, smile[smile.len() - 1].0);
+        let h = (high - low) / (points - 1) as FloatType;
+        let grid: Vec<FloatType> = (0..points).map(|i| low + h * i as FloatType).collect();
+        let calls: Vec<FloatType> = grid
+            .iter()
+            .map(|k| {
+                BsParams {
+                    strike: *k,
+                    sigma: spline.eval(*k),
+                    option_type: OptionType::Call,
+                    ..template.clone()
+                }
+                .theoretical_price()
+            })
+            .collect();
+
+        let interior = 1..points - 1;
+        Ok(ImpliedDistribution {
+            strikes: grid[interior.clone()].to_vec(),
+            density: interior
+                .clone()
+                .map(|i| growth * (calls[i + 1] - 2. * calls[i] + calls[i - 1]) / (h * h))
+                .collect(),
+            cdf: interior.map(|i| 1. + growth * (calls[i + 1] - calls[i - 1]) / (2. * h)).collect(),
+        })
+    }
+
+    /// (strikes, density) of the implied distribution on 200 grid points
+    pub fn implied_density(&self) -> Result<(Vec<FloatType>, Vec<FloatType>)> {
+        let dist = self.implied_distribution(200)?;
+        Ok((dist.strikes, dist.density))
+    }
+
+    /// (strikes, cdf) of the implied distribution on 200 grid points
+    pub fn implied_cdf(&self) -> Result<(Vec<FloatType>, Vec<FloatType>)> {
+        let dist = self.implied_distribution(200)?;
+        Ok((dist.strikes, dist.cdf))
+    }
+}
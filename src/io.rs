@@ -0,0 +1,34 @@
+//! Columnar storage of option ticks, available with the `parquet` feature.
+//!
+//! An OptionChain<OptionTick> maps to an Arrow RecordBatch with one row per tick (see tick_schema()), which is written
+//! to Parquet files or to an Arrow IPC stream. ParquetArchive lays out historical boards as one file per observation date
+//! and expiry, so a backtest reads only the partitions it needs instead of loading the whole archive.
+//!
+//! Only the quote data is stored: strike, maturity, asset price, rates, option type, value, side, open interest, volume,
+//! pricing model, valuation time, style, engine, day count and contract spec. The discount curve, dividend curve and discrete
+//! dividends are restored to their defaults.
+//!
+//! The readers check every row with OptionTick::validate() and fail on the first invalid one. RowValidation skips or keeps
+//! those rows instead, e.g. the NaN IVs of failed solves in an archive: `OptionChain::from_parquet_with(path, RowValidation::Skip)`.
+//!
+//! # Example
+//! ```ignore
+//! chain.to_parquet("chain.parquet")?;
+//! let chain = OptionChain::<OptionTick>::from_parquet("chain.parquet")?;
+//!
+//! let archive = ParquetArchive::new("archive");
+//! archive.write(date, &board)?;
+//! for entry in archive.boards(start, end)? {
+//!     let (date, board) = entry?;
+//! }
+//! ```
+
+#[cfg(feature = "parquet")]
+mod columns;
+#[cfg(feature = "parquet")]
+pub mod ipc;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+#[cfg(feature = "parquet")]
+pub use columns::*;
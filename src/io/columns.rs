@@ -0,0 +1,289 @@
+use crate::contract::ContractSpec;
+use crate::models::*;
+use anyhow::{anyhow, Result};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use std::sync::Arc;
+
+/// Arrow schema of a RecordBatch of ticks. The strike is stored as a decimal string to keep it exact, and the pricing engine
+/// and day count as JSON. multiplier, currency and tick_size are null for a tick without a ContractSpec.
+pub fn tick_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("strike", DataType::Utf8, false),
+        Field::new("maturity", timestamp.clone(), false),
+        Field::new("asset_price", DataType::Float64, false),
+        Field::new("risk_free_rate", DataType::Float64, false),
+        Field::new("dividend_yield", DataType::Float64, false),
+        Field::new("option_type", DataType::Utf8, false),
+        Field::new("value_kind", DataType::Utf8, false),
+        Field::new("value", DataType::Float64, false),
+        Field::new("side", DataType::Utf8, true),
+        Field::new("open_interest", DataType::Float64, true),
+        Field::new("volume", DataType::Float64, true),
+        Field::new("pricing_model", DataType::Utf8, false),
+        Field::new("valuation_time", timestamp, true),
+        Field::new("underlying", DataType::Utf8, false),
+        Field::new("option_style", DataType::Utf8, false),
+        Field::new("pricing_engine", DataType::Utf8, false),
+        Field::new("day_count", DataType::Utf8, false),
+        Field::new("multiplier", DataType::Float64, true),
+        Field::new("currency", DataType::Utf8, true),
+        Field::new("tick_size", DataType::Float64, true),
+    ]))
+}
+
+/// What the readers do with a row that OptionTick::validate() rejects, e.g. a NaN IV written after a failed solve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowValidation {
+    /// Fail the whole batch with the row and the TickError
+    #[default]
+    Reject,
+    /// Leave the row out
+    Skip,
+    /// Keep the row as read
+    Off,
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<T>())
+        .ok_or_else(|| anyhow!("Column {} is missing or has the wrong type", name))
+}
+
+fn timestamp(millis: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis).ok_or_else(|| anyhow!("Invalid timestamp {}", millis))
+}
+
+impl OptionChain<OptionTick> {
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let ticks = &self.0;
+        let strings = |f: &dyn Fn(&OptionTick) -> Option<String>| -> ArrayRef {
+            Arc::new(ticks.iter().map(f).collect::<StringArray>())
+        };
+        let floats = |f: &dyn Fn(&OptionTick) -> Option<FloatType>| -> ArrayRef {
+            Arc::new(ticks.iter().map(f).collect::<Float64Array>())
+        };
+        let timestamps = |f: &dyn Fn(&OptionTick) -> Option<i64>| -> ArrayRef {
+            Arc::new(ticks.iter().map(f).collect::<TimestampMillisecondArray>().with_timezone("UTC"))
+        };
+        let columns = vec![
+            strings(&|t| Some(t.strike.to_string())),
+            timestamps(&|t| Some(t.maturity.timestamp_millis())),
+            floats(&|t| Some(t.asset_price)),
+            floats(&|t| Some(t.risk_free_rate)),
+            floats(&|t| Some(t.dividend_yield)),
+            strings(&|t| Some(format!("{:?}", t.option_type))),
+            strings(&|t| match t.option_value {
+                OptionValue::Price(_) => Some("Price".to_string()),
+                OptionValue::ImpliedVolatility(_) => Some("ImpliedVolatility".to_string()),
+            }),
+            floats(&|t| Some(t.get_value())),
+            strings(&|t| t.side.as_ref().map(|s| format!("{:?}", s))),
+            floats(&|t| t.additional_data.as_ref().and_then(|d| d.open_interest)),
+            floats(&|t| t.additional_data.as_ref().and_then(|d| d.volume)),
+            strings(&|t| Some(format!("{:?}", t.pricing_model))),
+            timestamps(&|t| t.valuation_time.map(|v| v.timestamp_millis())),
+            strings(&|t| Some(t.underlying.clone())),
+            strings(&|t| Some(format!("{:?}", t.option_style))),
+            strings(&|t| serde_json::to_string(&t.pricing_engine).ok()),
+            strings(&|t| serde_json::to_string(&t.day_count).ok()),
+            floats(&|t| t.contract_spec.as_ref().map(|c| c.multiplier)),
+            strings(&|t| t.contract_spec.as_ref().map(|c| c.currency.clone())),
+            floats(&|t| t.contract_spec.as_ref().map(|c| c.tick_size)),
+        ];
+        Ok(RecordBatch::try_new(tick_schema(), columns)?)
+    }
+
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self> {
+        Self::from_record_batch_with(batch, RowValidation::default())
+    }
+
+    /// Reads the rows of the batch, handling the rows that fail validation as set by `validation`.
+    pub fn from_record_batch_with(batch: &RecordBatch, validation: RowValidation) -> Result<Self> {
+        let strike = column::<StringArray>(batch, "strike")?;
+        let maturity = column::<TimestampMillisecondArray>(batch, "maturity")?;
+        let asset_price = column::<Float64Array>(batch, "asset_price")?;
+        let risk_free_rate = column::<Float64Array>(batch, "risk_free_rate")?;
+        let dividend_yield = column::<Float64Array>(batch, "dividend_yield")?;
+        let option_type = column::<StringArray>(batch, "option_type")?;
+        let value_kind = column::<StringArray>(batch, "value_kind")?;
+        let value = column::<Float64Array>(batch, "value")?;
+        let side = column::<StringArray>(batch, "side")?;
+        let open_interest = column::<Float64Array>(batch, "open_interest")?;
+        let volume = column::<Float64Array>(batch, "volume")?;
+        let pricing_model = column::<StringArray>(batch, "pricing_model")?;
+        let valuation_time = column::<TimestampMillisecondArray>(batch, "valuation_time")?;
+        // Files written before the underlying was recorded do not have the column
+        let underlying = column::<StringArray>(batch, "underlying").ok();
+        // nor those written before the style, engine, day count and contract spec were
+        let option_style = column::<StringArray>(batch, "option_style").ok();
+        let pricing_engine = column::<StringArray>(batch, "pricing_engine").ok();
+        let day_count = column::<StringArray>(batch, "day_count").ok();
+        let multiplier = column::<Float64Array>(batch, "multiplier").ok();
+        let currency = column::<StringArray>(batch, "currency").ok();
+        let tick_size = column::<Float64Array>(batch, "tick_size").ok();
+
+        let optional = |array: &Float64Array, i: usize| (!array.is_null(i)).then(|| array.value(i));
+        let mut chain = OptionChain(Vec::with_capacity(batch.num_rows()));
+        for i in 0..batch.num_rows() {
+            let mut tick = OptionTick::builder()
+                .strike(DecimalType::from_str(strike.value(i))?)
+                .maturity(timestamp(maturity.value(i))?)
+                .asset_price(asset_price.value(i))
+                .risk_free_rate(risk_free_rate.value(i))
+                .dividend_yield(dividend_yield.value(i))
+                .option_type(match option_type.value(i) {
+                    "Call" => OptionType::Call,
+                    "Put" => OptionType::Put,
+                    other => return Err(anyhow!("Unknown option type {}", other)),
+                })
+                .option_value(match value_kind.value(i) {
+                    "Price" => OptionValue::Price(value.value(i)),
+                    "ImpliedVolatility" => OptionValue::ImpliedVolatility(value.value(i)),
+                    other => return Err(anyhow!("Unknown option value {}", other)),
+                })
+                .pricing_model(match pricing_model.value(i) {
+                    "BlackScholes" => PricingModel::BlackScholes,
+                    "Black76" => PricingModel::Black76,
+                    "Bachelier" => PricingModel::Bachelier,
+                    other => return Err(anyhow!("Unknown pricing model {}", other)),
+                })
+                .build();
+            if !side.is_null(i) {
+                tick.side = Some(match side.value(i) {
+                    "Bid" => OptionSide::Bid,
+                    "Ask" => OptionSide::Ask,
+                    "Trade" => OptionSide::Trade,
+                    "MarkPrice" => OptionSide::MarkPrice,
+                    "Settlement" => OptionSide::Settlement,
+                    other => return Err(anyhow!("Unknown side {}", other)),
+                });
+            }
+            let (open_interest, volume) = (optional(open_interest, i), optional(volume, i));
+            if open_interest.is_some() || volume.is_some() {
+                let mut data = AdditionalOptionData::builder().build();
+                data.open_interest = open_interest;
+                data.volume = volume;
+                tick.additional_data = Some(data);
+            }
+            if !valuation_time.is_null(i) {
+                tick.valuation_time = Some(timestamp(valuation_time.value(i))?);
+            }
+            if let Some(underlying) = underlying {
+                tick.underlying = underlying.value(i).to_string();
+            }
+            if let Some(option_style) = option_style {
+                tick.option_style = match option_style.value(i) {
+                    "European" => OptionStyle::European,
+                    "American" => OptionStyle::American,
+                    other => return Err(anyhow!("Unknown option style {}", other)),
+                };
+            }
+            if let Some(pricing_engine) = pricing_engine {
+                tick.pricing_engine = serde_json::from_str(pricing_engine.value(i))?;
+            }
+            if let Some(day_count) = day_count {
+                tick.day_count = serde_json::from_str(day_count.value(i))?;
+            }
+            if let Some(multiplier) = multiplier.filter(|m| !m.is_null(i)) {
+                let mut spec = ContractSpec::builder().multiplier(multiplier.value(i)).build();
+                if let Some(currency) = currency.filter(|c| !c.is_null(i)) {
+                    spec.currency = currency.value(i).to_string();
+                }
+                if let Some(tick_size) = tick_size.and_then(|t| optional(t, i)) {
+                    spec.tick_size = tick_size;
+                }
+                tick.contract_spec = Some(spec);
+            }
+            match (tick.validate(), validation) {
+                (Err(e), RowValidation::Reject) => return Err(anyhow!("Invalid tick at row {}: {}", i, e)),
+                (Err(_), RowValidation::Skip) => continue,
+                _ => chain.0.push(tick),
+            }
+        }
+        Ok(chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, iv: FloatType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .underlying("BTC")
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(iv))
+            .maturity(now + Duration::days(30))
+            .option_type(OptionType::Put)
+            .side(OptionSide::Bid)
+            .additional_data(AdditionalOptionData::builder().open_interest(10.).volume(2.).build())
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn record_batch_round_trip() {
+        let mut expired = tick(dec!(95.5), 0.3);
+        expired.maturity = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        expired.valuation_time = None;
+        expired.side = None;
+        expired.additional_data = None;
+        let mut american = tick(dec!(105), 0.25);
+        american.option_style = OptionStyle::American;
+        american.pricing_engine = PricingEngine::FiniteDifference {
+            space_steps: 200,
+            time_steps: 100,
+        };
+        american.day_count = DayCount::Business252;
+        american.contract_spec = Some(ContractSpec::builder().multiplier(100.).currency("USD".to_string()).tick_size(0.05).build());
+        let chain = OptionChain(vec![tick(dec!(100), 0.2), expired, american]);
+        let read = OptionChain::from_record_batch(&chain.to_record_batch().unwrap()).unwrap();
+        assert_eq!(read.0.len(), 3);
+        for (a, b) in chain.0.iter().zip(read.0.iter()) {
+            assert_eq!(a.underlying, b.underlying);
+            assert_eq!(a.strike, b.strike);
+            assert_eq!(a.maturity, b.maturity);
+            assert_eq!(a.valuation_time, b.valuation_time);
+            assert_eq!(a.asset_price, b.asset_price);
+            assert_eq!(a.risk_free_rate, b.risk_free_rate);
+            assert_eq!(a.dividend_yield, b.dividend_yield);
+            assert_eq!(a.option_type, b.option_type);
+            assert_eq!(a.option_value, b.option_value);
+            assert_eq!(a.side, b.side);
+            assert_eq!(a.pricing_model, b.pricing_model);
+            assert_eq!(a.option_style, b.option_style);
+            assert_eq!(a.pricing_engine, b.pricing_engine);
+            assert_eq!(a.day_count, b.day_count);
+            assert_eq!(a.contract_spec, b.contract_spec);
+            assert_eq!(a.multiplier(), b.multiplier());
+            assert_eq!(
+                a.additional_data.as_ref().map(|d| (d.open_interest, d.volume)),
+                b.additional_data.as_ref().map(|d| (d.open_interest, d.volume))
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_rows() {
+        let chain = OptionChain(vec![tick(dec!(100), 0.2), tick(dec!(110), FloatType::NAN)]);
+        let batch = chain.to_record_batch().unwrap();
+        assert!(OptionChain::from_record_batch(&batch).is_err());
+        let skipped = OptionChain::from_record_batch_with(&batch, RowValidation::Skip).unwrap();
+        assert_eq!(skipped.0.len(), 1);
+        assert_eq!(skipped.0[0].strike, dec!(100));
+        let kept = OptionChain::from_record_batch_with(&batch, RowValidation::Off).unwrap();
+        assert_eq!(kept.0.len(), 2);
+        assert!(kept.0[1].get_value().is_nan());
+    }
+}
@@ -0,0 +1,59 @@
+//! Arrow IPC streams of chains, one RecordBatch per chain.
+
+use super::columns::{tick_schema, RowValidation};
+use crate::models::*;
+use anyhow::Result;
+use arrow_ipc::reader::StreamReader;
+use arrow_ipc::writer::StreamWriter;
+use std::io::{Read, Write};
+
+pub struct TickStreamWriter<W: Write> {
+    writer: StreamWriter<W>,
+}
+
+impl<W: Write> TickStreamWriter<W> {
+    pub fn new(writer: W) -> Result<Self> {
+        Ok(Self {
+            writer: StreamWriter::try_new(writer, &tick_schema())?,
+        })
+    }
+
+    pub fn write(&mut self, chain: &OptionChain<OptionTick>) -> Result<()> {
+        self.writer.write(&chain.to_record_batch()?)?;
+        Ok(())
+    }
+
+    /// Writes the end-of-stream marker and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.finish()?;
+        Ok(self.writer.into_inner()?)
+    }
+}
+
+/// Reads the chains of a stream one at a time.
+pub struct TickStreamReader<R: Read> {
+    reader: StreamReader<R>,
+    validation: RowValidation,
+}
+
+impl<R: Read> TickStreamReader<R> {
+    pub fn new(reader: R) -> Result<Self> {
+        Self::with_validation(reader, RowValidation::default())
+    }
+
+    /// Reader handling the rows that fail validation as set by `validation`.
+    pub fn with_validation(reader: R, validation: RowValidation) -> Result<Self> {
+        Ok(Self {
+            reader: StreamReader::try_new(reader, None)?,
+            validation,
+        })
+    }
+}
+
+impl<R: Read> Iterator for TickStreamReader<R> {
+    type Item = Result<OptionChain<OptionTick>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.reader.next()?;
+        Some(batch.map_err(Into::into).and_then(|b| OptionChain::from_record_batch_with(&b, self.validation)))
+    }
+}
@@ -0,0 +1,206 @@
+//! Parquet files of chains and a partitioned archive of boards.
+
+use super::columns::{tick_schema, RowValidation};
+use crate::greek_grid::GreekGrid;
+use crate::models::*;
+use anyhow::{anyhow, Result};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+impl OptionChain<OptionTick> {
+    /// Writes the chain to a Snappy compressed Parquet file.
+    pub fn to_parquet(&self, path: impl AsRef<Path>) -> Result<()> {
+        let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let mut writer = ArrowWriter::try_new(File::create(path)?, tick_schema(), Some(properties))?;
+        writer.write(&self.to_record_batch()?)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    pub fn from_parquet(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_parquet_with(path, RowValidation::default())
+    }
+
+    /// Reads the file, handling the rows that fail validation as set by `validation`.
+    pub fn from_parquet_with(path: impl AsRef<Path>, validation: RowValidation) -> Result<Self> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+        let mut chain = OptionChain(Vec::new());
+        for batch in reader {
+            chain.0.extend(OptionChain::from_record_batch_with(&batch?, validation)?.0);
+        }
+        Ok(chain)
+    }
+}
+
+impl GreekGrid {
+    /// Writes the cells with a value in long format, with the columns maturity, strike and value.
+    pub fn to_parquet(&self, path: impl AsRef<Path>) -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("maturity", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), false),
+            Field::new("strike", DataType::Float64, false),
+            Field::new("value", DataType::Float64, false),
+        ]));
+        let cells = self.cells();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                cells
+                    .iter()
+                    .map(|c| Some(c.0.timestamp_millis()))
+                    .collect::<TimestampMillisecondArray>()
+                    .with_timezone("UTC"),
+            ),
+            Arc::new(cells.iter().map(|c| Some(c.1)).collect::<Float64Array>()),
+            Arc::new(cells.iter().map(|c| Some(c.2)).collect::<Float64Array>()),
+        ];
+        let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))?;
+        writer.write(&RecordBatch::try_new(schema, columns)?)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const MATURITY_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// Directory of Parquet files partitioned by observation date and expiry:
+/// `root/date=2024-01-05/maturity=20240126T080000.parquet`.
+#[derive(Clone, Debug)]
+pub struct ParquetArchive {
+    pub root: PathBuf,
+    /// Handling of the rows that fail validation when reading, RowValidation::Reject by default
+    pub validation: RowValidation,
+}
+
+impl ParquetArchive {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            validation: RowValidation::default(),
+        }
+    }
+
+    pub fn with_validation(mut self, validation: RowValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    fn date_dir(&self, date: NaiveDate) -> PathBuf {
+        self.root.join(format!("date={}", date.format(DATE_FORMAT)))
+    }
+
+    fn chain_path(&self, date: NaiveDate, maturity: DateTime<Utc>) -> PathBuf {
+        self.date_dir(date)
+            .join(format!("maturity={}.parquet", maturity.format(MATURITY_FORMAT)))
+    }
+
+    /// Writes each chain of the board to its partition, replacing the existing files.
+    pub fn write(&self, date: NaiveDate, board: &OptionBoard<OptionTick>) -> Result<()> {
+        fs::create_dir_all(self.date_dir(date))?;
+        for chain in board.0.iter() {
+            chain.to_parquet(self.chain_path(date, chain.maturity()?))?;
+        }
+        Ok(())
+    }
+
+    /// Partition values `key=value` of the entries of dir, in ascending order.
+    fn partitions(dir: &Path, key: &str, suffix: &str) -> Result<Vec<String>> {
+        let mut values = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if let Some(value) = name.strip_prefix(key).and_then(|v| v.strip_suffix(suffix)) {
+                values.push(value.to_string());
+            }
+        }
+        values.sort();
+        Ok(values)
+    }
+
+    /// Observation dates in the archive, in ascending order.
+    pub fn dates(&self) -> Result<Vec<NaiveDate>> {
+        Self::partitions(&self.root, "date=", "")?
+            .iter()
+            .map(|d| Ok(NaiveDate::parse_from_str(d, DATE_FORMAT)?))
+            .collect()
+    }
+
+    /// Expiries stored for the date, in ascending order.
+    pub fn maturities(&self, date: NaiveDate) -> Result<Vec<DateTime<Utc>>> {
+        Self::partitions(&self.date_dir(date), "maturity=", ".parquet")?
+            .iter()
+            .map(|m| Ok(NaiveDateTime::parse_from_str(m, MATURITY_FORMAT)?.and_utc()))
+            .collect()
+    }
+
+    pub fn read_chain(&self, date: NaiveDate, maturity: DateTime<Utc>) -> Result<OptionChain<OptionTick>> {
+        let path = self.chain_path(date, maturity);
+        if !path.exists() {
+            return Err(anyhow!("No chain for {} on {}", maturity, date));
+        }
+        OptionChain::from_parquet_with(path, self.validation)
+    }
+
+    pub fn read_board(&self, date: NaiveDate) -> Result<OptionBoard<OptionTick>> {
+        let chains = self
+            .maturities(date)?
+            .into_iter()
+            .map(|maturity| self.read_chain(date, maturity))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(OptionBoard(chains))
+    }
+
+    /// Boards of the dates in [from, to], read one date at a time as the iterator advances.
+    pub fn boards(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<impl Iterator<Item = Result<(NaiveDate, OptionBoard<OptionTick>)>> + '_> {
+        let dates = self.dates()?.into_iter().filter(move |d| from <= *d && *d <= to);
+        Ok(dates.map(move |date| Ok((date, self.read_board(date)?))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn archive_round_trip() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let tick = |strike: DecimalType, iv: FloatType| {
+            OptionTick::builder()
+                .strike(strike)
+                .asset_price(100.)
+                .option_value(OptionValue::ImpliedVolatility(iv))
+                .maturity(now + Duration::days(30))
+                .option_type(OptionType::Call)
+                .valuation_time(now)
+                .build()
+        };
+        let board = OptionBoard(vec![OptionChain(vec![tick(dec!(100), 0.2), tick(dec!(110), FloatType::NAN)])]);
+        let root = std::env::temp_dir().join(format!("optiors-archive-{}", std::process::id()));
+        let date = now.date_naive();
+        let archive = ParquetArchive::new(&root);
+        archive.write(date, &board).unwrap();
+
+        assert_eq!(archive.dates().unwrap(), vec![date]);
+        assert_eq!(archive.maturities(date).unwrap(), vec![now + Duration::days(30)]);
+        assert!(archive.read_board(date).is_err());
+        let read = archive.with_validation(RowValidation::Skip).read_board(date).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(read.0.len(), 1);
+        assert_eq!(read.0[0].0.len(), 1);
+        assert_eq!(read.0[0].0[0].strike, dec!(100));
+        assert_eq!(read.0[0].0[0].get_value(), 0.2);
+    }
+}
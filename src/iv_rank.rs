@@ -0,0 +1,94 @@
+//! IV rank and IV percentile over a trailing window.
+//!
+//! Both answer whether volatility is rich or cheap relative to its own history, typically over 252 daily observations of ATM IV:
+//! * IV rank: (IV - min) / (max - min) over the window, i.e. where the current IV sits in the range
+//! * IV percentile: the share of the observations of the window that are below the current IV
+//!
+//! The window includes the current observation; the first `window - 1` elements have no full window and are NaN.
+//! NaN observations (e.g. a snapshot whose ATM IV could not be computed) are ignored within a window.
+//!
+//! # Example
+//! ```ignore
+//! let atm_iv = boards.map(|board| board.get_front_month().atm().iv());
+//! let rank = atm_iv.iv_rank(252);
+//! println!("IV rank {:.0}, IV percentile {:.0}", rank.0.last().unwrap().rank * 100., rank.0.last().unwrap().percentile * 100.);
+//! ```
+
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IvRank {
+    pub iv: FloatType,
+    /// (iv - min) / (max - min) over the window, in [0, 1]
+    pub rank: FloatType,
+    /// Share of the observations of the window below iv, in [0, 1]
+    pub percentile: FloatType,
+}
+
+impl TimeSeries<FloatType> {
+    /// Applies f to the current value and the trailing window of `window` observations ending with it.
+    fn rolling(&self, window: usize, f: impl Fn(FloatType, &[FloatType]) -> FloatType) -> Self {
+        let window = window.max(1);
+        TimeSeries(
+            (0..self.0.len())
+                .map(|i| {
+                    if i + 1 < window || self.0[i].is_nan() {
+                        return FloatType::NAN;
+                    }
+                    let past: Vec<FloatType> = self.0[i + 1 - window..=i]
+                        .iter()
+                        .copied()
+                        .filter(|v| !v.is_nan())
+                        .collect();
+                    f(self.0[i], &past)
+                })
+                .collect(),
+        )
+    }
+
+    /// Share of the trailing `window` observations strictly below the current value.
+    pub fn percentile_rank(&self, window: usize) -> Self {
+        self.rolling(window, |current, past| {
+            past.iter().filter(|v| **v < current).count() as FloatType / past.len() as FloatType
+        })
+    }
+
+    /// Position of the current value in the [min, max] range of the trailing `window` observations.
+    /// 0.5 when the window is flat.
+    pub fn range_rank(&self, window: usize) -> Self {
+        self.rolling(window, |current, past| {
+            let min = past
+                .iter()
+                .copied()
+                .fold(FloatType::INFINITY, FloatType::min);
+            let max = past
+                .iter()
+                .copied()
+                .fold(FloatType::NEG_INFINITY, FloatType::max);
+            if max > min {
+                (current - min) / (max - min)
+            } else {
+                0.5
+            }
+        })
+    }
+
+    /// IV rank and IV percentile of a series of IVs over the trailing `window` observations.
+    pub fn iv_rank(&self, window: usize) -> TimeSeries<IvRank> {
+        let rank = self.range_rank(window);
+        let percentile = self.percentile_rank(window);
+        TimeSeries(
+            self.0
+                .iter()
+                .zip(rank.0)
+                .zip(percentile.0)
+                .map(|((iv, rank), percentile)| IvRank {
+                    iv: *iv,
+                    rank,
+                    percentile,
+                })
+                .collect(),
+        )
+    }
+}
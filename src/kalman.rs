@@ -0,0 +1,87 @@
+//! Kalman filter and Rauch-Tung-Striebel smoother for noisy indicator time series such as tick-level IV or GEX.
+//!
+//! The series is modelled as a local level (random walk plus noise):
+//! * state: `x_t = x_{t-1} + w_t`, `w_t ~ N(0, process_noise)`
+//! * observation: `y_t = x_t + v_t`, `v_t ~ N(0, observation_noise)`
+//!
+//! The ratio process_noise / observation_noise controls the smoothness: a small ratio gives a smooth series, a large ratio follows the observations closely.
+//! NaN observations are treated as missing and only the prediction step is applied.
+//!
+//! # Example
+//! ```ignore
+//! let params = KalmanParams::builder().process_noise(1e-6).observation_noise(1e-4).build();
+//! let smooth_iv = atm_iv_ts.kalman_smooth(&params);
+//! ```
+
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct KalmanParams {
+    /// Variance of the state innovation per step
+    pub process_noise: FloatType,
+    /// Variance of the observation error
+    pub observation_noise: FloatType,
+    /// Initial state. If None, the first finite observation is used.
+    #[builder(default, setter(strip_option))]
+    pub initial_state: Option<FloatType>,
+    /// Variance of the initial state
+    #[builder(default = 1.)]
+    pub initial_variance: FloatType,
+}
+
+impl TimeSeries<FloatType> {
+    /// Causal Kalman filter. Each value only depends on past observations, so it can be used in backtests and live.
+    pub fn kalman_filter(&self, params: &KalmanParams) -> TimeSeries<FloatType> {
+        let (filtered, _, _) = self.forward_pass(params);
+        TimeSeries(filtered)
+    }
+
+    /// Kalman filter followed by the Rauch-Tung-Striebel backward pass.
+    /// Each value uses the whole series, so the result must not be used as a signal in backtests (look-ahead bias).
+    pub fn kalman_smooth(&self, params: &KalmanParams) -> TimeSeries<FloatType> {
+        let (mut state, mut variance, predicted_variance) = self.forward_pass(params);
+        for t in (0..state.len().saturating_sub(1)).rev() {
+            let gain = variance[t] / predicted_variance[t + 1];
+            state[t] += gain * (state[t + 1] - state[t]);
+            variance[t] += gain * gain * (variance[t + 1] - predicted_variance[t + 1]);
+        }
+        TimeSeries(state)
+    }
+
+    /// Returns the filtered states, filtered variances and predicted variances.
+    fn forward_pass(
+        &self,
+        params: &KalmanParams,
+    ) -> (Vec<FloatType>, Vec<FloatType>, Vec<FloatType>) {
+        let n = self.0.len();
+        let mut state = Vec::with_capacity(n);
+        let mut variance = Vec::with_capacity(n);
+        let mut predicted_variance = Vec::with_capacity(n);
+
+        let mut x = params
+            .initial_state
+            .or_else(|| self.0.iter().copied().find(|y| y.is_finite()))
+            .unwrap_or(FloatType::NAN);
+        let mut p = params.initial_variance;
+
+        for (t, y) in self.0.iter().enumerate() {
+            // predict (the initial state is the prior of the first observation)
+            if t > 0 {
+                p += params.process_noise;
+            }
+            predicted_variance.push(p);
+
+            // update
+            if y.is_finite() {
+                let gain = p / (p + params.observation_noise);
+                x += gain * (y - x);
+                p *= 1. - gain;
+            }
+            state.push(x);
+            variance.push(p);
+        }
+        (state, variance, predicted_variance)
+    }
+}
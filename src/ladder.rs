@@ -0,0 +1,269 @@
+//! Greek ladders: exposures of a Portfolio bucketed by strike or by delta band.
+//!
+//! A single aggregate vanna or volga hides where the exposure sits. A ladder splits `quantity * greek` of each position into buckets,
+//! either per strike, per strike interval, or per band of call-equivalent delta (the delta of a call, 1 + delta for a put).
+//!
+//! # Example
+//! ```ignore
+//! for bucket in portfolio.greek_ladder(Greek::Vanna, &Bucketing::DeltaBands(vec![0., 0.1, 0.25, 0.5, 0.75, 0.9, 1.])) {
+//!     println!("{:.2}-{:.2}: {}", bucket.lower, bucket.upper, bucket.exposure);
+//! }
+//! ```
+//!
+//! A vega map projects the vega of each position onto the pillars of a volatility surface (tenor × delta),
+//! splitting it between the neighbouring pillars with linear interpolation weights, which is the bucketed view used for vol risk limits and hedging.
+//! ```ignore
+//! let pillars = SurfacePillars::new(vec![1. / 12., 0.25, 0.5, 1.], vec![0.1, 0.25, 0.5, 0.75, 0.9])?;
+//! let map = portfolio.vega_map(&pillars);
+//! println!("3M 25d vega: {}", map.vega[1][1]);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::*;
+use crate::models::*;
+use crate::portfolio::*;
+use anyhow::{ensure, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Bucketing {
+    /// One bucket per strike
+    Strike,
+    /// Buckets of strikes `[n * width, (n + 1) * width)`
+    StrikeWidth(FloatType),
+    /// Buckets between consecutive edges of call-equivalent delta, e.g. `[0, 0.25, 0.5, 0.75, 1]`
+    DeltaBands(Vec<FloatType>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LadderBucket {
+    /// Lower edge of the bucket (the strike itself for Bucketing::Strike)
+    pub lower: FloatType,
+    /// Upper edge of the bucket (the strike itself for Bucketing::Strike)
+    pub upper: FloatType,
+    /// Sum of quantity * greek of the positions in the bucket
+    pub exposure: FloatType,
+}
+
+impl Portfolio {
+    /// Buckets `quantity * greek` of every position. Buckets are returned in ascending order; empty buckets are omitted
+    /// except for delta bands, which are always all returned. Positions outside every delta band are ignored.
+    pub fn greek_ladder(&self, greek: Greek, bucketing: &Bucketing) -> Vec<LadderBucket> {
+        let exposures = self.positions.iter().map(|p| {
+            let tick = p.tick.get_implied_volatility();
+            (tick.clone(), p.quantity * greek.of(&tick))
+        });
+
+        match bucketing {
+            Bucketing::Strike => {
+                let mut ladder: BTreeMap<DecimalType, FloatType> = BTreeMap::new();
+                for (tick, exposure) in exposures {
+                    *ladder.entry(tick.strike).or_default() += exposure;
+                }
+                ladder
+                    .into_iter()
+                    .map(|(strike, exposure)| {
+                        let k = strike.to_f64().unwrap();
+                        LadderBucket {
+                            lower: k,
+                            upper: k,
+                            exposure,
+                        }
+                    })
+                    .collect()
+            }
+            Bucketing::StrikeWidth(width) => {
+                let mut ladder: BTreeMap<i64, FloatType> = BTreeMap::new();
+                for (tick, exposure) in exposures {
+                    let bucket = (tick.strike.to_f64().unwrap() / width).floor() as i64;
+                    *ladder.entry(bucket).or_default() += exposure;
+                }
+                ladder
+                    .into_iter()
+                    .map(|(bucket, exposure)| LadderBucket {
+                        lower: bucket as FloatType * width,
+                        upper: (bucket + 1) as FloatType * width,
+                        exposure,
+                    })
+                    .collect()
+            }
+            Bucketing::DeltaBands(edges) => {
+                let mut ladder: Vec<LadderBucket> = edges
+                    .windows(2)
+                    .map(|w| LadderBucket {
+                        lower: w[0],
+                        upper: w[1],
+                        exposure: 0.,
+                    })
+                    .collect();
+                let n = ladder.len();
+                for (tick, exposure) in exposures {
+                    let call_delta = match tick.option_type {
+                        OptionType::Call => tick.delta(),
+                        OptionType::Put => 1. + tick.delta(),
+                    };
+                    // The last band includes its upper edge
+                    if let Some(bucket) = ladder.iter_mut().enumerate().find_map(|(i, b)| {
+                        (b.lower <= call_delta && (call_delta < b.upper || (i == n - 1 && call_delta <= b.upper)))
+                            .then_some(b)
+                    }) {
+                        bucket.exposure += exposure;
+                    }
+                }
+                ladder
+            }
+        }
+    }
+
+    /// Vanna exposure per strike as (strike, sum of quantity * vanna).
+    pub fn vanna_exposure_by_strike(&self) -> Vec<(DecimalType, FloatType)> {
+        self.exposure_by_strike(Greek::Vanna)
+    }
+
+    /// Volga (vomma) exposure per strike as (strike, sum of quantity * volga).
+    pub fn volga_exposure_by_strike(&self) -> Vec<(DecimalType, FloatType)> {
+        self.exposure_by_strike(Greek::Vomma)
+    }
+
+    fn exposure_by_strike(&self, greek: Greek) -> Vec<(DecimalType, FloatType)> {
+        let mut ladder: BTreeMap<DecimalType, FloatType> = BTreeMap::new();
+        for position in self.positions.iter() {
+            let tick = position.tick.get_implied_volatility();
+            *ladder.entry(tick.strike).or_default() += position.quantity * greek.of(&tick);
+        }
+        ladder.into_iter().collect()
+    }
+}
+
+/// Pillars of a volatility surface: tenors in years and call-equivalent deltas, both in ascending order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SurfacePillars {
+    pub tenors: Vec<FloatType>,
+    pub deltas: Vec<FloatType>,
+}
+
+impl SurfacePillars {
+    pub fn new(tenors: Vec<FloatType>, deltas: Vec<FloatType>) -> Result<Self> {
+        ensure!(!tenors.is_empty() && !deltas.is_empty(), "At least one tenor and one delta pillar are required");
+        ensure!(
+            tenors.windows(2).all(|w| w[0] < w[1]) && deltas.windows(2).all(|w| w[0] < w[1]),
+            "Pillars must be in strictly ascending order"
+        );
+        Ok(Self { tenors, deltas })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VegaMap {
+    pub pillars: SurfacePillars,
+    /// Vega per pillar, indexed as vega[tenor][delta]
+    pub vega: Vec<Vec<FloatType>>,
+}
+
+impl VegaMap {
+    fn zeros(pillars: &SurfacePillars) -> Self {
+        Self {
+            pillars: pillars.clone(),
+            vega: vec![vec![0.; pillars.deltas.len()]; pillars.tenors.len()],
+        }
+    }
+
+    /// Vega per tenor, summed over the delta pillars.
+    pub fn by_tenor(&self) -> Vec<FloatType> {
+        self.vega.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    pub fn total(&self) -> FloatType {
+        self.by_tenor().iter().sum()
+    }
+}
+
+/// Linear interpolation weights of `x` on the ascending `pillars`. Outside the pillars, everything goes to the nearest one.
+/// A NaN `x` has no weights.
+fn interpolation_weights(pillars: &[FloatType], x: FloatType) -> Vec<(usize, FloatType)> {
+    let n = pillars.len();
+    if x.is_nan() {
+        return Vec::new();
+    }
+    if x <= pillars[0] {
+        return vec![(0, 1.)];
+    }
+    if x >= pillars[n - 1] {
+        return vec![(n - 1, 1.)];
+    }
+    let Some(i) = pillars.iter().rposition(|p| *p <= x) else {
+        return Vec::new();
+    };
+    let w = (x - pillars[i]) / (pillars[i + 1] - pillars[i]);
+    vec![(i, 1. - w), (i + 1, w)]
+}
+
+impl Position {
+    /// Projects quantity * vega onto the surface pillars by the tenor and call-equivalent delta of the option.
+    /// An option whose vega, tenor or delta is not finite, e.g. quoted below intrinsic so that it has no IV, is left out.
+    pub fn vega_map(&self, pillars: &SurfacePillars) -> VegaMap {
+        let mut map = VegaMap::zeros(pillars);
+        self.add_to_vega_map(&mut map);
+        map
+    }
+
+    fn add_to_vega_map(&self, map: &mut VegaMap) {
+        let tick = self.tick.get_implied_volatility();
+        let vega = self.quantity * tick.vega();
+        let call_delta = match tick.option_type {
+            OptionType::Call => tick.delta(),
+            OptionType::Put => 1. + tick.delta(),
+        };
+        if !(vega.is_finite() && tick.tau().is_finite() && call_delta.is_finite()) {
+            return;
+        }
+        for (i, wt) in interpolation_weights(&map.pillars.tenors, tick.tau()) {
+            for (j, wd) in interpolation_weights(&map.pillars.deltas, call_delta) {
+                map.vega[i][j] += vega * wt * wd;
+            }
+        }
+    }
+}
+
+impl Portfolio {
+    /// Sum of the vega maps of the positions. Positions without a finite vega, tenor or delta are left out.
+    pub fn vega_map(&self, pillars: &SurfacePillars) -> VegaMap {
+        let mut map = VegaMap::zeros(pillars);
+        for position in self.positions.iter() {
+            position.add_to_vega_map(&mut map);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn vega_map_skips_quotes_below_intrinsic() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let tick = |strike: DecimalType, value: OptionValue| {
+            OptionTick::builder()
+                .strike(strike)
+                .asset_price(100.)
+                .option_value(value)
+                .maturity(now + Duration::days(90))
+                .option_type(OptionType::Call)
+                .valuation_time(now)
+                .build()
+        };
+        // intrinsic value 20
+        let below_intrinsic = tick(dec!(80), OptionValue::Price(5.));
+        let atm = tick(dec!(100), OptionValue::ImpliedVolatility(0.2));
+        let portfolio = Portfolio::from_positions(vec![Position::new(below_intrinsic, 1.), Position::new(atm.clone(), 2.)]);
+        let pillars = SurfacePillars::new(vec![1. / 12., 0.25, 0.5], vec![0.25, 0.5, 0.75]).unwrap();
+        let map = portfolio.vega_map(&pillars);
+        assert_float_relative_eq!(map.total(), 2. * atm.vega(), 1e-12);
+    }
+}
@@ -1,5 +1,65 @@
+
+pub mod arbitrage;
+pub mod asian;
+pub mod bachelier;
+pub mod backtest;
+pub mod barrier;
+pub mod batch;
 pub mod black_scholes;
+pub mod bootstrap;
+pub mod bus;
+pub mod calendar;
+pub mod calibration;
+pub mod carry;
+pub mod checkpoint;
+pub mod cleaning;
+pub mod construction;
+pub mod context;
+pub mod contract;
+pub mod diagram;
+pub mod discount_curve;
+pub mod dispersion;
+pub mod dividend;
+pub mod engine;
+pub mod expected_move;
+pub mod expiry;
 pub mod exposure;
+pub mod feed;
+pub mod flow;
+pub mod greek_grid;
 pub mod greeks;
+pub mod hedging;
+pub mod heston;
+pub mod implied_distribution;
+pub mod io;
+pub mod iv_rank;
+pub mod kalman;
+pub mod ladder;
+pub mod liquidity;
+pub mod math;
 pub mod models;
+pub mod moneyness;
+pub mod monte_carlo;
+pub mod numerical_greeks;
+pub mod open_interest;
+pub mod optimize;
+pub mod pde;
+pub mod portfolio;
 pub mod prelude;
+pub mod realized_vol;
+pub mod recorder;
+pub mod regime;
+pub mod risk;
+pub mod scenario;
+pub mod screening;
+pub mod seasonality;
+pub mod skew_term_structure;
+pub mod smile_dynamics;
+pub mod smile_smoothing;
+pub mod spread;
+pub mod strategy;
+pub mod term_structure;
+pub mod variance_swap;
+pub mod vol_cone;
+pub mod wing;
+pub use models::*;
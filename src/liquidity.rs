@@ -0,0 +1,172 @@
+//! Liquidity of the quotes of a StrikeBoard chain.
+//!
+//! iv_spread() measures the width of a strike in IV, the unit a smile fit works in. liquidity_report() summarises a chain
+//! of live boards: the relative bid/ask spread and displayed size of each strike, the median spread and the number of
+//! strikes quoted on both sides. The per-strike entries are meant as inputs to weight strikes in a fit, so that wide or
+//! one-sided wings count less than the liquid strikes around the money.
+//!
+//! smile_weights() is the default weighting of the smile fits: the weight of a strike is 1 / IV spread, i.e. its vega over
+//! its bid/ask spread in price, so a quote counts in proportion to how precisely it pins down the IV. Strikes without a
+//! two-sided market get the weight of the least liquid two-sided strike, and the weights are scaled to a mean of 1.
+//!
+//! # Example
+//! ```ignore
+//! let iv_spread = strike_board.iv_spread()?;
+//! let report = chain.liquidity_report();
+//! println!("{} live strikes, median spread {:?}", report.live_strikes, report.median_spread_pct);
+//! let fit = chain.fit_wing_model_weighted(&report.smile_weights())?;
+//! ```
+
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StrikeLiquidity {
+    pub strike: FloatType,
+    pub option_type: OptionType,
+    /// (ask - bid) / mid of the top of book, None unless both sides are quoted with a positive mid
+    pub spread_pct: Option<FloatType>,
+    /// Ask IV - bid IV, None unless both sides are quoted and their IVs solve
+    pub iv_spread: Option<FloatType>,
+    /// Displayed size summed over all bid and ask levels
+    pub quoted_size: FloatType,
+}
+
+impl StrikeLiquidity {
+    /// A strike is live when both sides are quoted.
+    pub fn is_live(&self) -> bool {
+        self.spread_pct.is_some()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityReport {
+    /// One entry per non-empty StrikeBoard, in the order of the chain
+    pub strikes: Vec<StrikeLiquidity>,
+    /// Median of spread_pct over the live strikes, None if no strike is live
+    pub median_spread_pct: Option<FloatType>,
+    /// Displayed size summed over all strikes
+    pub quoted_size: FloatType,
+    /// Number of strikes quoted on both sides
+    pub live_strikes: usize,
+}
+
+/// Floor of the IV spread in smile_weights(), so that a locked market does not get an infinite weight
+const MIN_IV_SPREAD: FloatType = 1e-4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StrikeWeight {
+    pub strike: FloatType,
+    pub option_type: OptionType,
+    pub weight: FloatType,
+}
+
+/// Per-strike weights of a smile fit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmileWeights(pub Vec<StrikeWeight>);
+
+impl SmileWeights {
+    /// Weight of the strike and option type of the tick, 1 if it has none.
+    pub fn weight(&self, tick: &OptionTick) -> FloatType {
+        let strike = tick.strike_f64();
+        self.0
+            .iter()
+            .find(|w| w.strike == strike && w.option_type == tick.option_type)
+            .map_or(1., |w| w.weight)
+    }
+}
+
+fn median(mut values: Vec<FloatType>) -> Option<FloatType> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = values.len();
+    Some(if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.
+    })
+}
+
+impl LiquidityReport {
+    /// Weights of the strikes for a smile fit, 1 / IV spread scaled to a mean of 1 (see the module documentation).
+    pub fn smile_weights(&self) -> SmileWeights {
+        let raw: Vec<Option<FloatType>> = self
+            .strikes
+            .iter()
+            .map(|s| {
+                s.iv_spread
+                    .filter(|spread| *spread >= 0.)
+                    .map(|spread| 1. / spread.max(MIN_IV_SPREAD))
+            })
+            .collect();
+        let floor = raw
+            .iter()
+            .flatten()
+            .cloned()
+            .reduce(FloatType::min)
+            .unwrap_or(1.);
+        let weights: Vec<FloatType> = raw.into_iter().map(|w| w.unwrap_or(floor)).collect();
+        let mean = weights.iter().sum::<FloatType>() / weights.len().max(1) as FloatType;
+        SmileWeights(
+            self.strikes
+                .iter()
+                .zip(weights)
+                .map(|(s, weight)| StrikeWeight {
+                    strike: s.strike,
+                    option_type: s.option_type.clone(),
+                    weight: weight / mean,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl StrikeBoard {
+    /// IV of the best ask minus IV of the best bid, solving the IVs from the premiums if the board is quoted in price.
+    pub fn iv_spread(&self) -> Result<FloatType, OptionError> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Ok(ask.try_iv()? - bid.try_iv()?)
+    }
+
+    /// Spread, IV spread and displayed size of the strike. None if the board is empty.
+    pub fn liquidity(&self) -> Option<StrikeLiquidity> {
+        let first = self.0.first()?;
+        let ladder = self.ladder();
+        let spread_pct = match (ladder.bids.first(), ladder.asks.first()) {
+            (Some(bid), Some(ask)) => {
+                let mid = (bid.price + ask.price) / 2.;
+                (mid > 0.).then(|| (ask.price - bid.price) / mid)
+            }
+            _ => None,
+        };
+        let quoted_size = ladder
+            .bids
+            .iter()
+            .chain(ladder.asks.iter())
+            .map(|level| level.size)
+            .sum();
+        Some(StrikeLiquidity {
+            strike: first.strike_f64(),
+            option_type: first.option_type.clone(),
+            spread_pct,
+            iv_spread: self.iv_spread().ok(),
+            quoted_size,
+        })
+    }
+}
+
+impl OptionChain<StrikeBoard> {
+    /// Liquidity of each strike of the chain and its summary.
+    pub fn liquidity_report(&self) -> LiquidityReport {
+        let strikes: Vec<StrikeLiquidity> = self.0.iter().filter_map(|b| b.liquidity()).collect();
+        let spreads: Vec<FloatType> = strikes.iter().filter_map(|s| s.spread_pct).collect();
+        LiquidityReport {
+            live_strikes: spreads.len(),
+            median_spread_pct: median(spreads),
+            quoted_size: strikes.iter().map(|s| s.quoted_size).sum(),
+            strikes,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Standard normal distribution functions used by the pricing formulas.
+//!
+//! norm_cdf is computed from the complementary error function, which keeps full relative precision in the lower tail,
+//! and norm_inv_cdf is Wichura's algorithm AS241, accurate to about 1e-16. The inverse converts deltas to strikes and
+//! probabilities to quantiles.
+//!
+//! CubicSpline is a natural cubic interpolating spline, used to get smooth curves (e.g. IV against strike) through quoted points.
+//!
+//! # Example
+//! ```ignore
+//! let p = norm_cdf(1.96); // 0.975
+//! let x = norm_inv_cdf(0.975); // 1.96
+//! ```
+
+use crate::models::FloatType;
+use anyhow::{ensure, Result};
+use std::f64::consts::{PI, SQRT_2};
+
+/// Density of the standard normal distribution
+pub fn norm_pdf(x: FloatType) -> FloatType {
+    (-0.5 * x * x).exp() / (2. * PI).sqrt()
+}
+
+/// Cumulative distribution function of the standard normal distribution: erfc(-x / sqrt(2)) / 2
+pub fn norm_cdf(x: FloatType) -> FloatType {
+    0.5 * libm::erfc(-x / SQRT_2)
+}
+
+#[rustfmt::skip]
+fn poly(c: &[FloatType; 8], x: FloatType) -> FloatType {
+    c[0] + x * (c[1] + x * (c[2] + x * (c[3] + x * (c[4] + x * (c[5] + x * (c[6] + x * c[7]))))))
+}
+
+/// Inverse of norm_cdf (Wichura, AS241). Returns -inf for p = 0, inf for p = 1 and NaN outside [0, 1].
+#[allow(clippy::excessive_precision)]
+pub fn norm_inv_cdf(p: FloatType) -> FloatType {
+    const A: [FloatType; 8] = [
+        3.3871328727963666080e+00, 1.3314166789178437745e+02, 1.9715909503065514427e+03, 1.3731693765509461125e+04,
+        4.5921953931549871457e+04, 6.7265770927008700853e+04, 3.3430575583588128105e+04, 2.5090809287301226727e+03,
+    ];
+    const B: [FloatType; 8] = [
+        1.0000000000000000000e+00, 4.2313330701600911252e+01, 6.8718700749205790830e+02, 5.3941960214247511077e+03,
+        2.1213794301586595867e+04, 3.9307895800092710610e+04, 2.8729085735721942674e+04, 5.2264952788528545610e+03,
+    ];
+    const C: [FloatType; 8] = [
+        1.42343711074968357734e+00, 4.63033784615654529590e+00, 5.76949722146069140550e+00, 3.64784832476320460504e+00,
+        1.27045825245236838258e+00, 2.41780725177450611770e-01, 2.27238449892691845833e-02, 7.74545014278341407640e-04,
+    ];
+    const D: [FloatType; 8] = [
+        1.00000000000000000000e+00, 2.05319162663775882187e+00, 1.67638483018380384940e+00, 6.89767334985100004550e-01,
+        1.48103976427480074590e-01, 1.51986665636164571966e-02, 5.47593808499534494600e-04, 1.05075007164441684324e-09,
+    ];
+    const E: [FloatType; 8] = [
+        6.65790464350110377720e+00, 5.46378491116411436990e+00, 1.78482653991729133580e+00, 2.96560571828504891230e-01,
+        2.65321895265761230930e-02, 1.24266094738807843860e-03, 2.71155556874348757815e-05, 2.01033439929228813265e-07,
+    ];
+    const F: [FloatType; 8] = [
+        1.00000000000000000000e+00, 5.99832206555887937690e-01, 1.36929880922735805310e-01, 1.48753612908506148525e-02,
+        7.86869131145613259100e-04, 1.84631831751005468180e-05, 1.42151175831644588870e-07, 2.04426310338993978564e-15,
+    ];
+
+    if !(0. ..=1.).contains(&p) {
+        return FloatType::NAN;
+    }
+    if p == 0. {
+        return FloatType::NEG_INFINITY;
+    }
+    if p == 1. {
+        return FloatType::INFINITY;
+    }
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        return q * poly(&A, r) / poly(&B, r);
+    }
+    let r = (-(if q < 0. { p } else { 1. - p }).ln()).sqrt();
+    let x = if r <= 5. {
+        let r = r - 1.6;
+        poly(&C, r) / poly(&D, r)
+    } else {
+        let r = r - 5.;
+        poly(&E, r) / poly(&F, r)
+    };
+    if q < 0. {
+        -x
+    } else {
+        x
+    }
+}
+
+/// Natural cubic spline through (xs, ys), extrapolated linearly outside [xs[0], xs[n-1]].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicSpline {
+    xs: Vec<FloatType>,
+    ys: Vec<FloatType>,
+    /// Second derivatives at the knots
+    m: Vec<FloatType>,
+}
+
+impl CubicSpline {
+    /// xs must be strictly increasing and have at least two points.
+    pub fn new(xs: Vec<FloatType>, ys: Vec<FloatType>) -> Result<Self> {
+        ensure!(xs.len() >= 2, "At least two points are required");
+        ensure!(xs.len() == ys.len(), "xs and ys must have the same length");
+        ensure!(xs.windows(2).all(|w| w[0] < w[1]), "xs must be strictly increasing");
+        let n = xs.len();
+        let mut m = vec![0.; n];
+        if n > 2 {
+            // Tridiagonal system for the interior second derivatives, solved by the Thomas algorithm
+            let h: Vec<FloatType> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+            let mut diag = vec![0.; n];
+            let mut rhs = vec![0.; n];
+            for i in 1..n - 1 {
+                diag[i] = 2. * (h[i - 1] + h[i]);
+                rhs[i] = 6. * ((ys[i + 1] - ys[i]) / h[i] - (ys[i] - ys[i - 1]) / h[i - 1]);
+            }
+            for i in 2..n - 1 {
+                let w = h[i - 1] / diag[i - 1];
+                diag[i] -= w * h[i - 1];
+                rhs[i] -= w * rhs[i - 1];
+            }
+            for i in (1..n - 1).rev() {
+                m[i] = (rhs[i] - h[i] * m[i + 1]) / diag[i];
+            }
+        }
+        Ok(Self { xs, ys, m })
+    }
+
+    pub fn eval(&self, x: FloatType) -> FloatType {
+        let n = self.xs.len();
+        if x <= self.xs[0] {
+            return self.ys[0] + self.derivative(self.xs[0]) * (x - self.xs[0]);
+        }
+        if x >= self.xs[n - 1] {
+            return self.ys[n - 1] + self.derivative(self.xs[n - 1]) * (x - self.xs[n - 1]);
+        }
+        let i = self.segment(x);
+        let h = self.xs[i + 1] - self.xs[i];
+        let (a, b) = ((self.xs[i + 1] - x) / h, (x - self.xs[i]) / h);
+        a * self.ys[i]
+            + b * self.ys[i + 1]
+            + ((a * a * a - a) * self.m[i] + (b * b * b - b) * self.m[i + 1]) * h * h / 6.
+    }
+
+    /// First derivative, constant outside the knots
+    pub fn derivative(&self, x: FloatType) -> FloatType {
+        let n = self.xs.len();
+        let i = self.segment(x.clamp(self.xs[0], self.xs[n - 1]));
+        let h = self.xs[i + 1] - self.xs[i];
+        let x = x.clamp(self.xs[0], self.xs[n - 1]);
+        let (a, b) = ((self.xs[i + 1] - x) / h, (x - self.xs[i]) / h);
+        (self.ys[i + 1] - self.ys[i]) / h - (3. * a * a - 1.) * h * self.m[i] / 6. + (3. * b * b - 1.) * h * self.m[i + 1] / 6.
+    }
+
+    /// Index i of the segment [xs[i], xs[i+1]] containing x, for x within the knots
+    fn segment(&self, x: FloatType) -> usize {
+        let n = self.xs.len();
+        self.xs.partition_point(|k| *k <= x).clamp(1, n - 1) - 1
+    }
+}
+
+/// Merges the points with the same x, sorted by x, into one point with the mean of their y.
+pub(crate) fn average_by_x(points: &[(FloatType, FloatType)]) -> (Vec<FloatType>, Vec<FloatType>) {
+    let (mut xs, mut ys, mut counts): (Vec<FloatType>, Vec<FloatType>, Vec<FloatType>) =
+        (Vec::new(), Vec::new(), Vec::new());
+    for (x, y) in points {
+        if xs.last() == Some(x) {
+            let i = xs.len() - 1;
+            ys[i] = (ys[i] * counts[i] + y) / (counts[i] + 1.);
+            counts[i] += 1.;
+        } else {
+            xs.push(*x);
+            ys.push(*y);
+            counts.push(1.);
+        }
+    }
+    (xs, ys)
+}
+
+/// Gaussian elimination with partial pivoting
+pub(crate) fn solve_dense(mut a: Vec<Vec<FloatType>>, mut b: Vec<FloatType>) -> Vec<FloatType> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|i, j| a[*i][col].abs().partial_cmp(&a[*j][col].abs()).unwrap())
+            .unwrap();
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum: FloatType = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+
+    #[test]
+    fn inverse_of_cdf() {
+        assert_float_absolute_eq!(norm_cdf(0.), 0.5, 1e-16);
+        assert_float_absolute_eq!(norm_cdf(1.96), 0.9750021048517795, 1e-15);
+        for x in [-8., -3., -0.5, 0.1, 2.] {
+            assert_float_relative_eq!(norm_inv_cdf(norm_cdf(x)), x, 1e-12);
+        }
+    }
+}
@@ -1,9 +1,26 @@
 pub mod crud;
+pub mod day_count;
+pub mod error;
+pub mod exotic;
 pub mod extract_common_info;
+pub mod indexed;
+pub mod iter;
+pub mod order_book;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod schema;
 pub mod structs;
 pub mod time_series;
+pub mod validation;
 
 pub use crud::*;
+pub use day_count::*;
+pub use error::*;
+pub use exotic::*;
 pub use extract_common_info::*;
+pub use indexed::*;
+pub use order_book::*;
+pub use schema::*;
 pub use structs::*;
 pub use time_series::*;
+pub use validation::*;
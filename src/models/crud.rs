@@ -1,12 +1,24 @@
 use super::extract_common_info::*;
-use super::structs::{FloatType, OptionBase, OptionBoard, OptionChain, OptionTick, StrikeBoard};
+use super::structs::{FloatType, OptionBase, OptionBoard, OptionChain, OptionTick, OptionUniverse, StrikeBoard};
+use std::collections::BTreeMap;
+
+/// Effect of an upsert or delete, so that callers can invalidate caches only when something changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    Inserted,
+    Updated,
+    Deleted,
+    /// Nothing matched the tick, e.g. the delete of a tick which is not in the container
+    Unchanged,
+}
 
 /// This trait automatically builds OptionChain, OptionBoard, StrikeBoard, etc. by simply entering an OptionTick.
+/// Containers are mutated in place; upsert and delete return the effect on the innermost tick.
 pub trait CRUD {
     type DataType;
     fn new() -> Self;
-    fn upsert(&mut self, tick: OptionTick);
-    fn delete(&mut self, tick: OptionTick);
+    fn upsert(&mut self, tick: OptionTick) -> Mutation;
+    fn delete(&mut self, tick: OptionTick) -> Mutation;
     fn push(&mut self, data: Self::DataType);
 }
 
@@ -15,43 +27,38 @@ impl CRUD for StrikeBoard {
     fn new() -> Self {
         Self(Vec::new())
     }
-    fn upsert(&mut self, tick: OptionTick) {
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
         if tick.get_value() < FloatType::EPSILON {
             return self.delete(tick);
         }
-        let mut ticks = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, t) in ticks.iter().enumerate() {
-            if t.option_value == tick.option_value && t.side == tick.side {
-                index = i;
-                found = true;
-                break;
+        match self
+            .0
+            .iter()
+            .position(|t| t.option_value == tick.option_value && t.side == tick.side)
+        {
+            Some(i) => {
+                self.0[i] = tick;
+                Mutation::Updated
+            }
+            None => {
+                self.0.push(tick);
+                Mutation::Inserted
             }
         }
-        if found {
-            ticks[index] = tick;
-        } else {
-            ticks.push(tick);
-        }
-        self.0 = ticks;
     }
 
-    fn delete(&mut self, tick: OptionTick) {
-        let mut ticks = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, t) in ticks.iter().enumerate() {
-            if t.option_value == tick.option_value && t.side == tick.side {
-                index = i;
-                found = true;
-                break;
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        match self
+            .0
+            .iter()
+            .position(|t| t.option_value == tick.option_value && t.side == tick.side)
+        {
+            Some(i) => {
+                self.0.remove(i);
+                Mutation::Deleted
             }
+            None => Mutation::Unchanged,
         }
-        if found {
-            ticks.remove(index);
-        }
-        self.0 = ticks;
     }
 
     fn push(&mut self, data: OptionTick) {
@@ -64,43 +71,38 @@ impl CRUD for OptionChain<OptionTick> {
     fn new() -> Self {
         Self(Vec::new())
     }
-    fn upsert(&mut self, tick: OptionTick) {
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
         if tick.get_value() < FloatType::EPSILON {
             return self.delete(tick);
         }
-        let mut ticks = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, t) in ticks.iter().enumerate() {
-            if t.strike == tick.strike && t.option_type == tick.option_type {
-                index = i;
-                found = true;
-                break;
+        match self
+            .0
+            .iter()
+            .position(|t| t.strike == tick.strike && t.option_type == tick.option_type)
+        {
+            Some(i) => {
+                self.0[i] = tick;
+                Mutation::Updated
+            }
+            None => {
+                self.0.push(tick);
+                Mutation::Inserted
             }
         }
-        if found {
-            ticks[index] = tick;
-        } else {
-            ticks.push(tick);
-        }
-        self.0 = ticks;
     }
 
-    fn delete(&mut self, tick: OptionTick) {
-        let mut ticks = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, t) in ticks.iter().enumerate() {
-			if t.strike == tick.strike && t.option_type == tick.option_type {
-				index = i;
-				found = true;
-                break;
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        match self
+            .0
+            .iter()
+            .position(|t| t.strike == tick.strike && t.option_type == tick.option_type)
+        {
+            Some(i) => {
+                self.0.remove(i);
+                Mutation::Deleted
             }
+            None => Mutation::Unchanged,
         }
-        if found {
-            ticks.remove(index);
-        }
-        self.0 = ticks;
     }
 
     fn push(&mut self, data: OptionTick) {
@@ -113,46 +115,43 @@ impl CRUD for OptionChain<StrikeBoard> {
     fn new() -> Self {
         Self(Vec::new())
     }
-    fn upsert(&mut self, tick: OptionTick) {
-        let mut strike_boards = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, sb) in strike_boards.iter().enumerate() {
-            if sb.strike().unwrap() == tick.strike && sb.option_type().unwrap() == tick.option_type
-            {
-                index = i;
-                found = true;
-                break;
+    /// StrikeBoards left empty by the upsert of a zero value are removed.
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        let position = self.0.iter().position(|sb| {
+            sb.strike().is_ok_and(|k| k == tick.strike) && sb.option_type().is_ok_and(|t| t == tick.option_type)
+        });
+        match position {
+            Some(i) => {
+                let mutation = self.0[i].upsert(tick);
+                if self.0[i].0.is_empty() {
+                    self.0.remove(i);
+                }
+                mutation
             }
-        }
-        if found {
-            strike_boards[index].upsert(tick);
-        } else {
-            let mut sb = StrikeBoard::new();
-            sb.push(tick);
-            strike_boards.push(sb);
-        }
-        self.0 = strike_boards;
-    }
-    fn delete(&mut self, tick: OptionTick) {
-        let mut strike_boards = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, sb) in strike_boards.iter().enumerate() {
-            if sb.strike().unwrap() == tick.strike && sb.option_type().unwrap() == tick.option_type
-            {
-                index = i;
-                found = true;
-                break;
+            None => {
+                let mut sb = StrikeBoard::new();
+                let mutation = sb.upsert(tick);
+                if !sb.0.is_empty() {
+                    self.0.push(sb);
+                }
+                mutation
             }
         }
-        if found {
-            strike_boards[index].delete(tick);
-            if strike_boards[index].0.is_empty() {
-                strike_boards.remove(index);
+    }
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        let position = self.0.iter().position(|sb| {
+            sb.strike().is_ok_and(|k| k == tick.strike) && sb.option_type().is_ok_and(|t| t == tick.option_type)
+        });
+        match position {
+            Some(i) => {
+                let mutation = self.0[i].delete(tick);
+                if self.0[i].0.is_empty() {
+                    self.0.remove(i);
+                }
+                mutation
             }
+            None => Mutation::Unchanged,
         }
-        self.0 = strike_boards;
     }
     fn push(&mut self, data: Self::DataType) {
         self.0.push(data);
@@ -167,47 +166,80 @@ where
     fn new() -> Self {
         Self(Vec::new())
     }
-    fn upsert(&mut self, tick: OptionTick) {
-        let mut option_chains = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, oc) in option_chains.iter().enumerate() {
-            if oc.maturity().unwrap() == tick.maturity {
-                index = i;
-                found = true;
-                break;
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        match self.0.iter().position(|oc| oc.maturity().is_ok_and(|m| m == tick.maturity)) {
+            Some(i) => {
+                let mutation = self.0[i].upsert(tick);
+                if self.0[i].0.is_empty() {
+                    self.0.remove(i);
+                }
+                mutation
+            }
+            None => {
+                let mut oc = OptionChain::new();
+                let mutation = oc.upsert(tick);
+                if !oc.0.is_empty() {
+                    self.0.push(oc);
+                }
+                mutation
             }
         }
-        if found {
-            option_chains[index].upsert(tick);
-        } else {
-            let mut oc = OptionChain::new();
-            oc.upsert(tick);
-            option_chains.push(oc);
-        }
-        self.0 = option_chains;
     }
 
-    fn delete(&mut self, tick: OptionTick) {
-        let mut option_chains = self.0.clone();
-        let mut index = 0;
-        let mut found = false;
-        for (i, oc) in option_chains.iter().enumerate() {
-            if oc.maturity().unwrap() == tick.maturity {
-                index = i;
-                found = true;
-                break;
-            }
-        }
-        if found {
-            option_chains[index].delete(tick);
-            if option_chains[index].0.is_empty() {
-                option_chains.remove(index);
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        match self.0.iter().position(|oc| oc.maturity().is_ok_and(|m| m == tick.maturity)) {
+            Some(i) => {
+                let mutation = self.0[i].delete(tick);
+                if self.0[i].0.is_empty() {
+                    self.0.remove(i);
+                }
+                mutation
             }
+            None => Mutation::Unchanged,
         }
-        self.0 = option_chains;
     }
     fn push(&mut self, data: Self::DataType) {
         self.0.push(data);
     }
 }
+
+/// Routes each tick to the board of its underlying, so that ticks of different underlyings with the same maturity and strike do not collide.
+impl<T> CRUD for OptionUniverse<T>
+where
+    T: OptionBase + ExtractCommonInfo,
+    OptionBoard<T>: CRUD<DataType = OptionChain<T>>,
+{
+    type DataType = OptionBoard<T>;
+    fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        let underlying = tick.underlying.clone();
+        let board = self.0.entry(underlying.clone()).or_insert_with(|| OptionBoard(Vec::new()));
+        let mutation = board.upsert(tick);
+        if board.0.is_empty() {
+            self.0.remove(&underlying);
+        }
+        mutation
+    }
+
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        let board = match self.0.get_mut(&tick.underlying) {
+            Some(board) => board,
+            None => return Mutation::Unchanged,
+        };
+        let underlying = tick.underlying.clone();
+        let mutation = board.delete(tick);
+        if board.0.is_empty() {
+            self.0.remove(&underlying);
+        }
+        mutation
+    }
+
+    /// Inserts the board under the underlying of its ticks, replacing the previous board. Empty boards are ignored.
+    fn push(&mut self, data: Self::DataType) {
+        if let Ok(underlying) = data.underlying() {
+            self.0.insert(underlying, data);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::structs::FloatType;
+use crate::calendar::ExchangeCalendar;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Convention used to convert the time between two instants into a year fraction.
+/// * Act365: actual time / 365 days (the default convention of OptionTick::tau())
+/// * Act360: actual time / 360 days
+/// * Business252: time elapsed on weekdays / 252 days
+/// * TradingCalendar: time elapsed on weekdays that are not in the holiday list / 252 days
+/// * Exchange: time elapsed inside the trading sessions of the exchange, see ExchangeCalendar::year_fraction()
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DayCount {
+    #[default]
+    Act365,
+    Act360,
+    Business252,
+    TradingCalendar(Vec<NaiveDate>),
+    Exchange(ExchangeCalendar),
+}
+
+const SECONDS_PER_DAY: FloatType = 86400.;
+
+impl DayCount {
+    /// Year fraction between `start` and `end` (negative if end is before start).
+    pub fn year_fraction(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> FloatType {
+        if end < start {
+            return -self.year_fraction(end, start);
+        }
+        let seconds = (end - start).num_seconds() as FloatType;
+        match self {
+            DayCount::Act365 => seconds / (365. * SECONDS_PER_DAY),
+            DayCount::Act360 => seconds / (360. * SECONDS_PER_DAY),
+            DayCount::Business252 => business_seconds(start, end, |_| true) / (252. * SECONDS_PER_DAY),
+            DayCount::TradingCalendar(holidays) => {
+                business_seconds(start, end, |date| !holidays.contains(&date)) / (252. * SECONDS_PER_DAY)
+            }
+            DayCount::Exchange(calendar) => calendar.year_fraction(start, end),
+        }
+    }
+
+    /// Number of hours counted in one year by the convention.
+    pub fn hours_per_year(&self) -> FloatType {
+        match self {
+            DayCount::Act365 => 365. * 24.,
+            DayCount::Act360 => 360. * 24.,
+            DayCount::Business252 | DayCount::TradingCalendar(_) => 252. * 24.,
+            DayCount::Exchange(calendar) => calendar.seconds_per_trading_day() / 3600. * calendar.trading_days_per_year,
+        }
+    }
+}
+
+/// Seconds between start and end that fall on weekdays accepted by `is_open`.
+fn business_seconds(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    is_open: impl Fn(NaiveDate) -> bool,
+) -> FloatType {
+    let mut total = 0.;
+    let mut date = start.date_naive();
+    while date <= end.date_naive() {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        if !weekend && is_open(date) {
+            let day_start = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+            let day_end = day_start + Duration::days(1);
+            let from = day_start.max(start);
+            let to = day_end.min(end);
+            if to > from {
+                total += (to - from).num_seconds() as FloatType;
+            }
+        }
+        date = date.succ_opt().unwrap();
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_float_eq::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn year_fraction() {
+        // Friday 12:00 to the following Tuesday 12:00
+        let start = Utc.with_ymd_and_hms(2023, 3, 3, 12, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2023, 3, 7, 12, 0, 0).unwrap();
+        assert_float_relative_eq!(DayCount::Act365.year_fraction(start, end), 4. / 365.);
+        assert_float_relative_eq!(DayCount::Act360.year_fraction(start, end), 4. / 360.);
+        assert_float_relative_eq!(DayCount::Business252.year_fraction(start, end), 2. / 252.);
+        let holiday = NaiveDate::from_ymd_opt(2023, 3, 6).unwrap();
+        assert_float_relative_eq!(DayCount::TradingCalendar(vec![holiday]).year_fraction(start, end), 1. / 252.);
+        assert_float_relative_eq!(DayCount::Business252.year_fraction(end, start), -2. / 252.);
+    }
+}
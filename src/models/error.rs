@@ -0,0 +1,84 @@
+//! Typed errors of the containers and of the exposure analytics.
+//!
+//! The accessors of StrikeBoard, ExtractCommonInfo and GreeksExposure return an OptionError, so that callers can match on
+//! the cause of a failure (e.g. skip a strike without bids) instead of parsing a message. OptionError implements
+//! std::error::Error, so `?` still converts it into anyhow::Error in application code.
+
+use super::structs::FloatType;
+use crate::greeks::GreeksError;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionError {
+    /// The StrikeBoard has no bid tick
+    NoBids,
+    /// The StrikeBoard has no ask tick
+    NoAsks,
+    /// The StrikeBoard has neither a bid nor an ask tick
+    NoQuotes,
+    /// No source of the fallback chain of StrikeBoard::mark_with gives a price
+    NoMark,
+    /// The StrikeBoard, OptionChain or OptionBoard has no element
+    EmptyChain,
+    /// The field is not common to the elements of the container, e.g. the strike of an OptionChain
+    NotCommonField(&'static str),
+    /// A tick has no open interest in its additional_data
+    MissingOpenInterest,
+    /// The implied volatility could not be solved from the premium
+    IvNotConverged,
+    /// No option of the chain has a well-defined delta, e.g. because every IV solve failed
+    NoDelta,
+    /// No two listed options of the chain have deltas that bracket the target delta
+    DeltaOutOfRange(FloatType),
+    /// The engine does not support the pricing model of the tick, e.g. a lognormal simulation of a Bachelier tick
+    UnsupportedModel,
+    /// A greek of a tick is not defined
+    Greeks(GreeksError),
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionError::NoBids => write!(f, "No bid ticks in the StrikeBoard."),
+            OptionError::NoAsks => write!(f, "No ask ticks in the StrikeBoard."),
+            OptionError::NoQuotes => write!(f, "No bid or ask ticks in the StrikeBoard."),
+            OptionError::NoMark => write!(f, "No mark source gives a price in the StrikeBoard."),
+            OptionError::EmptyChain => write!(f, "The container is empty."),
+            OptionError::NotCommonField(field) => write!(
+                f,
+                "The {} is not a common value of the elements of this container.",
+                field
+            ),
+            OptionError::MissingOpenInterest => write!(
+                f,
+                "No open interest is set. Set a value in the open_interest field of the additional_data."
+            ),
+            OptionError::IvNotConverged => {
+                write!(f, "The implied volatility could not be solved from the premium.")
+            }
+            OptionError::NoDelta => write!(f, "No option in the chain has a well-defined delta."),
+            OptionError::DeltaOutOfRange(target) => {
+                write!(f, "Target delta {} is outside the range of listed strikes.", target)
+            }
+            OptionError::UnsupportedModel => {
+                write!(f, "The pricing model of the tick is not supported by this engine.")
+            }
+            OptionError::Greeks(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for OptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OptionError::Greeks(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<GreeksError> for OptionError {
+    fn from(e: GreeksError) -> Self {
+        OptionError::Greeks(e)
+    }
+}
@@ -0,0 +1,25 @@
+use super::structs::OptionTick;
+use crate::asian::AsianOption;
+use crate::numerical_greeks::BarrierOption;
+use crate::spread::SpreadOption;
+use serde::{Deserialize, Serialize};
+
+/// Options whose payoff is not a vanilla function of the terminal price, wrapping the OptionTick that carries the market data.
+/// Prices and greeks are available through NumericalGreeks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ExoticOption {
+    Barrier(BarrierOption),
+    Asian(AsianOption),
+    Spread(SpreadOption),
+}
+
+impl ExoticOption {
+    /// Tick holding the strike, maturity, option type and market data of the option
+    pub fn tick(&self) -> &OptionTick {
+        match self {
+            ExoticOption::Barrier(option) => &option.tick,
+            ExoticOption::Asian(option) => &option.tick,
+            ExoticOption::Spread(option) => &option.tick,
+        }
+    }
+}
@@ -2,106 +2,127 @@ use super::structs::{
     FloatType, OptionBase, OptionBoard, OptionChain, OptionSide, OptionTick, OptionType,
     OptionValue, StrikeBoard,DecimalType
 };
-use anyhow::{anyhow, Result};
+use super::error::OptionError;
 use chrono::{DateTime, Utc};
 
 /// Trait to retrieve common information
 /// For example, since OptionChain is a set of OptionTicks with the same maturity, this trait can be used to retrieve maturity information.
-/// If it tries to retrieve information that is not common information, it returns None.
+/// If it tries to retrieve information that is not common information, it returns OptionError::NotCommonField,
+/// and an empty container returns OptionError::EmptyChain.
 pub trait ExtractCommonInfo {
-    fn strike(&self) -> Result<DecimalType> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn strike(&self) -> Result<DecimalType, OptionError> {
+        Err(OptionError::NotCommonField("strike"))
     }
-    fn maturity(&self) -> Result<DateTime<Utc>> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn maturity(&self) -> Result<DateTime<Utc>, OptionError> {
+        Err(OptionError::NotCommonField("maturity"))
     }
-    fn asset_price(&self) -> Result<FloatType> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn asset_price(&self) -> Result<FloatType, OptionError> {
+        Err(OptionError::NotCommonField("asset price"))
     }
-    fn risk_free_rate(&self) -> Result<FloatType> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn risk_free_rate(&self) -> Result<FloatType, OptionError> {
+        Err(OptionError::NotCommonField("risk free rate"))
     }
-    fn dividend_yield(&self) -> Result<FloatType> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn dividend_yield(&self) -> Result<FloatType, OptionError> {
+        Err(OptionError::NotCommonField("dividend yield"))
     }
-    fn option_type(&self) -> Result<OptionType> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn option_type(&self) -> Result<OptionType, OptionError> {
+        Err(OptionError::NotCommonField("option type"))
     }
-    fn option_value(&self) -> Result<OptionValue> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn option_value(&self) -> Result<OptionValue, OptionError> {
+        Err(OptionError::NotCommonField("option value"))
     }
-    fn side(&self) -> Result<OptionSide> {
-        Err(anyhow!("This function is not available for this struct. Because the value you are calling is not a common value."))
+    fn side(&self) -> Result<OptionSide, OptionError> {
+        Err(OptionError::NotCommonField("side"))
     }
+    fn underlying(&self) -> Result<String, OptionError> {
+        Err(OptionError::NotCommonField("underlying"))
+    }
+}
+/// First element of a container, the one the common information is read from.
+fn first<T>(elements: &[T]) -> Result<&T, OptionError> {
+    elements.first().ok_or(OptionError::EmptyChain)
 }
+
 impl ExtractCommonInfo for OptionTick {
-    fn strike(&self) -> Result<DecimalType> {
+    fn strike(&self) -> Result<DecimalType, OptionError> {
         Ok(self.strike)
     }
-    fn maturity(&self) -> Result<DateTime<Utc>> {
+    fn maturity(&self) -> Result<DateTime<Utc>, OptionError> {
         Ok(self.maturity)
     }
-    fn asset_price(&self) -> Result<FloatType> {
+    fn asset_price(&self) -> Result<FloatType, OptionError> {
         Ok(self.asset_price)
     }
-    fn risk_free_rate(&self) -> Result<FloatType> {
+    fn risk_free_rate(&self) -> Result<FloatType, OptionError> {
         Ok(self.risk_free_rate)
     }
-    fn dividend_yield(&self) -> Result<FloatType> {
+    fn dividend_yield(&self) -> Result<FloatType, OptionError> {
         Ok(self.dividend_yield)
     }
-    fn option_type(&self) -> Result<OptionType> {
+    fn option_type(&self) -> Result<OptionType, OptionError> {
         Ok(self.option_type.clone())
     }
-    fn option_value(&self) -> Result<OptionValue> {
+    fn option_value(&self) -> Result<OptionValue, OptionError> {
         Ok(self.option_value.clone())
     }
+    fn underlying(&self) -> Result<String, OptionError> {
+        Ok(self.underlying.clone())
+    }
 }
 
 impl ExtractCommonInfo for StrikeBoard {
-    fn strike(&self) -> Result<DecimalType> {
-        Ok(self.0[0].strike().unwrap())
+    fn strike(&self) -> Result<DecimalType, OptionError> {
+        first(&self.0)?.strike()
     }
-    fn maturity(&self) -> Result<DateTime<Utc>> {
-        Ok(self.0[0].maturity().unwrap())
+    fn maturity(&self) -> Result<DateTime<Utc>, OptionError> {
+        first(&self.0)?.maturity()
     }
-    fn asset_price(&self) -> Result<FloatType> {
-        Ok(self.0[0].asset_price().unwrap())
+    fn asset_price(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.asset_price()
     }
-    fn risk_free_rate(&self) -> Result<FloatType> {
-        Ok(self.0[0].risk_free_rate().unwrap())
+    fn risk_free_rate(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.risk_free_rate()
     }
-    fn dividend_yield(&self) -> Result<FloatType> {
-        Ok(self.0[0].dividend_yield().unwrap())
+    fn dividend_yield(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.dividend_yield()
     }
-    fn option_type(&self) -> Result<OptionType> {
-        Ok(self.0[0].option_type().unwrap())
+    fn option_type(&self) -> Result<OptionType, OptionError> {
+        first(&self.0)?.option_type()
+    }
+    fn underlying(&self) -> Result<String, OptionError> {
+        first(&self.0)?.underlying()
     }
 }
 
 impl<T: OptionBase + ExtractCommonInfo> ExtractCommonInfo for OptionChain<T> {
-    fn asset_price(&self) -> Result<FloatType> {
-        Ok(self.0[0].asset_price().unwrap())
+    fn asset_price(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.asset_price()
     }
 
-    fn maturity(&self) -> Result<DateTime<Utc>> {
-        Ok(self.0[0].maturity().unwrap())
+    fn maturity(&self) -> Result<DateTime<Utc>, OptionError> {
+        first(&self.0)?.maturity()
+    }
+    fn risk_free_rate(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.risk_free_rate()
     }
-    fn risk_free_rate(&self) -> Result<FloatType> {
-        Ok(self.0[0].risk_free_rate().unwrap())
+    fn dividend_yield(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.dividend_yield()
     }
-    fn dividend_yield(&self) -> Result<FloatType> {
-        Ok(self.0[0].dividend_yield().unwrap())
+    fn underlying(&self) -> Result<String, OptionError> {
+        first(&self.0)?.underlying()
     }
 }
 impl<T: OptionBase + ExtractCommonInfo> ExtractCommonInfo for OptionBoard<T> {
-    fn maturity(&self) -> Result<DateTime<Utc>> {
-        Ok(self.0[0].maturity().unwrap())
+    fn maturity(&self) -> Result<DateTime<Utc>, OptionError> {
+        first(&self.0)?.maturity()
+    }
+    fn risk_free_rate(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.risk_free_rate()
     }
-    fn risk_free_rate(&self) -> Result<FloatType> {
-        Ok(self.0[0].risk_free_rate().unwrap())
+    fn dividend_yield(&self) -> Result<FloatType, OptionError> {
+        first(&self.0)?.dividend_yield()
     }
-    fn dividend_yield(&self) -> Result<FloatType> {
-        Ok(self.0[0].dividend_yield().unwrap())
+    fn underlying(&self) -> Result<String, OptionError> {
+        first(&self.0)?.underlying()
     }
 }
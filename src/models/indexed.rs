@@ -0,0 +1,184 @@
+//! Strike-indexed chains and maturity-indexed boards for live updates.
+//!
+//! OptionChain and OptionBoard are Vec based, so every upsert scans the whole chain. IndexedChain keys its StrikeBoards by
+//! (strike, option type) and IndexedBoard keys its chains by maturity in BTreeMaps, so an upsert or delete is O(log n).
+//! Both iterate in key order and convert to and from the Vec based types; they are serialized as those types.
+//!
+//! # Example
+//! ```ignore
+//! let mut board = IndexedBoard::new();
+//! for tick in feed {
+//!     board.upsert(tick);
+//! }
+//! let snapshot: OptionBoard<StrikeBoard> = board.to_board();
+//! ```
+
+use super::crud::{Mutation, CRUD};
+use super::extract_common_info::*;
+use super::schema::Versioned;
+use super::structs::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub type StrikeKey = (DecimalType, OptionType);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "OptionChain<StrikeBoard>", into = "OptionChain<StrikeBoard>")]
+pub struct IndexedChain(pub BTreeMap<StrikeKey, StrikeBoard>);
+
+impl IndexedChain {
+    pub fn get(&self, strike: DecimalType, option_type: OptionType) -> Option<&StrikeBoard> {
+        self.0.get(&(strike, option_type))
+    }
+
+    /// StrikeBoards in ascending order of strike, puts before calls at the same strike (the order of OptionType).
+    pub fn iter(&self) -> impl Iterator<Item = &StrikeBoard> {
+        self.0.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_chain(&self) -> OptionChain<StrikeBoard> {
+        OptionChain(self.0.values().cloned().collect())
+    }
+}
+
+impl From<OptionChain<StrikeBoard>> for IndexedChain {
+    fn from(chain: OptionChain<StrikeBoard>) -> Self {
+        let mut indexed = Self::default();
+        for board in chain.0 {
+            indexed.push(board);
+        }
+        indexed
+    }
+}
+
+impl From<IndexedChain> for OptionChain<StrikeBoard> {
+    fn from(chain: IndexedChain) -> Self {
+        OptionChain(chain.0.into_values().collect())
+    }
+}
+
+impl CRUD for IndexedChain {
+    type DataType = StrikeBoard;
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        let key = (tick.strike, tick.option_type.clone());
+        let board = self.0.entry(key.clone()).or_insert_with(StrikeBoard::new);
+        let mutation = board.upsert(tick);
+        if board.0.is_empty() {
+            self.0.remove(&key);
+        }
+        mutation
+    }
+
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        let key = (tick.strike, tick.option_type.clone());
+        let board = match self.0.get_mut(&key) {
+            Some(board) => board,
+            None => return Mutation::Unchanged,
+        };
+        let mutation = board.delete(tick);
+        if board.0.is_empty() {
+            self.0.remove(&key);
+        }
+        mutation
+    }
+
+    /// Inserts the StrikeBoard, replacing the one with the same strike and option type. Empty boards are ignored.
+    fn push(&mut self, data: StrikeBoard) {
+        if let (Ok(strike), Ok(option_type)) = (data.strike(), data.option_type()) {
+            self.0.insert((strike, option_type), data);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "OptionBoard<StrikeBoard>", into = "OptionBoard<StrikeBoard>")]
+pub struct IndexedBoard(pub BTreeMap<DateTime<Utc>, IndexedChain>);
+
+impl IndexedBoard {
+    pub fn chain(&self, maturity: DateTime<Utc>) -> Option<&IndexedChain> {
+        self.0.get(&maturity)
+    }
+
+    pub fn maturities(&self) -> impl Iterator<Item = &DateTime<Utc>> {
+        self.0.keys()
+    }
+
+    /// Chains in ascending order of maturity.
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedChain> {
+        self.0.values()
+    }
+
+    pub fn to_board(&self) -> OptionBoard<StrikeBoard> {
+        OptionBoard(self.0.values().map(IndexedChain::to_chain).collect())
+    }
+}
+
+impl From<OptionBoard<StrikeBoard>> for IndexedBoard {
+    fn from(board: OptionBoard<StrikeBoard>) -> Self {
+        let mut indexed = Self::default();
+        for chain in board.0 {
+            indexed.push(chain.into());
+        }
+        indexed
+    }
+}
+
+impl From<IndexedBoard> for OptionBoard<StrikeBoard> {
+    fn from(board: IndexedBoard) -> Self {
+        OptionBoard(board.0.into_values().map(OptionChain::from).collect())
+    }
+}
+
+impl CRUD for IndexedBoard {
+    type DataType = IndexedChain;
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn upsert(&mut self, tick: OptionTick) -> Mutation {
+        let maturity = tick.maturity;
+        let chain = self.0.entry(maturity).or_default();
+        let mutation = chain.upsert(tick);
+        if chain.is_empty() {
+            self.0.remove(&maturity);
+        }
+        mutation
+    }
+
+    fn delete(&mut self, tick: OptionTick) -> Mutation {
+        let maturity = tick.maturity;
+        let chain = match self.0.get_mut(&maturity) {
+            Some(chain) => chain,
+            None => return Mutation::Unchanged,
+        };
+        let mutation = chain.delete(tick);
+        if chain.is_empty() {
+            self.0.remove(&maturity);
+        }
+        mutation
+    }
+
+    /// Inserts the chain, replacing the one with the same maturity. Empty chains are ignored.
+    fn push(&mut self, data: IndexedChain) {
+        let maturity = data.iter().next().map(|board| board.0[0].maturity);
+        if let Some(maturity) = maturity {
+            self.0.insert(maturity, data);
+        }
+    }
+}
+
+impl Versioned for IndexedChain {}
+impl Versioned for IndexedBoard {}
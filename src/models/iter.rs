@@ -0,0 +1,95 @@
+//! Standard collection traits for the Vec based containers.
+//!
+//! TimeSeries, StrikeBoard, OptionChain and OptionBoard implement IntoIterator (owned, by reference and by mutable reference),
+//! FromIterator, Extend, Index and IndexMut, so they work with iterator adapters, `collect()` and slicing without reaching into `.0`.
+//! Indexing follows the order of the underlying Vec and accepts ranges, which return a slice.
+//!
+//! # Example
+//! ```ignore
+//! let calls: OptionChain<OptionTick> = chain.iter().filter(|t| t.option_type == OptionType::Call).cloned().collect();
+//! let first_two = &board[..2];
+//! for tick in &chain {
+//!     println!("{}", tick.strike);
+//! }
+//! ```
+
+use super::structs::*;
+use super::time_series::TimeSeries;
+use std::ops::{Index, IndexMut};
+use std::slice::SliceIndex;
+
+macro_rules! vec_container {
+    (impl<$($g:ident),*> $container:ty, $item:ty $(, where $($bound:tt)*)?) => {
+        impl<$($g),*> $container $(where $($bound)*)? {
+            pub fn iter(&self) -> std::slice::Iter<'_, $item> {
+                self.0.iter()
+            }
+
+            pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, $item> {
+                self.0.iter_mut()
+            }
+
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+        }
+
+        impl<$($g),*> IntoIterator for $container $(where $($bound)*)? {
+            type Item = $item;
+            type IntoIter = std::vec::IntoIter<$item>;
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<'a, $($g),*> IntoIterator for &'a $container $(where $($bound)*)? {
+            type Item = &'a $item;
+            type IntoIter = std::slice::Iter<'a, $item>;
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        impl<'a, $($g),*> IntoIterator for &'a mut $container $(where $($bound)*)? {
+            type Item = &'a mut $item;
+            type IntoIter = std::slice::IterMut<'a, $item>;
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter_mut()
+            }
+        }
+
+        impl<$($g),*> FromIterator<$item> for $container $(where $($bound)*)? {
+            fn from_iter<I: IntoIterator<Item = $item>>(iter: I) -> Self {
+                Self(iter.into_iter().collect())
+            }
+        }
+
+        impl<$($g),*> Extend<$item> for $container $(where $($bound)*)? {
+            fn extend<I: IntoIterator<Item = $item>>(&mut self, iter: I) {
+                self.0.extend(iter)
+            }
+        }
+
+        impl<Idx: SliceIndex<[$item]>, $($g),*> Index<Idx> for $container $(where $($bound)*)? {
+            type Output = Idx::Output;
+            fn index(&self, index: Idx) -> &Self::Output {
+                &self.0[index]
+            }
+        }
+
+        impl<Idx: SliceIndex<[$item]>, $($g),*> IndexMut<Idx> for $container $(where $($bound)*)? {
+            fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+                &mut self.0[index]
+            }
+        }
+    };
+}
+
+vec_container!(impl<T> TimeSeries<T>, T);
+vec_container!(impl<> StrikeBoard, OptionTick);
+vec_container!(impl<T> OptionChain<T>, T, where T: OptionBase);
+vec_container!(impl<T> OptionBoard<T>, OptionChain<T>, where T: OptionBase);
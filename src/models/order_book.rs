@@ -0,0 +1,257 @@
+//! Bid/ask ladders of a StrikeBoard.
+//!
+//! A StrikeBoard holds the raw quotes of one strike. ladder() aggregates them into price levels, sorted from the best price,
+//! with the size of each level summed from AdditionalOptionData.volume (0 if not set). Prices are the option values of the ticks,
+//! so a board quoted in IV gives a ladder in IV.
+//!
+//! # Example
+//! ```ignore
+//! let (bids, asks) = strike_board.depth(3);
+//! println!("size at the third bid level: {}", bids[2].size);
+//! println!("spread: {:?}, microprice: {}", strike_board.spread(), strike_board.microprice()?);
+//! let mark = strike_board.mark_with(&[MarkSource::ExchangeMark, MarkSource::Mid, MarkSource::Settlement])?;
+//! ```
+//!
+//! mark() values a strike from the best available price: the mark published by the exchange, then the mid of the top of book,
+//! then the last trade. mark_with() takes the fallback chain explicitly.
+//!
+//! to_ticks() turns a chain or board of StrikeBoards into OptionTicks with one price per strike, for the analytics that work on
+//! OptionChain<OptionTick>:
+//! ```ignore
+//! let chain: OptionChain<OptionTick> = board_chain.to_ticks(PriceSource::Mid)?;
+//! let board: OptionBoard<OptionTick> = strike_boards.to_ticks(PriceSource::Mark)?;
+//! ```
+
+use super::error::OptionError;
+use super::structs::*;
+use serde::{Deserialize, Serialize};
+
+/// Source of the mark price of a StrikeBoard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkSource {
+    /// Latest tick with OptionSide::MarkPrice
+    ExchangeMark,
+    /// Mid of the best bid and the best ask, only if both sides are quoted
+    Mid,
+    /// Latest trade print
+    LastTrade,
+    /// Latest tick with OptionSide::Settlement
+    Settlement,
+}
+
+/// Price of a StrikeBoard taken as its OptionTick by to_ticks().
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceSource {
+    /// StrikeBoard::mid()
+    #[default]
+    Mid,
+    BestBid,
+    BestAsk,
+    /// StrikeBoard::mark()
+    Mark,
+}
+
+/// Fallback chain of StrikeBoard::mark()
+pub const DEFAULT_MARK_SOURCES: [MarkSource; 3] = [MarkSource::ExchangeMark, MarkSource::Mid, MarkSource::LastTrade];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: FloatType,
+    pub size: FloatType,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookLadder {
+    /// Bid levels, highest price first
+    pub bids: Vec<PriceLevel>,
+    /// Ask levels, lowest price first
+    pub asks: Vec<PriceLevel>,
+}
+
+fn aggregate<'a>(ticks: impl Iterator<Item = &'a OptionTick>, descending: bool) -> Vec<PriceLevel> {
+    let mut levels: Vec<PriceLevel> = Vec::new();
+    for tick in ticks {
+        let size = tick.additional_data.as_ref().and_then(|d| d.volume).unwrap_or(0.);
+        match levels.iter_mut().find(|l| l.price == tick.get_value()) {
+            Some(level) => level.size += size,
+            None => levels.push(PriceLevel {
+                price: tick.get_value(),
+                size,
+            }),
+        }
+    }
+    levels.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap());
+    if descending {
+        levels.reverse();
+    }
+    levels
+}
+
+impl BookLadder {
+    /// The first n levels of each side (fewer if the side is shallower)
+    pub fn depth(&self, n: usize) -> (Vec<PriceLevel>, Vec<PriceLevel>) {
+        (
+            self.bids.iter().take(n).cloned().collect(),
+            self.asks.iter().take(n).cloned().collect(),
+        )
+    }
+
+    pub fn spread(&self) -> Option<FloatType> {
+        Some(self.asks.first()?.price - self.bids.first()?.price)
+    }
+
+    /// Size-weighted mid of the top of book: (bid * ask size + ask * bid size) / (bid size + ask size).
+    /// Falls back to the plain mid when both sizes are 0.
+    pub fn microprice(&self) -> Option<FloatType> {
+        let (bid, ask) = (self.bids.first()?, self.asks.first()?);
+        let total = bid.size + ask.size;
+        if total <= 0. {
+            return Some((bid.price + ask.price) / 2.);
+        }
+        Some((bid.price * ask.size + ask.price * bid.size) / total)
+    }
+
+    /// Top of book imbalance (bid size - ask size) / (bid size + ask size), in [-1, 1]. None if a side is empty or both sizes are 0.
+    pub fn imbalance(&self) -> Option<FloatType> {
+        let (bid, ask) = (self.bids.first()?, self.asks.first()?);
+        let total = bid.size + ask.size;
+        (total > 0.).then(|| (bid.size - ask.size) / total)
+    }
+}
+
+impl StrikeBoard {
+    /// Aggregates the quotes into bid and ask price levels. Ticks without a side are ignored.
+    pub fn ladder(&self) -> BookLadder {
+        BookLadder {
+            bids: aggregate(self.0.iter().filter(|t| matches!(t.side, Some(OptionSide::Bid))), true),
+            asks: aggregate(self.0.iter().filter(|t| matches!(t.side, Some(OptionSide::Ask))), false),
+        }
+    }
+
+    pub fn depth(&self, n: usize) -> (Vec<PriceLevel>, Vec<PriceLevel>) {
+        self.ladder().depth(n)
+    }
+
+    pub fn spread(&self) -> Option<FloatType> {
+        self.ladder().spread()
+    }
+
+    /// Microprice of the top of book (see BookLadder::microprice), weighted by the displayed sizes of the ladder and equal to
+    /// the plain mid when no size is displayed. Both sides must be quoted.
+    pub fn microprice(&self) -> Result<FloatType, OptionError> {
+        let ladder = self.ladder();
+        match (ladder.bids.is_empty(), ladder.asks.is_empty()) {
+            (true, true) => Err(OptionError::NoQuotes),
+            (true, false) => Err(OptionError::NoBids),
+            (false, true) => Err(OptionError::NoAsks),
+            (false, false) => ladder.microprice().ok_or(OptionError::NoQuotes),
+        }
+    }
+
+    /// The best bid tick valued at the microprice, in the same kind of value (price or IV) as the quotes.
+    pub fn microprice_tick(&self) -> Result<OptionTick, OptionError> {
+        let microprice = self.microprice()?;
+        let mut tick = self.best_bid()?;
+        tick.option_value = match tick.option_value {
+            OptionValue::Price(_) => OptionValue::Price(microprice),
+            OptionValue::ImpliedVolatility(_) => OptionValue::ImpliedVolatility(microprice),
+        };
+        Ok(tick)
+    }
+
+    pub fn imbalance(&self) -> Option<FloatType> {
+        self.ladder().imbalance()
+    }
+
+    /// Volume traded on the bid and on the ask, summed over the sided ticks of the board. Ticks without volume count as 0.
+    pub fn volume_by_side(&self) -> (FloatType, FloatType) {
+        self.0.iter().fold((0., 0.), |(bid, ask), t| {
+            let volume = t.additional_data.as_ref().and_then(|d| d.volume).unwrap_or(0.);
+            match t.side {
+                Some(OptionSide::Bid) => (bid + volume, ask),
+                Some(OptionSide::Ask) => (bid, ask + volume),
+                _ => (bid, ask),
+            }
+        })
+    }
+
+    /// Traded volume imbalance (bid volume - ask volume) / (bid volume + ask volume), in [-1, 1]. None without sided volume.
+    pub fn volume_imbalance(&self) -> Option<FloatType> {
+        let (bid, ask) = self.volume_by_side();
+        (bid + ask > 0.).then(|| (bid - ask) / (bid + ask))
+    }
+
+    /// Latest tick of the side, by valuation_time (the last one of the board if they have none).
+    fn latest(&self, side: OptionSide) -> Option<OptionTick> {
+        self.0
+            .iter()
+            .filter(|t| t.side.as_ref() == Some(&side))
+            .max_by_key(|t| t.valuation_time)
+            .cloned()
+    }
+
+    /// Mark price published by the exchange.
+    pub fn exchange_mark(&self) -> Option<OptionTick> {
+        self.latest(OptionSide::MarkPrice)
+    }
+
+    pub fn settlement(&self) -> Option<OptionTick> {
+        self.latest(OptionSide::Settlement)
+    }
+
+    /// Mark of the strike from the first source of DEFAULT_MARK_SOURCES that gives a price.
+    pub fn mark(&self) -> Result<OptionTick, OptionError> {
+        self.mark_with(&DEFAULT_MARK_SOURCES)
+    }
+
+    /// Mark of the strike from the first source of `sources` that gives a price.
+    pub fn mark_with(&self, sources: &[MarkSource]) -> Result<OptionTick, OptionError> {
+        sources
+            .iter()
+            .find_map(|source| match source {
+                MarkSource::ExchangeMark => self.exchange_mark(),
+                MarkSource::Mid => match (self.best_bid(), self.best_ask()) {
+                    (Ok(_), Ok(_)) => self.mid().ok(),
+                    _ => None,
+                },
+                MarkSource::LastTrade => self.last_trade(),
+                MarkSource::Settlement => self.settlement(),
+            })
+            .ok_or(OptionError::NoMark)
+    }
+}
+
+impl StrikeBoard {
+    /// The tick of the board priced from the source.
+    pub fn price(&self, source: PriceSource) -> Result<OptionTick, OptionError> {
+        match source {
+            PriceSource::Mid => self.mid(),
+            PriceSource::BestBid => self.best_bid(),
+            PriceSource::BestAsk => self.best_ask(),
+            PriceSource::Mark => self.mark(),
+        }
+    }
+}
+
+impl OptionChain<StrikeBoard> {
+    /// One tick per strike priced from the source. Empty boards are skipped; a board the source cannot price is an error.
+    pub fn to_ticks(&self, source: PriceSource) -> Result<OptionChain<OptionTick>, OptionError> {
+        self.0
+            .iter()
+            .filter(|board| !board.0.is_empty())
+            .map(|board| board.price(source))
+            .collect::<Result<Vec<_>, _>>()
+            .map(OptionChain)
+    }
+}
+
+impl OptionBoard<StrikeBoard> {
+    /// Every chain of the board converted with OptionChain::to_ticks.
+    pub fn to_ticks(&self, source: PriceSource) -> Result<OptionBoard<OptionTick>, OptionError> {
+        self.0
+            .iter()
+            .map(|chain| chain.to_ticks(source))
+            .collect::<Result<Vec<_>, _>>()
+            .map(OptionBoard)
+    }
+}
@@ -0,0 +1,38 @@
+//! Parallel versions of map, available with the `parallel` feature.
+//!
+//! par_map takes the same function as map and runs it on the rayon thread pool. The order of the elements is preserved,
+//! so the result is identical to map; it only pays off when f is expensive, e.g. get_implied_volatility over long TimeSeries.
+//!
+//! # Example
+//! ```ignore
+//! let iv = ts.par_map(|chain| chain.atm().get_implied_volatility().iv());
+//! let board = board.par_map(OptionTick::get_implied_volatility);
+//! ```
+
+use super::structs::*;
+use super::time_series::TimeSeries;
+use rayon::prelude::*;
+
+impl<T: Sync> TimeSeries<T> {
+    pub fn par_map<U: Send>(&self, f: impl Fn(&T) -> U + Sync + Send) -> TimeSeries<U> {
+        TimeSeries(self.0.par_iter().map(f).collect())
+    }
+}
+
+impl<T: OptionBase + Sync> OptionChain<T> {
+    pub fn par_map<U: OptionBase + Send>(&self, f: impl Fn(&T) -> U + Sync + Send) -> OptionChain<U> {
+        OptionChain(self.0.par_iter().map(f).collect())
+    }
+}
+
+impl<T: OptionBase + Sync> OptionBoard<T> {
+    /// Applies f to every element of every chain.
+    pub fn par_map<U: OptionBase + Send>(&self, f: impl Fn(&T) -> U + Sync + Send) -> OptionBoard<U> {
+        OptionBoard(
+            self.0
+                .par_iter()
+                .map(|chain| OptionChain(chain.0.par_iter().map(&f).collect()))
+                .collect(),
+        )
+    }
+}
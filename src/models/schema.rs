@@ -0,0 +1,98 @@
+//! Schema versioning of the serialized containers.
+//!
+//! Containers are persisted inside an envelope `{"schema_version": n, "data": ...}`. When an archive is loaded,
+//! the data is upgraded one version at a time through `Versioned::migrate` until it reaches SCHEMA_VERSION,
+//! and only then deserialized. Archives written before versioning (the bare container) are read as version 0.
+//!
+//! When the layout of a persisted type changes, bump SCHEMA_VERSION and handle the previous version in `migrate`
+//! of the affected containers.
+//!
+//! # Example
+//! ```ignore
+//! board.save_versioned("board.json")?;
+//! let board = OptionBoard::<OptionTick>::load_versioned("board.json")?;
+//! ```
+
+use super::structs::*;
+use super::time_series::TimeSeries;
+use anyhow::{bail, ensure, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// Version of the layout written by this version of the crate.
+/// * 0: bare container, written before versioning
+/// * 1: container in an envelope with schema_version
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Upgrades `value`, written with schema `version`, to `version + 1`.
+    /// The default leaves the value as it is, for types whose layout did not change.
+    fn migrate(version: u32, value: Value) -> Result<Value> {
+        let _ = version;
+        Ok(value)
+    }
+
+    fn to_versioned_value(&self) -> Result<Value> {
+        Ok(json!({
+            "schema_version": SCHEMA_VERSION,
+            "data": serde_json::to_value(self)?,
+        }))
+    }
+
+    /// Reads an envelope or a bare container (version 0) and migrates it to the current schema.
+    fn from_versioned_value(value: Value) -> Result<Self> {
+        let (mut version, mut data) = match value {
+            Value::Object(map) if is_envelope(&map) => {
+                let mut map = map;
+                let version = match map["schema_version"].as_u64() {
+                    Some(v) => v as u32,
+                    None => bail!("schema_version must be an unsigned integer"),
+                };
+                (version, map.remove("data").unwrap())
+            }
+            value => (0, value),
+        };
+        ensure!(
+            version <= SCHEMA_VERSION,
+            "The data was written with schema version {}, newer than the supported version {}",
+            version,
+            SCHEMA_VERSION
+        );
+        while version < SCHEMA_VERSION {
+            data = Self::migrate(version, data)?;
+            version += 1;
+        }
+        Ok(serde_json::from_value(data)?)
+    }
+
+    fn to_versioned_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_versioned_value()?)?)
+    }
+
+    fn from_versioned_json(json: &str) -> Result<Self> {
+        Self::from_versioned_value(serde_json::from_str(json)?)
+    }
+
+    fn save_versioned(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_versioned_json()?)?;
+        Ok(())
+    }
+
+    fn load_versioned(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_versioned_json(&fs::read_to_string(path)?)
+    }
+}
+
+fn is_envelope(map: &Map<String, Value>) -> bool {
+    map.len() == 2 && map.contains_key("schema_version") && map.contains_key("data")
+}
+
+impl Versioned for OptionTick {}
+impl Versioned for StrikeBoard {}
+impl<T: OptionBase + Serialize + DeserializeOwned> Versioned for OptionChain<T> {}
+impl<T: OptionBase + Serialize + DeserializeOwned> Versioned for OptionBoard<T> {}
+impl<T: OptionBase + Serialize + DeserializeOwned> Versioned for OptionUniverse<T> {}
+impl<T: Serialize + DeserializeOwned> Versioned for TimeSeries<T> {}
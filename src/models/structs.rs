@@ -1,8 +1,14 @@
+use std::collections::BTreeMap;
 use std::ops::*;
+use super::day_count::DayCount;
+use crate::discount_curve::{DiscountCurve, ZeroCurve};
+use crate::contract::ContractSpec;
+use crate::dividend::DividendSchedule;
+use super::error::OptionError;
 use super::extract_common_info::*;
 use crate::black_scholes::BlackScholes;
 use crate::greeks::EuropeanGreeks;
-use anyhow::{anyhow, ensure, Result};
+use crate::math::average_by_x;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
@@ -12,22 +18,87 @@ pub type FloatType = f64;
 pub type DecimalType = Decimal;
 
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum OptionType {
     Put,
     Call,
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub enum OptionStyle {
+    #[default]
     European,
     American,
 }
 
+/// Numerical method used by OptionTick::price_with_engine().
+/// * Analytic: closed-form Black-Scholes for European options. American options fall back to the default finite difference grid.
+/// * FiniteDifference: Crank-Nicolson scheme on a uniform grid of the asset price.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PricingEngine {
+    #[default]
+    Analytic,
+    FiniteDifference {
+        space_steps: usize,
+        time_steps: usize,
+    },
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BarrierKind {
+    UpAndOut,
+    UpAndIn,
+    DownAndOut,
+    DownAndIn,
+}
+
+/// Continuously monitored barrier.
+#[derive(Clone, Debug, PartialEq, TypedBuilder, Serialize, Deserialize)]
+pub struct BarrierSpec {
+    pub kind: BarrierKind,
+    pub level: FloatType,
+    /// Paid when a knock-out barrier is hit. Not used for knock-in barriers.
+    #[builder(default = 0.)]
+    pub rebate: FloatType,
+}
+
+/// Kind of price a tick carries. Bid and Ask are quotes; a tick without a side is treated as a trade print.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OptionSide {
     Bid,
     Ask,
+    /// Last traded price
+    Trade,
+    /// Mark price published by the venue, e.g. the Deribit mark
+    MarkPrice,
+    /// Official settlement price of the session
+    Settlement,
+}
+
+/// Model used to interpret the underlying of an OptionTick.
+/// * BlackScholes: asset_price is a spot price carrying risk_free_rate and dividend_yield.
+/// * Black76: asset_price is the price of a futures contract, which has no cost of carry.
+/// * Bachelier: asset_price is a forward following a normal (arithmetic) process, which may be negative; see the bachelier module.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PricingModel {
+    #[default]
+    BlackScholes,
+    Black76,
+    Bachelier,
+}
+
+/// Quotes a smile is built from. ITM quotes carry wider spreads and, for American options, early exercise premium,
+/// so the OTM side is the usual choice.
+/// * OtmOnly: puts below the underlying price and calls at or above it
+/// * CallsOnly / PutsOnly: one option type across all strikes
+/// * Blend: calls and puts, with the IVs quoted at the same strike averaged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmileSource {
+    #[default]
+    OtmOnly,
+    CallsOnly,
+    PutsOnly,
+    Blend,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -45,6 +116,10 @@ pub struct AdditionalOptionData {
 
 #[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct OptionTick {
+    /// Symbol of the underlying, e.g. "BTC" or "N225". Empty if the workflow has a single underlying.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub underlying: String,
     pub strike: DecimalType,
     pub maturity: DateTime<Utc>,
     pub asset_price: FloatType,
@@ -60,9 +135,62 @@ pub struct OptionTick {
 
     #[builder(default=None, setter(strip_option))]
     pub additional_data: Option<AdditionalOptionData>,
+
+    #[builder(default)]
+    #[serde(default)]
+    pub pricing_model: PricingModel,
+
+    #[builder(default)]
+    #[serde(default)]
+    pub option_style: OptionStyle,
+
+    #[builder(default)]
+    #[serde(default)]
+    pub pricing_engine: PricingEngine,
+
+    /// Instant at which the tick is valued. If None, tau() is measured from Utc::now().
+    /// Set it to make pricing and greeks reproducible, e.g. in backtests.
+    #[builder(default=None, setter(strip_option))]
+    #[serde(default)]
+    pub valuation_time: Option<DateTime<Utc>>,
+
+    /// Convention used by tau()
+    #[builder(default)]
+    #[serde(default)]
+    pub day_count: DayCount,
+
+    /// If set, discounting uses the zero rate of the curve at the maturity of the tick instead of risk_free_rate.
+    #[builder(default=None, setter(strip_option))]
+    #[serde(default)]
+    pub discount_curve: Option<ZeroCurve>,
+
+    /// If set, the carry of a spot underlying is the zero rate of the curve at the maturity of the tick instead of dividend_yield.
+    #[builder(default=None, setter(strip_option))]
+    #[serde(default)]
+    pub dividend_curve: Option<ZeroCurve>,
+
+    /// Discrete cash dividends, priced with the escrowed dividend model in addition to dividend_yield.
+    #[builder(default=None, setter(strip_option))]
+    #[serde(default)]
+    pub dividends: Option<DividendSchedule>,
+
+    /// Multiplier, currency and tick size of the contract, used by the cash greeks.
+    #[builder(default=None, setter(strip_option))]
+    #[serde(default)]
+    pub contract_spec: Option<ContractSpec>,
 }
 
 impl OptionTick {
+    /// Strike as FloatType, for code written against float strikes.
+    pub fn strike_f64(&self) -> FloatType {
+        self.strike.to_f64().unwrap()
+    }
+
+    /// Sets the strike from a FloatType, for code written against float strikes.
+    pub fn set_strike_f64(&mut self, strike: FloatType) {
+        self.strike = Decimal::from_f64(strike).unwrap();
+    }
+
     /// Retrieve value from option_value
     /// Caution:
     /// Be careful when handling this function, as it extracts the value without distinguishing between premium and iv.
@@ -89,9 +217,91 @@ impl OptionTick {
         }
     }
 
+    /// Same as iv(), but returns OptionError::IvNotConverged instead of panicking or returning a non-finite IV
+    /// when no volatility reprices the premium.
+    pub fn try_iv(&self) -> Result<FloatType, OptionError> {
+        let price = match self.option_value {
+            OptionValue::Price(price) => price,
+            OptionValue::ImpliedVolatility(iv) => return Ok(iv),
+        };
+        let iv = self.get_implied_volatility().get_value();
+        let mut repriced = self.clone();
+        repriced.option_value = OptionValue::ImpliedVolatility(iv);
+        let error = repriced.get_theoretical_price().get_value() - price;
+        if !iv.is_finite() || iv <= 0. || !error.is_finite() || error.abs() > 1e-4 * price.abs().max(1.) {
+            return Err(OptionError::IvNotConverged);
+        }
+        Ok(iv)
+    }
+
+    /// Returns the continuous yield q that is used as the carry of the underlying: the zero rate of dividend_curve at tau if set,
+    /// dividend_yield otherwise. Under Black-76 and Bachelier the underlying is a futures contract, so q is equal to the risk free rate and the forward equals asset_price.
+    pub fn carry_yield(&self) -> FloatType {
+        match self.pricing_model {
+            PricingModel::BlackScholes => match &self.dividend_curve {
+                Some(curve) => curve.zero_rate(self.tau()),
+                None => self.dividend_yield,
+            },
+            PricingModel::Black76 | PricingModel::Bachelier => self.rate(),
+        }
+    }
+
+    /// Time to maturity in years with the day_count of the tick (Act/365 by default), from valuation_time or from now if it is not set.
     pub fn tau(&self) -> FloatType {
-        let now = Utc::now();
-        (self.maturity - now).num_seconds() as FloatType / 31536000.
+        let now = self.valuation_time.unwrap_or_else(Utc::now);
+        self.tau_with(&self.day_count, now)
+    }
+
+    /// Continuously compounded rate used for discounting: the zero rate of discount_curve at tau if set, risk_free_rate otherwise.
+    pub fn rate(&self) -> FloatType {
+        match &self.discount_curve {
+            Some(curve) => curve.zero_rate(self.tau()),
+            None => self.risk_free_rate,
+        }
+    }
+
+    /// Discount factor to maturity, e^{-r tau}
+    pub fn discount_factor(&self) -> FloatType {
+        (-self.rate() * self.tau()).exp()
+    }
+
+    /// Discount factor of the carry to maturity, e^{-q tau}
+    pub fn carry_discount_factor(&self) -> FloatType {
+        (-self.carry_yield() * self.tau()).exp()
+    }
+
+    pub fn with_discount_curve(&self, curve: ZeroCurve) -> Self {
+        Self {
+            discount_curve: Some(curve),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_dividend_curve(&self, curve: ZeroCurve) -> Self {
+        Self {
+            dividend_curve: Some(curve),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the tick valued at `time`.
+    pub fn with_valuation_time(&self, time: DateTime<Utc>) -> Self {
+        Self {
+            valuation_time: Some(time),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy valued `days` calendar days after its valuation time (now if it is not set). Its tau follows the day
+    /// count, and the dividends going ex in between drop out of its escrowed spot.
+    pub fn with_days_elapsed(&self, days: FloatType) -> Self {
+        let now = self.valuation_time.unwrap_or_else(Utc::now);
+        self.with_valuation_time(now + chrono::Duration::seconds((days * 86400.) as i64))
+    }
+
+    /// Time to maturity in years measured from `now` with the given day count convention.
+    pub fn tau_with(&self, day_count: &DayCount, now: DateTime<Utc>) -> FloatType {
+        day_count.year_fraction(now, self.maturity)
     }
 }
 
@@ -101,13 +311,16 @@ pub struct StrikeBoard(pub Vec<OptionTick>);
 
 impl StrikeBoard {
     /// The best_bid() function is a method of the StrikeBoard struct in Rust. It takes the self reference to an instance of StrikeBoard and returns the OptionTick instance with the highest value for bids.
-    fn best_bid(&self) -> Result<OptionTick> {
+    /// Ticks without a side (trade prints) are not quotes and are skipped; see trades().
+    pub fn best_bid(&self) -> Result<OptionTick, OptionError> {
         let ticks = self.0.clone();
         let bid_ticks = ticks
             .iter()
-            .filter(|t| matches!(t.side.as_ref().unwrap(), OptionSide::Bid))
+            .filter(|t| matches!(t.side, Some(OptionSide::Bid)))
             .collect::<Vec<&OptionTick>>();
-        ensure!(!bid_ticks.is_empty(), "No bid ticks in strikeboard");
+        if bid_ticks.is_empty() {
+            return Err(OptionError::NoBids);
+        }
 
 
 		let mut best_bid = bid_ticks[0].clone();
@@ -122,13 +335,16 @@ impl StrikeBoard {
     }
 
     /// The best_ask() function is a method of the StrikeBoard struct in Rust. It takes the self reference to an instance of StrikeBoard and returns the OptionTick instance with the lowest value for asks.
-    fn best_ask(&self) -> Result<OptionTick> {
+    /// Ticks without a side (trade prints) are not quotes and are skipped; see trades().
+    pub fn best_ask(&self) -> Result<OptionTick, OptionError> {
         let ticks = self.0.clone();
         let ask_ticks = ticks
             .iter()
-            .filter(|t| matches!(t.side.as_ref().unwrap(), OptionSide::Ask))
+            .filter(|t| matches!(t.side, Some(OptionSide::Ask)))
             .collect::<Vec<&OptionTick>>();
-        ensure!(!ask_ticks.is_empty(), "No ask ticks in strikeboard");
+        if ask_ticks.is_empty() {
+            return Err(OptionError::NoAsks);
+        }
 
         let mut best_ask = ask_ticks[0].clone();
         for tick in ask_ticks {
@@ -141,39 +357,29 @@ impl StrikeBoard {
         Ok(best_ask)
     }
 
-    /// The mid() function is a method of the StrikeBoard struct in Rust. It takes the self reference to an instance of StrikeBoard and calculates the mid-point between the OptionTick instance with the highest bid value and the OptionTick instance with the lowest ask value. It then returns an OptionTick instance with the calculated mid-point value.
-    pub fn mid(&self) -> Result<OptionTick> {
-        let best_bid = self.best_bid();
-        let best_ask = self.best_ask();
+    /// Trade prints mixed with the quotes in live data (ticks with OptionSide::Trade or without a side), in the order of the board.
+    pub fn trades(&self) -> Vec<OptionTick> {
+        self.0.iter().filter(|t| is_trade(t)).cloned().collect()
+    }
 
-        let mid_tick = match (best_bid, best_ask) {
-            (Ok(bid), Ok(ask)) => {
-                let mut tick = bid.clone();
-                let mid = (bid.get_value() + ask.get_value()) / 2.;
-                tick.option_value = OptionValue::Price(mid);
-                tick.side = None;
-                tick
-            }
-            (Err(_), Ok(ask)) => ask,
-            (Ok(bid), Err(_)) => bid,
-            (Err(_), Err(_)) => {
-                return Err(anyhow!("No bid or ask ticks in strikeboard"));
-            }
-        };
-        Ok(mid_tick)
+    /// The most recent trade print: the one with the latest valuation_time, or the last one of the board if they have none.
+    pub fn last_trade(&self) -> Option<OptionTick> {
+        self.0
+            .iter()
+            .filter(|t| is_trade(t))
+            .max_by_key(|t| t.valuation_time)
+            .cloned()
     }
 
-    pub fn mid_weighted(&self) -> OptionTick {
+    /// The mid() function is a method of the StrikeBoard struct in Rust. It takes the self reference to an instance of StrikeBoard and calculates the mid-point between the OptionTick instance with the highest bid value and the OptionTick instance with the lowest ask value. It then returns an OptionTick instance with the calculated mid-point value.
+    pub fn mid(&self) -> Result<OptionTick, OptionError> {
         let best_bid = self.best_bid();
         let best_ask = self.best_ask();
 
         let mid_tick = match (best_bid, best_ask) {
             (Ok(bid), Ok(ask)) => {
                 let mut tick = bid.clone();
-                let mid = (bid.get_value() * bid.additional_data.as_ref().unwrap().volume.unwrap()
-						   + ask.get_value() * ask.additional_data.as_ref().unwrap().volume.unwrap())
-                    / (bid.additional_data.as_ref().unwrap().volume.unwrap()
-                       + ask.additional_data.as_ref().unwrap().volume.unwrap());
+                let mid = (bid.get_value() + ask.get_value()) / 2.;
                 tick.option_value = OptionValue::Price(mid);
                 tick.side = None;
                 tick
@@ -181,10 +387,10 @@ impl StrikeBoard {
             (Err(_), Ok(ask)) => ask,
             (Ok(bid), Err(_)) => bid,
             (Err(_), Err(_)) => {
-				panic!("No Bid and Ask on a StrikeBoard")
+                return Err(OptionError::NoQuotes);
             }
         };
-		mid_tick
+        Ok(mid_tick)
     }
 }
 
@@ -231,27 +437,84 @@ where
 // 	}
 
 // }
-// impl OptionChain<StrikeBoard>{
-// 	pub fn otm(&self) -> Self{
-// 	    let asset_price = self.asset_price().unwrap();
-//         let mut otm_chain = self.clone();
-//         // Extract a call if the strike is higher than the underlying asset price, and extract a put if the strike is lower.
-//         otm_chain.0.retain(|t| {
-//             (matches!(t.option_type, OptionType::Call) && t.strike >= asset_price)
-//                 || (matches!(t.option_type, OptionType::Put) && t.strike < asset_price)
-//         });
-
-//         otm_chain
+/// The option type of a board, None if it is empty.
+fn board_type(board: &StrikeBoard) -> Option<OptionType> {
+    board.0.first().map(|t| t.option_type.clone())
+}
 
-// 	}
+/// Selectors of a chain of live boards. The ones returning a tick value each strike at its mark (see StrikeBoard::mark)
+/// and then work as on OptionChain<OptionTick>; strikes without a mark are left out.
+impl OptionChain<StrikeBoard> {
+    /// One tick per strike, valued at the mark of the board.
+    fn marks(&self) -> OptionChain<OptionTick> {
+        OptionChain(self.0.iter().filter_map(|board| board.mark().ok()).collect())
+    }
 
-// 	pub fn atm(&self) -> StrikeBoard{
+    /// The OTM boards: calls with a strike at or above the underlying price and puts below it. Empty boards are dropped.
+    /// Fails if the chain has no underlying price, e.g. when it is empty.
+    pub fn otm(&self) -> Result<Self, OptionError> {
+        let asset_price = self.asset_price()?;
+        let mut otm_chain = self.clone();
+        otm_chain.0.retain(|board| match board.0.first() {
+            Some(t) => match t.option_type {
+                OptionType::Call => t.strike_f64() >= asset_price,
+                OptionType::Put => t.strike_f64() < asset_price,
+            },
+            None => false,
+        });
+        Ok(otm_chain)
+    }
 
-// 	}
+    /// ATM tick interpolated between the marks of the OTM put and call closest to the underlying (see OptionChain::try_atm).
+    pub fn atm(&self) -> Result<OptionTick, OptionError> {
+        self.marks().try_atm()
+    }
 
-// }
+    pub fn call(&self) -> Self {
+        let mut call_chain = self.clone();
+        call_chain.0.retain(|board| board_type(board) == Some(OptionType::Call));
+        call_chain
+    }
+
+    pub fn put(&self) -> Self {
+        let mut put_chain = self.clone();
+        put_chain.0.retain(|board| board_type(board) == Some(OptionType::Put));
+        put_chain
+    }
+
+    pub fn call_25delta(&self) -> Result<OptionTick, OptionError> {
+        self.marks().call_25delta()
+    }
+
+    pub fn call_50delta(&self) -> Result<OptionTick, OptionError> {
+        self.marks().call_50delta()
+    }
+
+    pub fn put_25delta(&self) -> Result<OptionTick, OptionError> {
+        self.marks().put_25delta()
+    }
+
+    pub fn put_50delta(&self) -> Result<OptionTick, OptionError> {
+        self.marks().put_50delta()
+    }
+
+    /// Call synthesized at exactly the target delta from the marks (see OptionChain::call_delta).
+    pub fn call_delta(&self, target: FloatType) -> Result<OptionTick, OptionError> {
+        self.marks().call_delta(target)
+    }
+
+    /// Put synthesized at exactly the target delta from the marks (see OptionChain::put_delta).
+    pub fn put_delta(&self, target: FloatType) -> Result<OptionTick, OptionError> {
+        self.marks().put_delta(target)
+    }
+}
 
 impl OptionChain<OptionTick> {
+    /// Returns a copy of the chain with every tick valued at `time`.
+    pub fn with_valuation_time(&self, time: DateTime<Utc>) -> Self {
+        self.map(|tick| tick.with_valuation_time(time))
+    }
+
     pub fn otm(&self) -> Self {
         let asset_price = self.asset_price().unwrap();
         let mut otm_chain = self.clone();
@@ -264,7 +527,13 @@ impl OptionChain<OptionTick> {
         otm_chain
     }
     pub fn atm(&self) -> OptionTick {
-        let asset_price = self.asset_price().unwrap();
+        self.try_atm().unwrap()
+    }
+
+    /// Same as atm(), but returns an OptionError instead of panicking, e.g. OptionError::EmptyChain if the chain has no
+    /// OTM option.
+    pub fn try_atm(&self) -> Result<OptionTick, OptionError> {
+        let asset_price = self.asset_price()?;
         let atm_chain = self.clone().otm();
         // Perform linear interpolation of put and call
         let put = atm_chain.put();
@@ -273,7 +542,7 @@ impl OptionChain<OptionTick> {
         let best_put: &OptionTick;
         let best_call: &OptionTick;
         if put.0.is_empty() && call.0.is_empty() {
-            panic!("There is no put or call in the option chain.");
+            return Err(OptionError::EmptyChain);
         } else if put.0.is_empty() {
             best_call = call
                 .0
@@ -337,7 +606,7 @@ impl OptionChain<OptionTick> {
 		tick.strike = Decimal::from_f64(strike).unwrap();
 		tick.option_value = option_value;
 		tick.option_type = OptionType::Call;
-		tick
+		Ok(tick)
 	}
 
     pub fn call(&self) -> Self {
@@ -358,70 +627,85 @@ impl OptionChain<OptionTick> {
         put_chain
     }
 
-    pub fn call_25delta(&self) -> OptionTick {
-        let call_chain = self.call();
-        let delta_chain: Vec<FloatType> = call_chain.0.iter().map(|t| t.delta()).collect();
+    pub fn call_25delta(&self) -> Result<OptionTick, OptionError> {
+        closest_delta(&self.call(), 0.25)
+    }
 
-        // Get the index with delta closest to 0.25
-        let mut index = 0;
-        let mut delta = delta_chain[0];
-        for (i, d) in delta_chain.iter().enumerate() {
-            if (d - 0.25).abs() < (delta - 0.25).abs() {
-                index = i;
-                delta = *d;
-            }
-        }
+    pub fn call_50delta(&self) -> Result<OptionTick, OptionError> {
+        closest_delta(&self.call(), 0.5)
+    }
 
-        call_chain.0[index].clone()
+    pub fn put_25delta(&self) -> Result<OptionTick, OptionError> {
+        closest_delta(&self.put(), -0.25)
     }
 
-    pub fn call_50delta(&self) -> OptionTick {
-        let call_chain = self.call();
-        let delta_chain: Vec<FloatType> = call_chain.0.iter().map(|t| t.delta()).collect();
+    pub fn put_50delta(&self) -> Result<OptionTick, OptionError> {
+        closest_delta(&self.put(), -0.5)
+    }
 
-        // Get the index with delta closest to 0.5
-        let mut index = 0;
-        let mut delta = delta_chain[0];
-        for (i, d) in delta_chain.iter().enumerate() {
-            if (d - 0.5).abs() < (delta - 0.5).abs() {
-                index = i;
-                delta = *d;
-            }
-        }
-        call_chain.0[index].clone()
+    /// Returns a call synthesized at exactly the target delta (e.g. 0.25).
+    /// Unlike call_25delta(), which picks the nearest listed strike, the IV is interpolated linearly in strike between the two listed calls whose deltas bracket the target, and the strike is solved so that the synthesized option has the target delta.
+    /// The returned OptionTick holds an OptionValue::ImpliedVolatility.
+    pub fn call_delta(&self, target: FloatType) -> Result<OptionTick, OptionError> {
+        self.call().interpolate_delta(target)
     }
 
-    pub fn put_25delta(&self) -> OptionTick {
-        let put_chain = self.put();
-        let delta_chain: Vec<FloatType> = put_chain.0.iter().map(|t| t.delta()).collect();
+    /// Returns a put synthesized at exactly the target delta (e.g. -0.25).
+    /// See call_delta().
+    pub fn put_delta(&self, target: FloatType) -> Result<OptionTick, OptionError> {
+        self.put().interpolate_delta(target)
+    }
 
-        // Get the index with delta closest to -0.25
-        let mut index = 0;
-        let mut delta = delta_chain[0];
-        for (i, d) in delta_chain.iter().enumerate() {
-            if (d + 0.25).abs() < (delta + 0.25).abs() {
-                index = i;
-                delta = *d;
-            }
+    fn interpolate_delta(&self, target: FloatType) -> Result<OptionTick, OptionError> {
+        let chain = self.sort_by_strike().map(|t| t.get_implied_volatility());
+        let points: Vec<(&OptionTick, FloatType, FloatType)> = chain
+            .0
+            .iter()
+            .map(|t| (t, t.strike.to_f64().unwrap(), t.get_value()))
+            .filter(|(t, _, iv)| iv.is_finite() && t.delta().is_finite())
+            .collect();
+        if points.is_empty() {
+            return Err(OptionError::NoDelta);
         }
-        put_chain.0[index].clone()
-    }
 
-    pub fn put_50delta(&self) -> OptionTick {
-        let put_chain = self.put();
-        let delta_chain: Vec<FloatType> = put_chain.0.iter().map(|t| t.delta()).collect();
+        let synthesize = |template: &OptionTick, strike: FloatType, iv: FloatType| {
+            let mut tick = template.clone();
+            tick.strike = Decimal::from_f64(strike).unwrap().round_dp(8);
+            tick.option_value = OptionValue::ImpliedVolatility(iv);
+            tick
+        };
 
-        // Get the index with delta closest to -0.5
-        let mut index = 0;
-        let mut delta = delta_chain[0];
-        for (i, d) in delta_chain.iter().enumerate() {
-            if (d + 0.5).abs() < (delta + 0.5).abs() {
-                index = i;
-                delta = *d;
+        for w in points.windows(2) {
+            let (lower, k1, iv1) = w[0];
+            let (_, k2, iv2) = w[1];
+            let f1 = lower.delta() - target;
+            let f2 = w[1].0.delta() - target;
+            if f1 * f2 > 0. {
+                continue;
+            }
+
+            // bisection on the strike, with IV linear in strike
+            let iv_at = |k: FloatType| iv1 + (iv2 - iv1) * (k - k1) / (k2 - k1);
+            let (mut a, mut b, mut fa) = (k1, k2, f1);
+            for _ in 0..100 {
+                let m = (a + b) / 2.;
+                let fm = synthesize(lower, m, iv_at(m)).delta() - target;
+                if fa * fm <= 0. {
+                    b = m;
+                } else {
+                    a = m;
+                    fa = fm;
+                }
+                if (b - a).abs() < 1e-8 * k1.abs().max(1.) {
+                    break;
+                }
             }
+            let strike = (a + b) / 2.;
+            return Ok(synthesize(lower, strike, iv_at(strike)));
         }
-        put_chain.0[index].clone()
+        Err(OptionError::DeltaOutOfRange(target))
     }
+
 	pub fn smile_curve(&self) -> (Vec<FloatType>, Vec<FloatType>){
 		let mut smile_curve:Vec<FloatType> = Vec::new();
 		let mut strikes:Vec<FloatType> = Vec::new();
@@ -437,11 +721,65 @@ impl OptionChain<OptionTick> {
 		(strikes,smile_curve)
 	}
 
+    /// The ticks of the chain a smile of the source is built from.
+    pub fn smile_ticks(&self, source: SmileSource) -> Self {
+        match source {
+            SmileSource::OtmOnly => self.otm(),
+            SmileSource::CallsOnly => self.call(),
+            SmileSource::PutsOnly => self.put(),
+            SmileSource::Blend => self.clone(),
+        }
+    }
+
+    /// (strikes, IVs) of the smile built from the source, sorted by strike with one point per strike.
+    /// Unlike smile_curve(), which returns every tick, IVs quoted at the same strike are averaged.
+    pub fn smile_curve_with(&self, source: SmileSource) -> (Vec<FloatType>, Vec<FloatType>) {
+        let (strikes, ivs) = self.smile_ticks(source).smile_curve();
+        let points: Vec<(FloatType, FloatType)> = strikes.into_iter().zip(ivs).collect();
+        average_by_x(&points)
+    }
+
+    /// Returns the 25 delta risk reversal, i.e. IV of the 25 delta call minus IV of the 25 delta put.
+    /// A negative value means that downside protection is bid (put skew).
+    pub fn risk_reversal_25d(&self) -> Result<FloatType, OptionError> {
+        Ok(self.call_25delta()?.try_iv()? - self.put_25delta()?.try_iv()?)
+    }
+
+    /// Returns the 25 delta butterfly, i.e. the average IV of the 25 delta call and put minus the ATM IV.
+    /// It measures the convexity (smile) of the IV curve.
+    pub fn butterfly_25d(&self) -> Result<FloatType, OptionError> {
+        Ok((self.call_25delta()?.try_iv()? + self.put_25delta()?.try_iv()?) / 2. - self.try_atm()?.try_iv()?)
+    }
+
+    /// Returns the slope of the smile between the 25 delta put and the 25 delta call, measured per unit of log-moneyness:
+    /// `(iv_25c - iv_25p) / ln(K_25c / K_25p)`
+    pub fn skew_slope(&self) -> Result<FloatType, OptionError> {
+        let call = self.call_25delta()?;
+        let put = self.put_25delta()?;
+        Ok((call.try_iv()? - put.try_iv()?) / (call.strike.to_f64().unwrap() / put.strike.to_f64().unwrap()).ln())
+    }
+
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct OptionBoard<T: OptionBase>(pub Vec<OptionChain<T>>);
 
+fn is_trade(tick: &OptionTick) -> bool {
+    matches!(tick.side, None | Some(OptionSide::Trade))
+}
+
+/// Returns the tick of the chain whose delta is closest to `target`. Ticks whose delta is not defined are skipped,
+/// and OptionError::NoDelta is returned if no tick is left.
+fn closest_delta(chain: &OptionChain<OptionTick>, target: FloatType) -> Result<OptionTick, OptionError> {
+    chain
+        .0
+        .iter()
+        .filter_map(|t| t.get_implied_volatility().try_delta().ok().map(|d| (t, d)))
+        .min_by(|(_, a), (_, b)| (a - target).abs().partial_cmp(&(b - target).abs()).unwrap())
+        .map(|(t, _)| t.clone())
+        .ok_or(OptionError::NoDelta)
+}
+
 impl<T> OptionBoard<T>
 where
     T: OptionBase + ExtractCommonInfo,
@@ -475,6 +813,81 @@ where
         let sorted_board = self.sort_by_maturity();
         sorted_board.0[index].clone()
 	}
+
+    /// Same as get_front_month(), but returns OptionError::EmptyChain instead of panicking on an empty board.
+    pub fn try_get_front_month(&self) -> Result<OptionChain<T>, OptionError> {
+        self.try_get(0)
+    }
+
+    /// Same as get(), but returns an OptionError instead of panicking: OptionError::EmptyChain if the board has no chain
+    /// at `index`, or the error of a chain whose maturity is not defined.
+    pub fn try_get(&self, index: usize) -> Result<OptionChain<T>, OptionError> {
+        let mut chains = self
+            .0
+            .iter()
+            .map(|chain| Ok((chain.maturity()?, chain)))
+            .collect::<Result<Vec<_>, OptionError>>()?;
+        chains.sort_by_key(|(maturity, _)| *maturity);
+        chains
+            .get(index)
+            .map(|(_, chain)| (*chain).clone())
+            .ok_or(OptionError::EmptyChain)
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Returns a copy of the board with every tick valued at `time`.
+    pub fn with_valuation_time(&self, time: DateTime<Utc>) -> Self {
+        OptionBoard(
+            self.0
+                .iter()
+                .map(|chain| chain.map(|tick| tick.with_valuation_time(time)))
+                .collect(),
+        )
+    }
+}
+
+/// A set of OptionBoards keyed by the symbol of the underlying asset, used for multi-name workflows.
+/// Its CRUD upsert dispatches each tick to the board of OptionTick::underlying.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OptionUniverse<T: OptionBase>(pub BTreeMap<String, OptionBoard<T>>);
+
+/// Name of an OptionUniverse used as the live container of a multi-underlying feed.
+pub type Market<T> = OptionUniverse<T>;
+
+impl<T: OptionBase> OptionUniverse<T> {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn insert(&mut self, symbol: &str, board: OptionBoard<T>) {
+        self.0.insert(symbol.to_string(), board);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&OptionBoard<T>> {
+        self.0.get(symbol)
+    }
+
+    pub fn underlyings(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+}
+
+impl<T: OptionBase> Default for OptionUniverse<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Price of the underlying asset (spot, or a futures contract) at an instant, used for delta-one positions.
+#[derive(Clone, Debug, PartialEq, TypedBuilder, Serialize, Deserialize)]
+pub struct UnderlyingTick {
+    /// Symbol of the underlying asset, matching OptionTick::underlying
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub underlying: String,
+    pub price: FloatType,
+    pub timestamp: DateTime<Utc>,
 }
 
 pub trait OptionBase: Clone {}
@@ -30,6 +30,29 @@
 //! let ts3 = &ts1 + &ts2;
 //! let ts4 = ts1 + ts2;
 //! ```
+//!
+//! A TimeSeries\<f64\> is also combined with a scalar, which is broadcast to every element, on either side:
+//! ```rust
+//! let normalized = (&gex - mean) / std;
+//! let inverted = 1. / &ts;
+//! ```
+//! and has the unary transforms abs, ln, exp, cumsum, diff, pct_change, shift and lag. Transforms that look back
+//! keep the length of the series and put NaN where the previous value is missing, so the result stays aligned with the input.
+//!
+//! # Combining series
+//! zip() pairs two series of different types observed at the same instants, and map2() applies a function to both,
+//! e.g. to relate each chain snapshot to the futures price at the same time:
+//! ```rust
+//! let basis = chains.map2(&futures, |chain, future| future - chain.atm().asset_price);
+//! let report = hedger.run(&chains.zip(&spots))?;
+//! ```
+//!
+//! # Errors
+//! Computations that can fail per element (e.g. an IV that does not converge on one snapshot) give a TimeSeries\<Result\<T\>\>.
+//! Instead of unwrap(), which panics on the first error, use try_map() to fail with it, or resolve an ErrorPolicy:
+//! ```rust
+//! let atm_delta = snapshots.map_with_policy(ErrorPolicy::FillPrevious, |chain| Ok(chain.try_atm()?.try_delta()?))?;
+//! ```
 //! ### Attention.
 //! I don't know why, but it seems that an error is detected by rust-analyzer regarding TimeSeries\<T\> @ TimeSeries\<T\>. It actually works, but may be a bit of a hindrance when coding.
 //! # Mapping
@@ -61,10 +84,10 @@
 //! Similarly, you can extract the 25delta call and put option ticks, calculate their implied volatility values, and then calculate the difference to obtain the put-call parity value. Here is an example of how to do this:
 //! ```rust
 //! // Extract the 25delta call and put option ticks and calculate their implied volatility values
-//! let call_25delta_iv = ts.map(OptionChain::call_25delta)
+//! let call_25delta_iv = ts.try_map(|chain| Ok(chain.call_25delta()?))?
 //!     .map(OptionTick::get_implied_volatility)
 //!     .map(OptionTick::iv);
-//! let put_25delta_iv = ts.map(OptionChain::put_25delta)
+//! let put_25delta_iv = ts.try_map(|chain| Ok(chain.put_25delta()?))?
 //!     .map(OptionTick::get_implied_volatility)
 //!     .map(OptionTick::iv);
 //!
@@ -73,10 +96,12 @@
 //! ```
 //! In the above code, call_25delta_iv and put_25delta_iv are TimeSeries\<f64\> that contain the implied volatility values of the 25delta call and put option ticks, respectively. The delta_iv_ts is a TimeSeries\<f64\> that contains the put-call parity values.
 
+use super::structs::FloatType;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::ops::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TimeSeries<T>(pub Vec<T>);
 
 impl<T> TimeSeries<T>
@@ -92,16 +117,123 @@ impl<T> TimeSeries<T>
     }
 }
 
+impl<T: Clone> TimeSeries<T> {
+    /// Pairs the elements of the two series at the same position. The result has the length of the shorter series.
+    pub fn zip<U: Clone>(&self, other: &TimeSeries<U>) -> TimeSeries<(T, U)> {
+        self.map2(other, |a, b| (a.clone(), b.clone()))
+    }
+}
+
+impl<T> TimeSeries<T> {
+    /// Applies f to the elements of the two series at the same position. The result has the length of the shorter series.
+    pub fn map2<U, V>(&self, other: &TimeSeries<U>, f: impl Fn(&T, &U) -> V) -> TimeSeries<V> {
+        TimeSeries(
+            self.0
+                .iter()
+                .zip(other.0.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        )
+    }
+}
+
+impl<T, U> TimeSeries<(T, U)> {
+    pub fn unzip(self) -> (TimeSeries<T>, TimeSeries<U>) {
+        let (a, b) = self.0.into_iter().unzip();
+        (TimeSeries(a), TimeSeries(b))
+    }
+}
+
 impl<T> Default for TimeSeries<T> {
     fn default() -> Self {
         Self(Vec::new())
     }
 }
 
+/// How a TimeSeries\<Result\<T\>\> handles its errors, e.g. a chain snapshot whose IV could not be computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// Drop the failed elements
+    Skip,
+    /// Fail with the first error
+    Propagate,
+    /// Replace a failed element with the previous successful one, so that the series keeps its length. Fails with the
+    /// first error if it comes before the first success.
+    FillPrevious,
+}
+
+impl<T> TimeSeries<T> {
+    /// Applies a fallible function to each element and fails with the first error.
+    pub fn try_map<U>(&self, f: impl Fn(&T) -> Result<U>) -> Result<TimeSeries<U>> {
+        Ok(TimeSeries(self.0.iter().map(f).collect::<Result<Vec<U>>>()?))
+    }
+
+    /// Applies a fallible function to each element and handles the errors with the policy.
+    pub fn map_with_policy<U: Clone>(
+        &self,
+        policy: ErrorPolicy,
+        f: impl Fn(&T) -> Result<U>,
+    ) -> Result<TimeSeries<U>> {
+        self.map(f).resolve(policy)
+    }
+}
+
 impl<T> TimeSeries<Result<T>> {
+    /// Panics on the first error; see resolve() to handle errors without panicking.
     pub fn unwrap(self) -> TimeSeries<T> {
         TimeSeries(self.0.into_iter().map(|x| x.unwrap()).collect())
     }
+
+    /// The successful elements, in order.
+    pub fn filter_ok(self) -> TimeSeries<T> {
+        TimeSeries(self.0.into_iter().filter_map(|x| x.ok()).collect())
+    }
+
+    /// Fails with the first error.
+    pub fn collect_ok(self) -> Result<TimeSeries<T>> {
+        Ok(TimeSeries(self.0.into_iter().collect::<Result<Vec<T>>>()?))
+    }
+
+    /// Number of failed elements.
+    pub fn error_count(&self) -> usize {
+        self.0.iter().filter(|x| x.is_err()).count()
+    }
+}
+
+impl<T: Clone> TimeSeries<Result<T>> {
+    /// Replaces each error with the previous successful element, index for index. Errors before the first success are None.
+    pub fn fill_forward_on_err(self) -> TimeSeries<Option<T>> {
+        let mut previous: Option<T> = None;
+        let mut filled = Vec::with_capacity(self.0.len());
+        for x in self.0 {
+            if let Ok(value) = x {
+                previous = Some(value);
+            }
+            filled.push(previous.clone());
+        }
+        TimeSeries(filled)
+    }
+
+    pub fn resolve(self, policy: ErrorPolicy) -> Result<TimeSeries<T>> {
+        match policy {
+            ErrorPolicy::Skip => Ok(self.filter_ok()),
+            ErrorPolicy::Propagate => self.collect_ok(),
+            ErrorPolicy::FillPrevious => {
+                let mut previous: Option<T> = None;
+                let mut filled = Vec::with_capacity(self.0.len());
+                for x in self.0 {
+                    let value = match (x, previous) {
+                        (Ok(value), _) => value,
+                        (Err(_), Some(value)) => value,
+                        (Err(e), None) => return Err(e),
+                    };
+                    previous = Some(value.clone());
+                    filled.push(value);
+                }
+                Ok(TimeSeries(filled))
+            }
+        }
+    }
 }
 
 #[auto_impl_ops::auto_ops]
@@ -170,3 +302,128 @@ where
         )
     }
 }
+
+macro_rules! scalar_ops {
+    ($($op:ident, $method:ident, $symbol:tt);*) => {
+        $(
+            impl $op<FloatType> for &TimeSeries<FloatType> {
+                type Output = TimeSeries<FloatType>;
+                fn $method(self, other: FloatType) -> Self::Output {
+                    self.map(|a| a $symbol other)
+                }
+            }
+
+            impl $op<FloatType> for TimeSeries<FloatType> {
+                type Output = TimeSeries<FloatType>;
+                fn $method(self, other: FloatType) -> Self::Output {
+                    (&self).$method(other)
+                }
+            }
+
+            impl $op<&TimeSeries<FloatType>> for FloatType {
+                type Output = TimeSeries<FloatType>;
+                fn $method(self, other: &TimeSeries<FloatType>) -> Self::Output {
+                    other.map(|b| self $symbol b)
+                }
+            }
+
+            impl $op<TimeSeries<FloatType>> for FloatType {
+                type Output = TimeSeries<FloatType>;
+                fn $method(self, other: TimeSeries<FloatType>) -> Self::Output {
+                    self.$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+scalar_ops!(Add, add, +; Sub, sub, -; Mul, mul, *; Div, div, /);
+
+impl Neg for &TimeSeries<FloatType> {
+    type Output = TimeSeries<FloatType>;
+    fn neg(self) -> Self::Output {
+        self.map(|a| -a)
+    }
+}
+
+impl Neg for TimeSeries<FloatType> {
+    type Output = TimeSeries<FloatType>;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl TimeSeries<FloatType> {
+    pub fn abs(&self) -> Self {
+        self.map(|a| a.abs())
+    }
+
+    pub fn ln(&self) -> Self {
+        self.map(|a| a.ln())
+    }
+
+    pub fn exp(&self) -> Self {
+        self.map(|a| a.exp())
+    }
+
+    /// Running sum.
+    pub fn cumsum(&self) -> Self {
+        let mut total = 0.;
+        TimeSeries(
+            self.0
+                .iter()
+                .map(|a| {
+                    total += a;
+                    total
+                })
+                .collect(),
+        )
+    }
+
+    /// x_t - x_{t-1}, NaN for the first element.
+    pub fn diff(&self) -> Self {
+        self - &self.lag(1)
+    }
+
+    /// x_t / x_{t-1} - 1, NaN for the first element.
+    pub fn pct_change(&self) -> Self {
+        self / &self.lag(1) - 1.
+    }
+
+    /// Moves the values `periods` steps later (or earlier if negative), filling the vacated positions with NaN.
+    pub fn shift(&self, periods: isize) -> Self {
+        let n = self.0.len() as isize;
+        TimeSeries(
+            (0..n)
+                .map(|i| match i - periods {
+                    j if (0..n).contains(&j) => self.0[j as usize],
+                    _ => FloatType::NAN,
+                })
+                .collect(),
+        )
+    }
+
+    /// The value `periods` steps earlier, i.e. shift(periods).
+    pub fn lag(&self, periods: usize) -> Self {
+        self.shift(periods as isize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn fill_previous_keeps_length() {
+        let series: TimeSeries<Result<i32>> =
+            TimeSeries(vec![Err(anyhow!("first")), Ok(1), Err(anyhow!("second")), Ok(2)]);
+        assert_eq!(series.fill_forward_on_err().0, vec![None, Some(1), Some(1), Some(2)]);
+
+        let series: TimeSeries<Result<i32>> = TimeSeries(vec![Ok(1), Err(anyhow!("second")), Ok(2)]);
+        assert_eq!(series.resolve(ErrorPolicy::FillPrevious).unwrap().0, vec![1, 1, 2]);
+
+        let series: TimeSeries<Result<i32>> = TimeSeries(vec![Err(anyhow!("first")), Ok(1)]);
+        assert!(series.resolve(ErrorPolicy::FillPrevious).is_err());
+    }
+}
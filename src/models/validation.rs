@@ -0,0 +1,183 @@
+//! Validation of the fields of an OptionTick.
+//!
+//! The builder of OptionTick accepts any value, so a tick with a negative strike or an expiry before its valuation time
+//! only shows up later as NaN prices and greeks. validate() checks the fields and returns the first problem as a TickError;
+//! validated() is the constructor path for data coming from outside, e.g. `OptionTick::builder()...build().validated()?`.
+//! The readers of io and feed validate every tick they build.
+
+use super::structs::*;
+use std::fmt;
+
+/// Reason an OptionTick is rejected by validate().
+#[derive(Clone, Debug, PartialEq)]
+pub enum TickError {
+    /// The strike is not positive (only the Bachelier model allows any strike)
+    InvalidStrike(DecimalType),
+    /// asset_price is not finite, or not positive outside the Bachelier model
+    InvalidAssetPrice(FloatType),
+    /// The maturity is not after the valuation time. Not checked without a valuation_time, so that archived ticks stay valid
+    ExpiryInPast,
+    /// The implied volatility is negative or NaN
+    NegativeVol(FloatType),
+    /// The premium is negative or NaN
+    NegativePrice(FloatType),
+    /// risk_free_rate or dividend_yield is not finite
+    InvalidRate(FloatType),
+    /// Dividends are set on a futures or forward underlying (Black76, Bachelier), which ignores them
+    DividendsOnForward,
+}
+
+impl fmt::Display for TickError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TickError::InvalidStrike(strike) => write!(f, "The strike {} is not positive.", strike),
+            TickError::InvalidAssetPrice(price) => {
+                write!(f, "The asset price {} is not valid.", price)
+            }
+            TickError::ExpiryInPast => write!(f, "The maturity is not after the valuation time."),
+            TickError::NegativeVol(iv) => write!(f, "The implied volatility {} is negative.", iv),
+            TickError::NegativePrice(price) => write!(f, "The premium {} is negative.", price),
+            TickError::InvalidRate(rate) => write!(f, "The rate {} is not finite.", rate),
+            TickError::DividendsOnForward => {
+                write!(f, "Dividends are set on a futures or forward underlying.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TickError {}
+
+impl OptionTick {
+    /// Checks that the fields of the tick are consistent, returning the first problem found.
+    pub fn validate(&self) -> Result<(), TickError> {
+        let normal = self.pricing_model == PricingModel::Bachelier;
+        if !normal && self.strike <= DecimalType::ZERO {
+            return Err(TickError::InvalidStrike(self.strike));
+        }
+        if !self.asset_price.is_finite() || (!normal && self.asset_price <= 0.) {
+            return Err(TickError::InvalidAssetPrice(self.asset_price));
+        }
+        if self.valuation_time.is_some_and(|t| self.maturity <= t) {
+            return Err(TickError::ExpiryInPast);
+        }
+        match self.option_value {
+            OptionValue::ImpliedVolatility(iv) if iv.is_nan() || iv < 0. => {
+                return Err(TickError::NegativeVol(iv))
+            }
+            OptionValue::Price(price) if price.is_nan() || price < 0. => {
+                return Err(TickError::NegativePrice(price))
+            }
+            _ => {}
+        }
+        if let Some(rate) = [self.risk_free_rate, self.dividend_yield]
+            .into_iter()
+            .find(|r| !r.is_finite())
+        {
+            return Err(TickError::InvalidRate(rate));
+        }
+        let has_dividends =
+            self.dividend_yield != 0. || self.dividend_curve.is_some() || self.dividends.is_some();
+        if self.pricing_model != PricingModel::BlackScholes && has_dividends {
+            return Err(TickError::DividendsOnForward);
+        }
+        Ok(())
+    }
+
+    /// Returns the tick if validate() accepts it.
+    pub fn validated(self) -> Result<Self, TickError> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick() -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(dec!(100))
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(30))
+            .option_type(OptionType::Call)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn accepts_valid_tick() {
+        assert_eq!(tick().validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_strike() {
+        let mut t = tick();
+        t.strike = dec!(0);
+        assert_eq!(t.validate(), Err(TickError::InvalidStrike(dec!(0))));
+        t.pricing_model = PricingModel::Bachelier;
+        t.dividend_yield = 0.;
+        assert_eq!(t.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_asset_price() {
+        let mut t = tick();
+        t.asset_price = -1.;
+        assert_eq!(t.validate(), Err(TickError::InvalidAssetPrice(-1.)));
+        t.asset_price = FloatType::INFINITY;
+        assert_eq!(t.validate(), Err(TickError::InvalidAssetPrice(FloatType::INFINITY)));
+    }
+
+    #[test]
+    fn expiry_in_past() {
+        let mut t = tick();
+        t.maturity = t.valuation_time.unwrap();
+        assert_eq!(t.validate(), Err(TickError::ExpiryInPast));
+        // an archived tick without valuation time is not compared with now
+        t.valuation_time = None;
+        assert_eq!(t.validate(), Ok(()));
+    }
+
+    #[test]
+    fn negative_vol() {
+        let mut t = tick();
+        t.option_value = OptionValue::ImpliedVolatility(-0.1);
+        assert_eq!(t.validate(), Err(TickError::NegativeVol(-0.1)));
+        t.option_value = OptionValue::ImpliedVolatility(FloatType::NAN);
+        assert!(matches!(t.validate(), Err(TickError::NegativeVol(iv)) if iv.is_nan()));
+    }
+
+    #[test]
+    fn negative_price() {
+        let mut t = tick();
+        t.option_value = OptionValue::Price(-1.);
+        assert_eq!(t.validate(), Err(TickError::NegativePrice(-1.)));
+        t.option_value = OptionValue::Price(FloatType::NAN);
+        assert!(matches!(t.validate(), Err(TickError::NegativePrice(p)) if p.is_nan()));
+    }
+
+    #[test]
+    fn invalid_rate() {
+        let mut t = tick();
+        t.risk_free_rate = FloatType::INFINITY;
+        assert_eq!(t.validate(), Err(TickError::InvalidRate(FloatType::INFINITY)));
+        let mut t = tick();
+        t.dividend_yield = FloatType::NEG_INFINITY;
+        assert_eq!(t.validate(), Err(TickError::InvalidRate(FloatType::NEG_INFINITY)));
+    }
+
+    #[test]
+    fn dividends_on_forward() {
+        let mut t = tick();
+        t.pricing_model = PricingModel::Black76;
+        assert_eq!(t.validate(), Err(TickError::DividendsOnForward));
+        t.dividend_yield = 0.;
+        assert_eq!(t.validate(), Ok(()));
+    }
+}
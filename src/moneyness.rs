@@ -0,0 +1,95 @@
+//! Moneyness measures and conversions between strike and delta.
+//!
+//! Moneyness is measured against the forward F = S e^{(r - q) tau} of the tick (S being the escrowed spot), so that calls and
+//! puts of the same strike share the same coordinate and surfaces can be expressed in moneyness or delta instead of strike.
+//! * moneyness: K / F
+//! * log_moneyness: ln(K / F)
+//! * standardized_moneyness: ln(K / F) / (sigma sqrt(tau)), the log moneyness in units of the standard deviation to maturity
+//!
+//! # Example
+//! ```ignore
+//! let k_25c = chain.strike_for_delta(0.25, OptionType::Call)?;
+//! let delta = chain.delta_for_strike(dec!(30000), OptionType::Put)?;
+//! println!("{} {}", tick.log_moneyness(), tick.standardized_moneyness());
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::math::norm_inv_cdf;
+use crate::models::*;
+use anyhow::{ensure, Result};
+use rust_decimal::prelude::*;
+
+impl OptionTick {
+    /// Forward price to maturity, S e^{-q tau} / e^{-r tau}. Equal to asset_price under Black-76.
+    pub fn forward(&self) -> FloatType {
+        self.escrowed_spot() * self.carry_discount_factor() / self.discount_factor()
+    }
+
+    pub fn moneyness(&self) -> FloatType {
+        self.strike_f64() / self.forward()
+    }
+
+    pub fn log_moneyness(&self) -> FloatType {
+        self.moneyness().ln()
+    }
+
+    /// Log moneyness divided by the standard deviation sigma sqrt(tau), with the IV of the tick.
+    pub fn standardized_moneyness(&self) -> FloatType {
+        self.log_moneyness() / (self.iv() * self.tau().sqrt())
+    }
+
+    /// Strike at which an option of the same type, IV, maturity and rates as this tick has the given delta:
+    /// K = F exp(-d1 sigma sqrt(tau) + sigma^2 tau / 2), with d1 = N^{-1}(delta e^{q tau}) for calls and -N^{-1}(-delta e^{q tau}) for puts.
+    pub fn strike_for_delta(&self, delta: FloatType) -> FloatType {
+        let sigma = self.iv();
+        let tau = self.tau();
+        let scaled = delta / self.carry_discount_factor();
+        let d1 = match self.option_type {
+            OptionType::Call => norm_inv_cdf(scaled),
+            OptionType::Put => -norm_inv_cdf(-scaled),
+        };
+        self.forward() * (-d1 * sigma * tau.sqrt() + 0.5 * sigma * sigma * tau).exp()
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Strike whose delta on the smile of the chain is `delta`, e.g. 0.25 for calls or -0.25 for puts.
+    /// The IV is interpolated linearly in strike between the listed options of `option_type` (see call_delta()).
+    pub fn strike_for_delta(&self, delta: FloatType, option_type: OptionType) -> Result<DecimalType> {
+        let tick = match option_type {
+            OptionType::Call => self.call_delta(delta)?,
+            OptionType::Put => self.put_delta(delta)?,
+        };
+        Ok(tick.strike)
+    }
+
+    /// Delta of an option of `option_type` at `strike`, with the IV interpolated linearly in strike between the listed options
+    /// of that type. The strike must lie between the lowest and highest listed strikes.
+    pub fn delta_for_strike(&self, strike: DecimalType, option_type: OptionType) -> Result<FloatType> {
+        let chain = match option_type {
+            OptionType::Call => self.call(),
+            OptionType::Put => self.put(),
+        };
+        let chain = chain.sort_by_strike().map(|t| t.get_implied_volatility());
+        let points: Vec<&OptionTick> = chain.0.iter().filter(|t| t.get_value().is_finite()).collect();
+        ensure!(!points.is_empty(), "No {:?} with a valid IV in the chain", option_type);
+
+        let k = strike.to_f64().unwrap();
+        let iv = match points.windows(2).find(|w| w[0].strike_f64() <= k && k <= w[1].strike_f64()) {
+            Some(w) => {
+                let (k1, k2) = (w[0].strike_f64(), w[1].strike_f64());
+                let (iv1, iv2) = (w[0].get_value(), w[1].get_value());
+                iv1 + (iv2 - iv1) * (k - k1) / (k2 - k1)
+            }
+            None => {
+                let exact = points.iter().find(|t| t.strike == strike);
+                ensure!(exact.is_some(), "Strike {} is outside the range of listed strikes", strike);
+                exact.unwrap().get_value()
+            }
+        };
+        let mut tick = points[0].clone();
+        tick.strike = strike;
+        tick.option_value = OptionValue::ImpliedVolatility(iv);
+        Ok(tick.try_delta()?)
+    }
+}
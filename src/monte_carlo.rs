@@ -0,0 +1,247 @@
+//! Monte Carlo pricing under geometric Brownian motion.
+//!
+//! Paths follow `dS = (r - q) S dt + sigma S dW` with r, q and sigma taken from an OptionTick (sigma is its implied volatility),
+//! and are simulated with the exact log-normal step on an equally spaced grid of `n_steps` monitoring dates.
+//! Path-dependent payoffs are monitored discretely on that grid.
+//!
+//! # Example
+//! ```ignore
+//! let pricer = McPricer::new(McConfig::builder().n_paths(50_000).seed(42).build());
+//! let asian = pricer.price(&tick, &Payoff::Asian)?;
+//! println!("{} +- {}", asian.price, asian.std_error);
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use probability::prelude::*;
+use probability::source::Source;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct McConfig {
+    /// Number of simulated paths (pairs of paths count twice when antithetic is enabled).
+    #[builder(default = 10000)]
+    pub n_paths: usize,
+    /// Number of time steps, i.e. monitoring dates for path-dependent payoffs.
+    #[builder(default = 100)]
+    pub n_steps: usize,
+    #[builder(default = true)]
+    pub antithetic: bool,
+    #[builder(default = 0)]
+    pub seed: u64,
+}
+
+/// Payoff evaluated on the strike and option_type of the tick.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Payoff {
+    European,
+    /// Arithmetic average of the monitored prices in place of the terminal price.
+    Asian,
+    /// Vanilla payoff that is knocked out (or in) when the monitored price crosses the level.
+    Barrier { kind: BarrierKind, level: FloatType },
+    /// Fixed strike lookback: max(S_max - K, 0) for a call and max(K - S_min, 0) for a put.
+    Lookback,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct McResult {
+    pub price: FloatType,
+    pub std_error: FloatType,
+}
+
+/// GBM path simulator.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GbmSimulator {
+    pub spot: FloatType,
+    /// Drift r - q
+    pub drift: FloatType,
+    pub volatility: FloatType,
+    pub tau: FloatType,
+    pub n_steps: usize,
+}
+
+impl GbmSimulator {
+    pub fn from_tick(tick: &OptionTick, n_steps: usize) -> Self {
+        Self::try_from_tick(tick, n_steps).unwrap()
+    }
+
+    /// Same as from_tick(), but returns OptionError::IvNotConverged instead of panicking when the IV cannot be solved.
+    /// The paths start from the escrowed spot, net of the discrete dividends. A Bachelier tick has no lognormal dynamics and
+    /// fails with OptionError::UnsupportedModel.
+    pub fn try_from_tick(tick: &OptionTick, n_steps: usize) -> Result<Self, OptionError> {
+        if tick.pricing_model == PricingModel::Bachelier {
+            return Err(OptionError::UnsupportedModel);
+        }
+        Ok(Self {
+            spot: tick.escrowed_spot(),
+            drift: tick.rate() - tick.carry_yield(),
+            volatility: tick.try_iv()?,
+            tau: tick.tau(),
+            n_steps,
+        })
+    }
+
+    /// Builds the path (excluding the initial spot) from standard normal increments.
+    pub fn path_from_normals(&self, normals: &[FloatType]) -> Vec<FloatType> {
+        let dt = self.tau / self.n_steps as FloatType;
+        let mu = (self.drift - 0.5 * self.volatility.powi(2)) * dt;
+        let sd = self.volatility * dt.sqrt();
+        let mut s = self.spot;
+        normals
+            .iter()
+            .map(|z| {
+                s *= (mu + sd * z).exp();
+                s
+            })
+            .collect()
+    }
+
+    /// Simulates `n_paths` independent paths.
+    pub fn simulate(&self, n_paths: usize, seed: u64) -> Vec<Vec<FloatType>> {
+        let mut source = source::default(seed);
+        (0..n_paths)
+            .map(|_| self.path_from_normals(&standard_normals(&mut source, self.n_steps)))
+            .collect()
+    }
+}
+
+/// Box-Muller transform of uniform draws.
+pub(crate) fn standard_normals(source: &mut impl Source, n: usize) -> Vec<FloatType> {
+    (0..n)
+        .map(|_| {
+            let u1 = 1. - source.read_f64();
+            let u2 = source.read_f64();
+            (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos()
+        })
+        .collect()
+}
+
+impl Payoff {
+    /// Undiscounted payoff of a path (excluding the initial spot).
+    pub fn evaluate(&self, path: &[FloatType], strike: FloatType, option_type: &OptionType) -> FloatType {
+        let vanilla = |s: FloatType| match option_type {
+            OptionType::Call => (s - strike).max(0.),
+            OptionType::Put => (strike - s).max(0.),
+        };
+        let last = *path.last().unwrap();
+        match self {
+            Payoff::European => vanilla(last),
+            Payoff::Asian => vanilla(path.iter().sum::<FloatType>() / path.len() as FloatType),
+            Payoff::Barrier { kind, level } => {
+                let hit = match kind {
+                    BarrierKind::UpAndOut | BarrierKind::UpAndIn => path.iter().any(|s| s >= level),
+                    BarrierKind::DownAndOut | BarrierKind::DownAndIn => path.iter().any(|s| s <= level),
+                };
+                let alive = match kind {
+                    BarrierKind::UpAndOut | BarrierKind::DownAndOut => !hit,
+                    BarrierKind::UpAndIn | BarrierKind::DownAndIn => hit,
+                };
+                if alive {
+                    vanilla(last)
+                } else {
+                    0.
+                }
+            }
+            Payoff::Lookback => match option_type {
+                OptionType::Call => vanilla(path.iter().copied().fold(FloatType::MIN, FloatType::max)),
+                OptionType::Put => vanilla(path.iter().copied().fold(FloatType::MAX, FloatType::min)),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct McPricer {
+    pub config: McConfig,
+}
+
+impl McPricer {
+    pub fn new(config: McConfig) -> Self {
+        Self { config }
+    }
+
+    /// Prices the payoff on the strike, option_type, rates and implied volatility of the tick.
+    /// With antithetic variates, each pair of mirrored paths is averaged into one sample for the standard error.
+    pub fn price(&self, tick: &OptionTick, payoff: &Payoff) -> Result<McResult> {
+        let config = &self.config;
+        let n_samples = if config.antithetic { config.n_paths / 2 } else { config.n_paths };
+        ensure!(
+            n_samples >= 2 && config.n_steps >= 1,
+            "n_paths must give at least 2 samples (4 paths with antithetic variates) and n_steps must be at least 1"
+        );
+        let simulator = GbmSimulator::try_from_tick(tick, config.n_steps)?;
+        ensure!(simulator.tau > 0., "The option has expired");
+        ensure!(simulator.volatility.is_finite(), "Implied volatility is not available");
+
+        let strike = tick.strike.to_f64().unwrap();
+        let discount = (-tick.rate() * simulator.tau).exp();
+        let mut source = source::default(config.seed);
+
+        let samples: Vec<FloatType> = (0..n_samples)
+            .map(|_| {
+                let normals = standard_normals(&mut source, config.n_steps);
+                let value = payoff.evaluate(&simulator.path_from_normals(&normals), strike, &tick.option_type);
+                if config.antithetic {
+                    let mirrored: Vec<FloatType> = normals.iter().map(|z| -z).collect();
+                    let value_anti =
+                        payoff.evaluate(&simulator.path_from_normals(&mirrored), strike, &tick.option_type);
+                    0.5 * (value + value_anti)
+                } else {
+                    value
+                }
+            })
+            .collect();
+
+        let n = samples.len() as FloatType;
+        let mean = samples.iter().sum::<FloatType>() / n;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<FloatType>() / (n - 1.);
+        Ok(McResult {
+            price: discount * mean,
+            std_error: discount * (variance / n).sqrt(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::black_scholes::BlackScholes;
+    use crate::dividend::{CashDividend, DividendSchedule};
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick() -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(dec!(100))
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .option_value(OptionValue::ImpliedVolatility(0.25))
+            .maturity(now + Duration::days(180))
+            .option_type(OptionType::Call)
+            .valuation_time(now)
+            .build()
+            .with_dividends(DividendSchedule::new(vec![CashDividend::new(now + Duration::days(60), 3.)]))
+    }
+
+    #[test]
+    fn european_with_discrete_dividends() {
+        let tick = tick();
+        let config = McConfig::builder().n_paths(40000).n_steps(1).seed(3).build();
+        let result = McPricer::new(config).price(&tick, &Payoff::European).unwrap();
+        let expected = tick.get_theoretical_price().get_value();
+        assert!((result.price - expected).abs() < 3. * result.std_error);
+    }
+
+    #[test]
+    fn rejects_bachelier() {
+        let mut tick = tick();
+        tick.pricing_model = PricingModel::Bachelier;
+        tick.dividends = None;
+        tick.option_value = OptionValue::ImpliedVolatility(25.);
+        assert_eq!(GbmSimulator::try_from_tick(&tick, 10).unwrap_err(), OptionError::UnsupportedModel);
+    }
+}
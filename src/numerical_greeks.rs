@@ -0,0 +1,340 @@
+//! Greeks by bump-and-reprice for any pricing engine.
+//!
+//! A type implementing NumericalGreeks only provides `price()` and a way to shift its risk factors;
+//! the greeks are then obtained by central differences.
+//! This gives greeks for American options (PDE), barrier options and Monte Carlo priced payoffs without bespoke formulas.
+//!
+//! Bump sizes: spot 1% of the asset price, volatility 1 vol point, rate 1bp, time 1 day.
+//! As with EuropeanGreeks, vega and rho are per unit change (not per point) and theta is per year.
+//!
+//! OptionTick::numerical_greek() extends this to most higher order greeks of the Greek enum by differencing the lower order
+//! ones, e.g. vanna as the change of the numerical delta with the volatility. It is the engine behind the greeks of American
+//! ticks and ticks with the FiniteDifference engine (see the Greeks trait).
+//!
+//! # Example
+//! ```ignore
+//! let american = OptionTick::builder()...option_style(OptionStyle::American).build();
+//! println!("{} {}", american.numerical_delta(), american.numerical_gamma());
+//! let knock_out = BarrierOption::new(tick, barrier);
+//! println!("{}", knock_out.numerical_vega());
+//! ```
+
+use crate::asian::AsianOption;
+use crate::black_scholes::BlackScholes;
+use crate::greeks::Greek;
+use crate::models::*;
+use crate::monte_carlo::*;
+use crate::spread::SpreadOption;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+const SPOT_BUMP: FloatType = 0.01;
+const VOL_BUMP: FloatType = 0.01;
+const RATE_BUMP: FloatType = 0.0001;
+const TIME_BUMP_DAYS: FloatType = 1.;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RiskFactor {
+    /// Absolute shift of the asset price
+    Spot,
+    /// Absolute shift of the implied volatility
+    Volatility,
+    /// Shift of the maturity in days (positive = longer). The greeks divide by the resulting change of the time to maturity
+    /// under the day count of the option
+    Time,
+    /// Absolute shift of the risk free rate
+    Rate,
+}
+
+pub trait NumericalGreeks: Sized {
+    fn price(&self) -> FloatType;
+
+    /// Returns a copy with the risk factor shifted by `amount`.
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self;
+
+    /// Reference asset price used to size the spot bump.
+    fn spot(&self) -> FloatType;
+
+    /// Time to maturity in years, with the day count of the option.
+    fn tau(&self) -> FloatType;
+
+    fn central_difference(&self, factor: &RiskFactor, h: FloatType) -> FloatType {
+        (self.bumped(factor, h).price() - self.bumped(factor, -h).price()) / (2. * h)
+    }
+
+    fn numerical_delta(&self) -> FloatType {
+        self.central_difference(&RiskFactor::Spot, SPOT_BUMP * self.spot())
+    }
+
+    fn numerical_gamma(&self) -> FloatType {
+        let h = SPOT_BUMP * self.spot();
+        (self.bumped(&RiskFactor::Spot, h).price() - 2. * self.price()
+            + self.bumped(&RiskFactor::Spot, -h).price())
+            / h.powi(2)
+    }
+
+    fn numerical_vega(&self) -> FloatType {
+        self.central_difference(&RiskFactor::Volatility, VOL_BUMP)
+    }
+
+    /// Change of the value as time passes, i.e. minus the derivative with respect to the time to maturity.
+    fn numerical_theta(&self) -> FloatType {
+        let up = self.bumped(&RiskFactor::Time, TIME_BUMP_DAYS);
+        let down = self.bumped(&RiskFactor::Time, -TIME_BUMP_DAYS);
+        -(up.price() - down.price()) / (up.tau() - down.tau())
+    }
+
+    fn numerical_rho(&self) -> FloatType {
+        self.central_difference(&RiskFactor::Rate, RATE_BUMP)
+    }
+}
+
+fn bump_tick(tick: &OptionTick, factor: &RiskFactor, amount: FloatType) -> OptionTick {
+    let mut tick = tick.get_implied_volatility();
+    match factor {
+        RiskFactor::Spot => tick.asset_price += amount,
+        RiskFactor::Volatility => {
+            tick.option_value = OptionValue::ImpliedVolatility(tick.get_value() + amount)
+        }
+        RiskFactor::Time => tick.maturity += Duration::seconds((amount * 86400.) as i64),
+        RiskFactor::Rate => {
+            tick.risk_free_rate += amount;
+            tick.discount_curve = tick.discount_curve.as_ref().map(|curve| curve.shifted(amount));
+        }
+    }
+    tick
+}
+
+/// Priced with price_with_engine(), i.e. according to its option_style and pricing_engine.
+impl NumericalGreeks for OptionTick {
+    fn price(&self) -> FloatType {
+        self.price_with_engine().unwrap_or(FloatType::NAN)
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        bump_tick(self, factor, amount)
+    }
+
+    fn spot(&self) -> FloatType {
+        self.asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        OptionTick::tau(self)
+    }
+}
+
+/// Whether numerical_greek() computes the greek. Epsilon and the dual greeks have no risk factor to bump, and the second and
+/// third derivatives in the volatility (vomma, ultima) are dominated by the error of the finite difference grid, which moves
+/// with the volatility.
+pub(crate) fn has_numerical_engine(greek: Greek) -> bool {
+    !matches!(
+        greek,
+        Greek::Epsilon | Greek::DualDelta | Greek::DualGamma | Greek::Vomma | Greek::Ultima
+    )
+}
+
+impl OptionTick {
+    /// Greek by bump-and-reprice with price_with_engine(). The greeks above the second order are central differences of the
+    /// numerical greeks of lower order. None for the greeks without a numerical engine (see has_numerical_engine).
+    pub fn numerical_greek(&self, greek: Greek) -> Option<FloatType> {
+        if !has_numerical_engine(greek) {
+            return None;
+        }
+        let tick = self.get_implied_volatility();
+        // derivative of the inner greek with respect to the risk factor
+        let derivative = |inner: Greek, factor: RiskFactor, h: FloatType| -> Option<FloatType> {
+            let (up, down) = (tick.bumped(&factor, h), tick.bumped(&factor, -h));
+            let dx = match factor {
+                RiskFactor::Time => up.tau() - down.tau(),
+                _ => 2. * h,
+            };
+            Some((up.numerical_greek(inner)? - down.numerical_greek(inner)?) / dx)
+        };
+        let spot_bump = SPOT_BUMP * tick.asset_price;
+        let time_bump = TIME_BUMP_DAYS;
+        match greek {
+            Greek::Delta => Some(tick.numerical_delta()),
+            Greek::Gamma => Some(tick.numerical_gamma()),
+            Greek::Theta => Some(tick.numerical_theta()),
+            Greek::Rho => Some(tick.numerical_rho()),
+            Greek::Vega => Some(tick.numerical_vega()),
+            Greek::Vanna => derivative(Greek::Delta, RiskFactor::Volatility, VOL_BUMP),
+            // as in EuropeanGreeks, charm is taken as time passes and veta and color along the time to maturity
+            Greek::Charm => derivative(Greek::Delta, RiskFactor::Time, time_bump).map(|d| -d),
+            Greek::Veta => derivative(Greek::Vega, RiskFactor::Time, time_bump),
+            Greek::Speed => derivative(Greek::Gamma, RiskFactor::Spot, spot_bump),
+            Greek::Zomma => derivative(Greek::Gamma, RiskFactor::Volatility, VOL_BUMP),
+            Greek::Color => derivative(Greek::Gamma, RiskFactor::Time, time_bump),
+            Greek::DvegaDtime => derivative(Greek::Vega, RiskFactor::Time, time_bump).map(|d| -d),
+            Greek::Lambda => Some(tick.numerical_delta() * tick.asset_price / tick.price()),
+            _ => None,
+        }
+    }
+}
+
+/// An option with a continuous barrier, priced in closed form or by finite differences according to the engine of its tick.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BarrierOption {
+    pub tick: OptionTick,
+    pub barrier: BarrierSpec,
+}
+
+impl BarrierOption {
+    pub fn new(tick: OptionTick, barrier: BarrierSpec) -> Self {
+        Self { tick, barrier }
+    }
+}
+
+impl NumericalGreeks for BarrierOption {
+    fn price(&self) -> FloatType {
+        self.price_with_engine().unwrap_or(FloatType::NAN)
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        Self::new(bump_tick(&self.tick, factor, amount), self.barrier.clone())
+    }
+
+    fn spot(&self) -> FloatType {
+        self.tick.asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        self.tick.tau()
+    }
+}
+
+/// Priced in closed form (Kemna-Vorst or Turnbull-Wakeman).
+impl NumericalGreeks for AsianOption {
+    fn price(&self) -> FloatType {
+        self.price_closed_form().unwrap_or(FloatType::NAN)
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        Self::new(bump_tick(&self.tick, factor, amount), self.average.clone())
+    }
+
+    fn spot(&self) -> FloatType {
+        self.tick.asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        self.tick.tau()
+    }
+}
+
+/// Priced in closed form. The spot and volatility bumps apply to the first asset, i.e. the underlying of the tick.
+impl NumericalGreeks for SpreadOption {
+    fn price(&self) -> FloatType {
+        self.price_closed_form().unwrap_or(FloatType::NAN)
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        Self {
+            tick: bump_tick(&self.tick, factor, amount),
+            ..self.clone()
+        }
+    }
+
+    fn spot(&self) -> FloatType {
+        self.tick.asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        self.tick.tau()
+    }
+}
+
+/// A payoff priced by Monte Carlo. Every reprice uses the same seed (common random numbers), which keeps the differences stable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MonteCarloOption {
+    pub tick: OptionTick,
+    pub payoff: Payoff,
+    pub pricer: McPricer,
+}
+
+impl MonteCarloOption {
+    pub fn new(tick: OptionTick, payoff: Payoff, pricer: McPricer) -> Self {
+        Self {
+            tick,
+            payoff,
+            pricer,
+        }
+    }
+}
+
+impl NumericalGreeks for MonteCarloOption {
+    fn price(&self) -> FloatType {
+        self.pricer
+            .price(&self.tick, &self.payoff)
+            .map_or(FloatType::NAN, |r| r.price)
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        Self::new(
+            bump_tick(&self.tick, factor, amount),
+            self.payoff.clone(),
+            self.pricer.clone(),
+        )
+    }
+
+    fn spot(&self) -> FloatType {
+        self.tick.asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        self.tick.tau()
+    }
+}
+
+impl NumericalGreeks for ExoticOption {
+    fn price(&self) -> FloatType {
+        match self {
+            ExoticOption::Barrier(option) => option.price(),
+            ExoticOption::Asian(option) => option.price(),
+            ExoticOption::Spread(option) => option.price(),
+        }
+    }
+
+    fn bumped(&self, factor: &RiskFactor, amount: FloatType) -> Self {
+        match self {
+            ExoticOption::Barrier(option) => ExoticOption::Barrier(option.bumped(factor, amount)),
+            ExoticOption::Asian(option) => ExoticOption::Asian(option.bumped(factor, amount)),
+            ExoticOption::Spread(option) => ExoticOption::Spread(option.bumped(factor, amount)),
+        }
+    }
+
+    fn spot(&self) -> FloatType {
+        self.tick().asset_price
+    }
+
+    fn tau(&self) -> FloatType {
+        self.tick().tau()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::greeks::EuropeanGreeks;
+    use assert_float_eq::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn time_greeks_follow_day_count() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        let tick = OptionTick::builder()
+            .strike(dec!(100))
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(90))
+            .option_type(OptionType::Call)
+            .day_count(DayCount::Act360)
+            .valuation_time(now)
+            .build();
+        assert_float_relative_eq!(tick.numerical_theta(), tick.theta(), 1e-4);
+        assert_float_relative_eq!(tick.numerical_greek(Greek::Charm).unwrap(), tick.charm(), 1e-2);
+    }
+}
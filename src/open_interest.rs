@@ -0,0 +1,118 @@
+//! Open interest and volume analytics of an OptionChain.
+//!
+//! Open interest and volume are read from AdditionalOptionData; ticks without them count as 0. A chain may hold several quotes
+//! of one contract (e.g. a bid and an ask), which carry the same open interest, so the largest value of each contract is used
+//! rather than their sum.
+//! * max_pain: the strike at which the options outstanding pay the least to their holders at expiry
+//! * call_wall / put_wall: the strikes with the largest call and put open interest
+//! * put_call_ratio: put over call open interest or volume
+//!
+//! # Example
+//! ```ignore
+//! let max_pain = snapshots.try_map(|chain| chain.max_pain())?;
+//! let pcr = chain.put_call_ratio(PutCallRatioBasis::ByOi)?;
+//! ```
+
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrikeActivity {
+    pub strike: DecimalType,
+    pub call_oi: FloatType,
+    pub put_oi: FloatType,
+    pub call_volume: FloatType,
+    pub put_volume: FloatType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PutCallRatioBasis {
+    ByVolume,
+    ByOi,
+}
+
+impl OptionChain<OptionTick> {
+    /// Open interest and volume of calls and puts per strike, in ascending order of strike.
+    pub fn oi_by_strike(&self) -> Vec<StrikeActivity> {
+        let mut strikes: BTreeMap<DecimalType, StrikeActivity> = BTreeMap::new();
+        for tick in self.0.iter() {
+            let data = tick.additional_data.as_ref();
+            let oi = data.and_then(|d| d.open_interest).unwrap_or(0.);
+            let volume = data.and_then(|d| d.volume).unwrap_or(0.);
+            let entry = strikes
+                .entry(tick.strike)
+                .or_insert_with(|| StrikeActivity {
+                    strike: tick.strike,
+                    ..Default::default()
+                });
+            let (entry_oi, entry_volume) = match tick.option_type {
+                OptionType::Call => (&mut entry.call_oi, &mut entry.call_volume),
+                OptionType::Put => (&mut entry.put_oi, &mut entry.put_volume),
+            };
+            *entry_oi = entry_oi.max(oi);
+            *entry_volume = entry_volume.max(volume);
+        }
+        strikes.into_values().collect()
+    }
+
+    /// Strike minimizing the total intrinsic value of the open interest at expiry, among the listed strikes.
+    pub fn max_pain(&self) -> Result<DecimalType> {
+        let activity = self.oi_by_strike();
+        ensure!(
+            activity.iter().any(|a| a.call_oi + a.put_oi > 0.),
+            "No open interest is set in the OptionChain"
+        );
+        let payout = |settlement: FloatType| -> FloatType {
+            activity
+                .iter()
+                .map(|a| {
+                    let strike = a.strike.to_f64().unwrap();
+                    a.call_oi * (settlement - strike).max(0.)
+                        + a.put_oi * (strike - settlement).max(0.)
+                })
+                .sum()
+        };
+        activity
+            .iter()
+            .map(|a| (a.strike, payout(a.strike.to_f64().unwrap())))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(strike, _)| strike)
+            .ok_or_else(|| anyhow!("The OptionChain is empty"))
+    }
+
+    /// Strike with the largest call open interest.
+    pub fn call_wall(&self) -> Result<DecimalType> {
+        wall(&self.oi_by_strike(), |a| a.call_oi)
+    }
+
+    /// Strike with the largest put open interest.
+    pub fn put_wall(&self) -> Result<DecimalType> {
+        wall(&self.oi_by_strike(), |a| a.put_oi)
+    }
+
+    /// Total put open interest (or volume) divided by the total call open interest (or volume).
+    pub fn put_call_ratio(&self, basis: PutCallRatioBasis) -> Result<FloatType> {
+        let activity = self.oi_by_strike();
+        let (puts, calls) = activity.iter().fold((0., 0.), |(p, c), a| match basis {
+            PutCallRatioBasis::ByVolume => (p + a.put_volume, c + a.call_volume),
+            PutCallRatioBasis::ByOi => (p + a.put_oi, c + a.call_oi),
+        });
+        ensure!(calls > 0., "The calls have no {:?} activity", basis);
+        Ok(puts / calls)
+    }
+}
+
+fn wall(
+    activity: &[StrikeActivity],
+    f: impl Fn(&StrikeActivity) -> FloatType,
+) -> Result<DecimalType> {
+    activity
+        .iter()
+        .filter(|a| f(a) > 0.)
+        .max_by(|a, b| f(a).partial_cmp(&f(b)).unwrap())
+        .map(|a| a.strike)
+        .ok_or_else(|| anyhow!("No open interest is set in the OptionChain"))
+}
@@ -0,0 +1,77 @@
+//! Derivative-free minimization used by the calibration routines.
+
+use crate::models::FloatType;
+
+/// Minimizes `f` with the Nelder-Mead simplex method, starting from `x0` with initial simplex steps `step`.
+/// Returns the best point and its value. Stops when the spread of the simplex values falls below `tolerance` or after `max_iter` iterations.
+pub(crate) fn nelder_mead(
+    f: impl Fn(&[FloatType]) -> FloatType,
+    x0: &[FloatType],
+    step: &[FloatType],
+    max_iter: usize,
+    tolerance: FloatType,
+) -> (Vec<FloatType>, FloatType) {
+    let n = x0.len();
+    let eval = |x: &[FloatType]| {
+        let v = f(x);
+        if v.is_nan() {
+            FloatType::INFINITY
+        } else {
+            v
+        }
+    };
+
+    let mut simplex: Vec<(Vec<FloatType>, FloatType)> = (0..=n)
+        .map(|i| {
+            let mut x = x0.to_vec();
+            if i > 0 {
+                x[i - 1] += step[i - 1];
+            }
+            let v = eval(&x);
+            (x, v)
+        })
+        .collect();
+
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        if (simplex[n].1 - simplex[0].1).abs() < tolerance {
+            break;
+        }
+
+        let centroid: Vec<FloatType> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<FloatType>() / n as FloatType)
+            .collect();
+        let towards = |coef: FloatType| -> Vec<FloatType> {
+            (0..n)
+                .map(|j| centroid[j] + coef * (simplex[n].0[j] - centroid[j]))
+                .collect()
+        };
+
+        let reflected = towards(-1.);
+        let fr = eval(&reflected);
+        if fr < simplex[0].1 {
+            let expanded = towards(-2.);
+            let fe = eval(&expanded);
+            simplex[n] = if fe < fr { (expanded, fe) } else { (reflected, fr) };
+        } else if fr < simplex[n - 1].1 {
+            simplex[n] = (reflected, fr);
+        } else {
+            let contracted = if fr < simplex[n].1 { towards(-0.5) } else { towards(0.5) };
+            let fc = eval(&contracted);
+            if fc < simplex[n].1.min(fr) {
+                simplex[n] = (contracted, fc);
+            } else {
+                let best = simplex[0].0.clone();
+                for (x, v) in simplex.iter_mut().skip(1) {
+                    for j in 0..n {
+                        x[j] = best[j] + 0.5 * (x[j] - best[j]);
+                    }
+                    *v = eval(x);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    simplex.swap_remove(0)
+}
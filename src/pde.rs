@@ -0,0 +1,432 @@
+//! Finite difference pricing of the Black-Scholes PDE.
+//!
+//! `V_t + 1/2 sigma^2 S^2 V_SS + (r - q) S V_S - r V = 0` is solved backwards from the payoff with the Crank-Nicolson scheme on a uniform grid of S.
+//! The first steps are fully implicit (Rannacher smoothing) to damp the oscillations caused by the kink of the payoff.
+//! * American exercise: the linear complementarity problem of each step is solved by projected SOR.
+//! * Continuous barriers: the grid ends at the barrier, where the rebate is imposed. Knock-in values are obtained by in-out parity for European options.
+//!
+//! Delta and gamma are taken from the grid around the asset price and theta from the last time step.
+//!
+//! get_implied_volatility_american() inverts the finite difference price instead of the Black-Scholes formula,
+//! so that the IV of an American option does not absorb its early exercise premium.
+//!
+//! # Example
+//! ```ignore
+//! let tick = OptionTick::builder()
+//!     ...
+//!     .option_style(OptionStyle::American)
+//!     .pricing_engine(PricingEngine::FiniteDifference { space_steps: 400, time_steps: 400 })
+//!     .build();
+//! let price = tick.price_with_engine()?;
+//! let up_and_out = tick.finite_difference(Some(&barrier))?;
+//! let iv = quote.get_implied_volatility_american()?.iv();
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use anyhow::{ensure, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Grid used when the engine of the tick is not FiniteDifference.
+const DEFAULT_SPACE_STEPS: usize = 300;
+const DEFAULT_TIME_STEPS: usize = 300;
+/// Number of fully implicit steps at the start
+const RANNACHER_STEPS: usize = 4;
+const PSOR_OMEGA: FloatType = 1.2;
+const PSOR_TOLERANCE: FloatType = 1e-10;
+const PSOR_MAX_ITER: usize = 10000;
+/// Lower end of the bracket, tolerance on the price and iteration limit of the American IV search
+const IV_LOWER: FloatType = 1e-3;
+const IV_TOLERANCE: FloatType = 1e-8;
+const IV_MAX_ITER: usize = 100;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FdResult {
+    pub price: FloatType,
+    pub delta: FloatType,
+    pub gamma: FloatType,
+    pub theta: FloatType,
+}
+
+impl OptionTick {
+    /// Prices the tick with its pricing_engine, taking its option_style into account.
+    pub fn price_with_engine(&self) -> Result<FloatType> {
+        match (&self.pricing_engine, &self.option_style) {
+            (PricingEngine::Analytic, OptionStyle::European) => {
+                Ok(self.get_implied_volatility().get_theoretical_price().get_value())
+            }
+            _ => Ok(self.finite_difference(None)?.price),
+        }
+    }
+
+    /// Converts a premium into the IV that reproduces it with the finite difference pricer, taking the option_style into account.
+    /// The early exercise premium makes the American price at least the European one, so the root lies below the European IV;
+    /// it is found by regula falsi (Illinois variant) between IV_LOWER and the European IV. Ticks already quoted in IV are returned as they are.
+    pub fn get_implied_volatility_american(&self) -> Result<OptionTick> {
+        let price = match self.option_value {
+            OptionValue::Price(price) => price,
+            OptionValue::ImpliedVolatility(_) => return Ok(self.clone()),
+        };
+        let difference = |sigma: FloatType| -> Result<FloatType> {
+            let mut tick = self.clone();
+            tick.option_value = OptionValue::ImpliedVolatility(sigma);
+            Ok(tick.finite_difference(None)?.price - price)
+        };
+        // Slightly above the European IV, which is itself only solved to a tolerance
+        let european = self.get_implied_volatility().get_value();
+        let upper = if european.is_finite() && european > IV_LOWER { european * 1.1 } else { 1. };
+        let (mut a, mut b) = (IV_LOWER, upper);
+        let (mut fa, mut fb) = (difference(a)?, difference(b)?);
+        ensure!(
+            fa <= 0. && fb >= 0.,
+            "The premium is outside the range of prices for IVs in [{}, {}]",
+            a,
+            b
+        );
+        let mut side = 0;
+        let mut sigma = a;
+        for _ in 0..IV_MAX_ITER {
+            sigma = (a * fb - b * fa) / (fb - fa);
+            let fs = difference(sigma)?;
+            if fs.abs() < IV_TOLERANCE * price.max(1.) {
+                break;
+            }
+            // Halve the value of an end point kept twice in a row, so that both ends keep moving
+            if fs * fb > 0. {
+                b = sigma;
+                fb = fs;
+                if side == -1 {
+                    fa /= 2.;
+                }
+                side = -1;
+            } else {
+                a = sigma;
+                fa = fs;
+                if side == 1 {
+                    fb /= 2.;
+                }
+                side = 1;
+            }
+        }
+        let mut tick = self.clone();
+        tick.option_value = OptionValue::ImpliedVolatility(sigma);
+        Ok(tick)
+    }
+
+    /// Prices the tick, optionally with a continuous barrier, by finite differences.
+    /// The grid of the FiniteDifference engine is used if set, otherwise a default grid.
+    pub fn finite_difference(&self, barrier: Option<&BarrierSpec>) -> Result<FdResult> {
+        let (space_steps, time_steps) = match self.pricing_engine {
+            PricingEngine::FiniteDifference {
+                space_steps,
+                time_steps,
+            } => (space_steps, time_steps),
+            PricingEngine::Analytic => (DEFAULT_SPACE_STEPS, DEFAULT_TIME_STEPS),
+        };
+        ensure!(space_steps >= 10 && time_steps >= RANNACHER_STEPS, "The grid is too coarse");
+        let american = self.option_style == OptionStyle::American;
+
+        match barrier.map(|b| &b.kind) {
+            Some(BarrierKind::UpAndIn) | Some(BarrierKind::DownAndIn) => {
+                ensure!(!american, "Knock-in barriers are only supported for European options");
+                let barrier = barrier.unwrap();
+                let out_kind = match barrier.kind {
+                    BarrierKind::UpAndIn => BarrierKind::UpAndOut,
+                    _ => BarrierKind::DownAndOut,
+                };
+                let knock_out = BarrierSpec {
+                    kind: out_kind,
+                    level: barrier.level,
+                    rebate: 0.,
+                };
+                let vanilla = solve(self, None, space_steps, time_steps, false)?;
+                let out = solve(self, Some(&knock_out), space_steps, time_steps, false)?;
+                Ok(FdResult {
+                    price: vanilla.price - out.price,
+                    delta: vanilla.delta - out.delta,
+                    gamma: vanilla.gamma - out.gamma,
+                    theta: vanilla.theta - out.theta,
+                })
+            }
+            _ => solve(self, barrier, space_steps, time_steps, american),
+        }
+    }
+}
+
+fn solve(
+    tick: &OptionTick,
+    barrier: Option<&BarrierSpec>,
+    space_steps: usize,
+    time_steps: usize,
+    american: bool,
+) -> Result<FdResult> {
+    let s0 = tick.asset_price;
+    let k = tick.strike.to_f64().unwrap();
+    let r = tick.rate();
+    let q = tick.carry_yield();
+    let sigma = tick.iv();
+    let tau = tick.tau();
+    ensure!(tau > 0., "The option has expired");
+    ensure!(sigma.is_finite() && sigma > 0., "Implied volatility is not available");
+
+    let payoff = |s: FloatType| match tick.option_type {
+        OptionType::Call => (s - k).max(0.),
+        OptionType::Put => (k - s).max(0.),
+    };
+
+    let mut s_min = 0.;
+    let mut s_max = s0.max(k) * (6. * sigma * tau.sqrt()).exp();
+    let rebate = barrier.map_or(0., |b| b.rebate);
+    match barrier.map(|b| &b.kind) {
+        Some(BarrierKind::UpAndOut) => {
+            let level = barrier.unwrap().level;
+            ensure!(s0 < level, "The barrier has already been hit");
+            s_max = level;
+        }
+        Some(BarrierKind::DownAndOut) => {
+            let level = barrier.unwrap().level;
+            ensure!(s0 > level, "The barrier has already been hit");
+            s_min = level;
+        }
+        _ => {}
+    }
+
+    // Place the asset price exactly on a node, and an up barrier exactly on the last node
+    let mut ds = (s_max - s_min) / space_steps as FloatType;
+    let (j0, n) = if matches!(barrier.map(|b| &b.kind), Some(BarrierKind::UpAndOut)) {
+        let to_barrier = (((s_max - s0) / ds).round() as usize).max(1);
+        ds = (s_max - s0) / to_barrier as FloatType;
+        let n = (s_max / ds).floor() as usize;
+        ensure!(n > to_barrier, "The grid is too coarse");
+        s_min = s_max - n as FloatType * ds;
+        (n - to_barrier, n)
+    } else {
+        let j0 = (((s0 - s_min) / ds).round() as usize).max(1);
+        ds = (s0 - s_min) / j0 as FloatType;
+        (j0, (((s_max - s_min) / ds).round() as usize).max(j0 + 2))
+    };
+    let s: Vec<FloatType> = (0..=n).map(|i| s_min + i as FloatType * ds).collect();
+    let dt = tau / time_steps as FloatType;
+
+    let lower_boundary = |t: FloatType| -> FloatType {
+        if matches!(barrier.map(|b| &b.kind), Some(BarrierKind::DownAndOut)) {
+            return rebate;
+        }
+        match tick.option_type {
+            OptionType::Call => 0.,
+            OptionType::Put if american => k - s[0],
+            OptionType::Put => k * (-r * t).exp() - s[0] * (-q * t).exp(),
+        }
+    };
+    let upper_boundary = |t: FloatType| -> FloatType {
+        if matches!(barrier.map(|b| &b.kind), Some(BarrierKind::UpAndOut)) {
+            return rebate;
+        }
+        match tick.option_type {
+            OptionType::Put => 0.,
+            OptionType::Call if american => s[n] - k,
+            OptionType::Call => s[n] * (-q * t).exp() - k * (-r * t).exp(),
+        }
+    };
+
+    // Coefficients of L V at the interior nodes
+    let coefficients: Vec<(FloatType, FloatType, FloatType)> = (1..n)
+        .map(|i| {
+            let diffusion = 0.5 * sigma.powi(2) * s[i].powi(2) / ds.powi(2);
+            let convection = 0.5 * (r - q) * s[i] / ds;
+            (diffusion - convection, -2. * diffusion - r, diffusion + convection)
+        })
+        .collect();
+
+    let exercise: Vec<FloatType> = s.iter().map(|x| payoff(*x)).collect();
+    let mut v = exercise.clone();
+    if barrier.is_some() {
+        v[0] = lower_boundary(0.);
+        v[n] = upper_boundary(0.);
+    }
+    let mut previous = v.clone();
+
+    for step in 1..=time_steps {
+        let t = step as FloatType * dt;
+        let theta = if step <= RANNACHER_STEPS { 1. } else { 0.5 };
+
+        // Right hand side (I + (1 - theta) dt L) V
+        let mut rhs: Vec<FloatType> = (1..n)
+            .map(|i| {
+                let (a, b, c) = coefficients[i - 1];
+                v[i] + (1. - theta) * dt * (a * v[i - 1] + b * v[i] + c * v[i + 1])
+            })
+            .collect();
+        let (lower, upper) = (lower_boundary(t), upper_boundary(t));
+        rhs[0] += theta * dt * coefficients[0].0 * lower;
+        rhs[n - 2] += theta * dt * coefficients[n - 2].2 * upper;
+
+        // Left hand side (I - theta dt L)
+        let sub: Vec<FloatType> = coefficients.iter().map(|(a, _, _)| -theta * dt * a).collect();
+        let diag: Vec<FloatType> = coefficients.iter().map(|(_, b, _)| 1. - theta * dt * b).collect();
+        let sup: Vec<FloatType> = coefficients.iter().map(|(_, _, c)| -theta * dt * c).collect();
+
+        let interior = if american {
+            psor(&sub, &diag, &sup, &rhs, &v[1..n], &exercise[1..n])
+        } else {
+            thomas(&sub, &diag, &sup, &rhs)
+        };
+
+        previous = v.clone();
+        v[0] = lower;
+        v[n] = upper;
+        v[1..n].copy_from_slice(&interior);
+    }
+
+    let j = j0.min(n - 1);
+    Ok(FdResult {
+        price: v[j],
+        delta: (v[j + 1] - v[j - 1]) / (2. * ds),
+        gamma: (v[j + 1] - 2. * v[j] + v[j - 1]) / ds.powi(2),
+        theta: (previous[j] - v[j]) / dt,
+    })
+}
+
+/// Solves a tridiagonal system. sub[0] and sup[last] are ignored.
+fn thomas(sub: &[FloatType], diag: &[FloatType], sup: &[FloatType], rhs: &[FloatType]) -> Vec<FloatType> {
+    let m = diag.len();
+    let mut c = vec![0.; m];
+    let mut d = vec![0.; m];
+    c[0] = sup[0] / diag[0];
+    d[0] = rhs[0] / diag[0];
+    for i in 1..m {
+        let denominator = diag[i] - sub[i] * c[i - 1];
+        c[i] = sup[i] / denominator;
+        d[i] = (rhs[i] - sub[i] * d[i - 1]) / denominator;
+    }
+    let mut x = vec![0.; m];
+    x[m - 1] = d[m - 1];
+    for i in (0..m - 1).rev() {
+        x[i] = d[i] - c[i] * x[i + 1];
+    }
+    x
+}
+
+/// Projected SOR for the tridiagonal system with the constraint x >= obstacle.
+fn psor(
+    sub: &[FloatType],
+    diag: &[FloatType],
+    sup: &[FloatType],
+    rhs: &[FloatType],
+    initial: &[FloatType],
+    obstacle: &[FloatType],
+) -> Vec<FloatType> {
+    let m = diag.len();
+    let mut x = initial.to_vec();
+    for _ in 0..PSOR_MAX_ITER {
+        let mut error = 0.;
+        for i in 0..m {
+            let left = if i > 0 { sub[i] * x[i - 1] } else { 0. };
+            let right = if i < m - 1 { sup[i] * x[i + 1] } else { 0. };
+            let gauss_seidel = (rhs[i] - left - right) / diag[i];
+            let updated = (x[i] + PSOR_OMEGA * (gauss_seidel - x[i])).max(obstacle[i]);
+            error += (updated - x[i]).powi(2);
+            x[i] = updated;
+        }
+        if error < PSOR_TOLERANCE {
+            break;
+        }
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::greeks::EuropeanGreeks;
+    use assert_float_eq::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn tick(strike: DecimalType, option_type: OptionType) -> OptionTick {
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+        OptionTick::builder()
+            .strike(strike)
+            .asset_price(100.)
+            .risk_free_rate(0.05)
+            .dividend_yield(0.02)
+            .option_value(OptionValue::ImpliedVolatility(0.2))
+            .maturity(now + Duration::days(365))
+            .option_type(option_type)
+            .valuation_time(now)
+            .build()
+    }
+
+    #[test]
+    fn european_matches_black_scholes() {
+        for strike in [dec!(80), dec!(100), dec!(120)] {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let tick = tick(strike, option_type);
+                let fd = tick.finite_difference(None).unwrap();
+                assert_float_absolute_eq!(fd.price, tick.get_theoretical_price().get_value(), 5e-3);
+                assert_float_absolute_eq!(fd.delta, tick.delta(), 1e-3);
+                assert_float_absolute_eq!(fd.gamma, tick.gamma(), 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn barriers_match_closed_form() {
+        let cases = [
+            (dec!(100), OptionType::Call, BarrierKind::DownAndOut, 90.),
+            (dec!(80), OptionType::Call, BarrierKind::UpAndOut, 110.),
+            (dec!(100), OptionType::Put, BarrierKind::DownAndOut, 90.),
+            (dec!(120), OptionType::Put, BarrierKind::UpAndOut, 110.),
+            (dec!(100), OptionType::Call, BarrierKind::UpAndIn, 110.),
+            (dec!(100), OptionType::Put, BarrierKind::DownAndIn, 90.),
+        ];
+        for (strike, option_type, kind, level) in cases {
+            let tick = tick(strike, option_type);
+            let barrier = BarrierSpec::builder().kind(kind).level(level).build();
+            assert_float_absolute_eq!(
+                tick.finite_difference(Some(&barrier)).unwrap().price,
+                tick.barrier_price(&barrier).unwrap(),
+                5e-3
+            );
+        }
+    }
+
+    /// American put by a Cox-Ross-Rubinstein tree, averaged over n and n + 1 steps to damp the odd-even oscillation.
+    fn crr_american_put(tick: &OptionTick, n: usize) -> FloatType {
+        let (s, k) = (tick.asset_price, tick.strike.to_f64().unwrap());
+        let (r, q, sigma, tau) = (tick.risk_free_rate, tick.dividend_yield, tick.get_value(), tick.tau());
+        let price = |n: usize| {
+            let dt = tau / n as FloatType;
+            let (u, disc) = ((sigma * dt.sqrt()).exp(), (-r * dt).exp());
+            let p = (((r - q) * dt).exp() - 1. / u) / (u - 1. / u);
+            let mut values: Vec<FloatType> = (0..=n)
+                .map(|j| (k - s * u.powi(2 * j as i32 - n as i32)).max(0.))
+                .collect();
+            for step in (0..n).rev() {
+                for j in 0..=step {
+                    let continuation = disc * (p * values[j + 1] + (1. - p) * values[j]);
+                    values[j] = continuation.max(k - s * u.powi(2 * j as i32 - step as i32));
+                }
+            }
+            values[0]
+        };
+        (price(n) + price(n + 1)) / 2.
+    }
+
+    #[test]
+    fn american_put_matches_binomial_tree() {
+        for strike in [dec!(90), dec!(100), dec!(110)] {
+            let mut tick = tick(strike, OptionType::Put);
+            tick.option_style = OptionStyle::American;
+            let fd = tick.finite_difference(None).unwrap().price;
+            assert_float_absolute_eq!(fd, crr_american_put(&tick, 2000), 5e-3);
+            assert!(fd > tick.get_theoretical_price().get_value());
+
+            // the IV solver recovers the volatility from the American premium
+            tick.option_value = OptionValue::Price(fd);
+            let iv = tick.get_implied_volatility_american().unwrap().get_value();
+            assert_float_absolute_eq!(iv, 0.2, 1e-6);
+        }
+    }
+}
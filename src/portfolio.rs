@@ -0,0 +1,259 @@
+//! Positions and portfolios of options with aggregate greeks and scenario evaluation.
+//!
+//! A Position is an OptionTick held with a signed quantity (positive for long, negative for short).
+//! An UnderlyingPosition is a delta-one leg in the underlying asset, such as the stock of a covered call or a futures hedge.
+//! Portfolio greeks are the quantity-weighted sums of the greeks of each position; underlying legs contribute their quantity
+//! to the delta and nothing to the other greeks.
+//!
+//! # Example
+//! ```ignore
+//! let mut portfolio = Portfolio::new();
+//! portfolio.add(call, 1.);
+//! portfolio.add(put, -2.);
+//! portfolio.add_underlying(UnderlyingTick::builder().price(spot).timestamp(now).build(), 0.5);
+//! println!("delta: {}, vega: {}", portfolio.delta(), portfolio.vega());
+//! println!("pnl if spot +5% and vol +2pt in 7 days: {}", portfolio.pnl(spot * 1.05, 0.02, 7.));
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use paste::paste;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Position {
+    pub tick: OptionTick,
+    /// Number of options held. Positive for long, negative for short.
+    pub quantity: FloatType,
+}
+
+impl Position {
+    pub fn new(tick: OptionTick, quantity: FloatType) -> Self {
+        Self { tick, quantity }
+    }
+
+    /// Returns the tick with its option_value converted to implied volatility, which is required for the greeks.
+    pub(crate) fn with_iv(&self) -> OptionTick {
+        self.tick.get_implied_volatility()
+    }
+
+    /// Payoff of the position at expiry for the given spot price.
+    pub fn payoff_at_expiry(&self, spot: FloatType) -> FloatType {
+        self.quantity * intrinsic_value(&self.tick, spot)
+    }
+
+    /// Theoretical value of the position after moving the spot, shifting the IV by `iv_shift` (absolute, e.g. 0.01 = +1 vol point) and letting `days` pass.
+    /// After expiry, the intrinsic value is used.
+    pub fn value_at(&self, spot: FloatType, iv_shift: FloatType, days: FloatType) -> FloatType {
+        let tick = shocked_tick(&self.tick, spot, iv_shift, days);
+        if tick.tau() <= 0. {
+            return self.quantity * intrinsic_value(&tick, spot);
+        }
+        self.quantity * tick.get_theoretical_price().get_value()
+    }
+
+    /// Current theoretical value of the position.
+    pub fn value(&self) -> FloatType {
+        self.quantity * self.with_iv().get_theoretical_price().get_value()
+    }
+}
+
+/// Returns the tick valued by implied volatility with the spot replaced, the IV shifted by `iv_shift` and the valuation time
+/// moved `days` later (see OptionTick::with_days_elapsed).
+pub(crate) fn shocked_tick(
+    tick: &OptionTick,
+    spot: FloatType,
+    iv_shift: FloatType,
+    days: FloatType,
+) -> OptionTick {
+    let mut tick = tick.get_implied_volatility().with_days_elapsed(days);
+    tick.asset_price = spot;
+    tick.option_value = OptionValue::ImpliedVolatility(tick.get_value() + iv_shift);
+    tick
+}
+
+pub(crate) fn intrinsic_value(tick: &OptionTick, spot: FloatType) -> FloatType {
+    let strike = tick.strike.to_f64().unwrap();
+    match tick.option_type {
+        OptionType::Call => (spot - strike).max(0.),
+        OptionType::Put => (strike - spot).max(0.),
+    }
+}
+
+/// A quantity of the underlying asset. Its delta is the quantity; it has no other greeks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnderlyingPosition {
+    pub tick: UnderlyingTick,
+    /// Units held. Positive for long, negative for short.
+    pub quantity: FloatType,
+}
+
+impl UnderlyingPosition {
+    pub fn new(tick: UnderlyingTick, quantity: FloatType) -> Self {
+        Self { tick, quantity }
+    }
+
+    pub fn value(&self) -> FloatType {
+        self.quantity * self.tick.price
+    }
+
+    pub fn value_at(&self, spot: FloatType) -> FloatType {
+        self.quantity * spot
+    }
+}
+
+/// Option positions and underlying positions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "PortfolioRepr")]
+pub struct Portfolio {
+    pub positions: Vec<Position>,
+    /// Delta-one legs, e.g. the stock of a covered call or a futures hedge
+    pub underlyings: Vec<UnderlyingPosition>,
+}
+
+/// Serialized forms of a Portfolio: the list of option positions written before underlying legs existed, or both lists.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PortfolioRepr {
+    Positions(Vec<Position>),
+    Legs {
+        positions: Vec<Position>,
+        #[serde(default)]
+        underlyings: Vec<UnderlyingPosition>,
+    },
+}
+
+impl From<PortfolioRepr> for Portfolio {
+    fn from(repr: PortfolioRepr) -> Self {
+        match repr {
+            PortfolioRepr::Positions(positions) => Self::from_positions(positions),
+            PortfolioRepr::Legs {
+                positions,
+                underlyings,
+            } => Self {
+                positions,
+                underlyings,
+            },
+        }
+    }
+}
+
+macro_rules! portfolio_greeks {
+	($($greeks_name:ident),*) => {
+		impl Portfolio {
+			$(
+				paste! {
+					#[doc = "Sum of the " $greeks_name " of each position weighted by its quantity."]
+					pub fn $greeks_name(&self) -> FloatType {
+						self.positions
+							.iter()
+							.map(|p| p.quantity * p.with_iv().$greeks_name())
+							.sum()
+					}
+				}
+			)*
+		}
+	};
+}
+
+portfolio_greeks!(
+    gamma, theta, rho, vega, epsilon, vanna, charm, vomma, veta, speed, zomma, color, ultima,
+    dual_delta, dual_gamma
+);
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Portfolio of option positions without underlying legs.
+    pub fn from_positions(positions: Vec<Position>) -> Self {
+        Self {
+            positions,
+            underlyings: Vec::new(),
+        }
+    }
+
+    /// Sum of the delta of each option position weighted by its quantity, plus the quantity of the underlying positions.
+    pub fn delta(&self) -> FloatType {
+        self.option_delta() + self.underlying_delta()
+    }
+
+    /// Delta of the option positions only.
+    pub fn option_delta(&self) -> FloatType {
+        self.positions
+            .iter()
+            .map(|p| p.quantity * p.with_iv().delta())
+            .sum()
+    }
+
+    /// Delta of the underlying positions, i.e. the net quantity held.
+    pub fn underlying_delta(&self) -> FloatType {
+        self.underlyings.iter().map(|p| p.quantity).sum()
+    }
+
+    pub fn push(&mut self, position: Position) {
+        self.positions.push(position);
+    }
+
+    pub fn add(&mut self, tick: OptionTick, quantity: FloatType) {
+        self.positions.push(Position::new(tick, quantity));
+    }
+
+    pub fn add_underlying(&mut self, tick: UnderlyingTick, quantity: FloatType) {
+        self.underlyings.push(UnderlyingPosition::new(tick, quantity));
+    }
+
+    /// Returns the portfolio with every quantity multiplied by `factor` (e.g. -1 to sell the whole structure).
+    pub fn scaled(&self, factor: FloatType) -> Self {
+        Self {
+            positions: self
+                .positions
+                .iter()
+                .map(|p| Position::new(p.tick.clone(), p.quantity * factor))
+                .collect(),
+            underlyings: self
+                .underlyings
+                .iter()
+                .map(|p| UnderlyingPosition::new(p.tick.clone(), p.quantity * factor))
+                .collect(),
+        }
+    }
+
+    /// Current theoretical value of the portfolio, the underlying positions valued at their price.
+    pub fn value(&self) -> FloatType {
+        self.positions.iter().map(|p| p.value()).sum::<FloatType>()
+            + self.underlyings.iter().map(|p| p.value()).sum::<FloatType>()
+    }
+
+    /// Value of the underlying positions if the underlying trades at `spot`.
+    pub fn underlying_value_at(&self, spot: FloatType) -> FloatType {
+        self.underlyings.iter().map(|p| p.value_at(spot)).sum()
+    }
+
+    /// Payoff of the portfolio at expiry for the given spot price.
+    /// All options are assumed to expire at the same time.
+    pub fn payoff_at_expiry(&self, spot: FloatType) -> FloatType {
+        self.positions
+            .iter()
+            .map(|p| p.payoff_at_expiry(spot))
+            .sum::<FloatType>()
+            + self.underlying_value_at(spot)
+    }
+
+    /// Profit and loss of the portfolio relative to its current value in a scenario.
+    /// * spot: new price of the underlying asset
+    /// * iv_shift: absolute shift added to every IV (0.01 = +1 vol point)
+    /// * days: number of days that pass
+    pub fn pnl(&self, spot: FloatType, iv_shift: FloatType, days: FloatType) -> FloatType {
+        let options: FloatType = self
+            .positions
+            .iter()
+            .map(|p| p.value_at(spot, iv_shift, days) - p.value())
+            .sum();
+        let underlying: FloatType = self.underlyings.iter().map(|p| p.value_at(spot) - p.value()).sum();
+        options + underlying
+    }
+}
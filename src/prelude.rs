@@ -1,4 +1,56 @@
+pub use crate::arbitrage::*;
+pub use crate::asian::*;
+pub use crate::bachelier::*;
+pub use crate::backtest::*;
+pub use crate::batch::*;
 pub use crate::black_scholes::*;
+pub use crate::bootstrap::*;
+pub use crate::bus::*;
+pub use crate::calendar::*;
+pub use crate::calibration::*;
+pub use crate::carry::*;
+pub use crate::checkpoint::*;
+pub use crate::cleaning::*;
+pub use crate::construction::*;
+pub use crate::context::*;
+pub use crate::contract::*;
+pub use crate::diagram::*;
+pub use crate::discount_curve::*;
+pub use crate::dispersion::*;
+pub use crate::dividend::*;
+pub use crate::engine::*;
+pub use crate::expected_move::*;
+pub use crate::expiry::*;
 pub use crate::exposure::*;
+pub use crate::feed::*;
+pub use crate::flow::*;
+pub use crate::greek_grid::*;
 pub use crate::greeks::*;
+pub use crate::hedging::*;
+pub use crate::heston::*;
+pub use crate::implied_distribution::*;
+pub use crate::iv_rank::*;
+pub use crate::kalman::*;
+pub use crate::ladder::*;
+pub use crate::liquidity::*;
+pub use crate::math::*;
 pub use crate::models::*;
+pub use crate::monte_carlo::*;
+pub use crate::numerical_greeks::*;
+pub use crate::open_interest::*;
+pub use crate::pde::*;
+pub use crate::portfolio::*;
+pub use crate::realized_vol::*;
+pub use crate::recorder::*;
+pub use crate::regime::*;
+pub use crate::risk::*;
+pub use crate::scenario::*;
+pub use crate::screening::*;
+pub use crate::seasonality::*;
+pub use crate::skew_term_structure::*;
+pub use crate::smile_dynamics::*;
+pub use crate::smile_smoothing::*;
+pub use crate::spread::*;
+pub use crate::term_structure::*;
+pub use crate::vol_cone::*;
+pub use crate::wing::*;
@@ -0,0 +1,117 @@
+//! Realized volatility estimators computed from OHLC data of the underlying asset.
+//!
+//! All estimators return an annualized volatility, so they can be compared directly with implied volatility (e.g. IV - RV spread).
+//! `periods_per_year` is the number of bars in a year, e.g. 252 for daily bars or 252 * 24 for hourly bars of a 24h market.
+//!
+//! # Example
+//! ```ignore
+//! let rv = ohlc_ts.realized_vol(RealizedVolEstimator::YangZhang, 252.)?;
+//! let iv_rv_spread = atm_iv - rv;
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// Open, high, low and close price of the underlying asset in one period.
+#[derive(Clone, Debug, PartialEq, TypedBuilder, Serialize, Deserialize)]
+pub struct Ohlc {
+    pub open: FloatType,
+    pub high: FloatType,
+    pub low: FloatType,
+    pub close: FloatType,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RealizedVolEstimator {
+    CloseToClose,
+    Parkinson,
+    GarmanKlass,
+    YangZhang,
+}
+
+impl TimeSeries<Ohlc> {
+    /// Calculates the annualized realized volatility with the given estimator.
+    pub fn realized_vol(
+        &self,
+        estimator: RealizedVolEstimator,
+        periods_per_year: FloatType,
+    ) -> Result<FloatType> {
+        match estimator {
+            RealizedVolEstimator::CloseToClose => self.close_to_close_vol(periods_per_year),
+            RealizedVolEstimator::Parkinson => self.parkinson_vol(periods_per_year),
+            RealizedVolEstimator::GarmanKlass => self.garman_klass_vol(periods_per_year),
+            RealizedVolEstimator::YangZhang => self.yang_zhang_vol(periods_per_year),
+        }
+    }
+
+    /// Standard deviation of close-to-close log returns.
+    pub fn close_to_close_vol(&self, periods_per_year: FloatType) -> Result<FloatType> {
+        ensure!(self.0.len() >= 3, "At least 3 periods are required");
+        let returns: Vec<FloatType> = self
+            .0
+            .windows(2)
+            .map(|w| (w[1].close / w[0].close).ln())
+            .collect();
+        Ok((sample_variance(&returns) * periods_per_year).sqrt())
+    }
+
+    /// Parkinson estimator using the high-low range.
+    /// `sigma^2 = sum(ln(H/L)^2) / (4 n ln2)`
+    pub fn parkinson_vol(&self, periods_per_year: FloatType) -> Result<FloatType> {
+        ensure!(!self.0.is_empty(), "TimeSeries is empty");
+        let n = self.0.len() as FloatType;
+        let sum: FloatType = self.0.iter().map(|x| (x.high / x.low).ln().powi(2)).sum();
+        Ok((sum / (4. * n * (2.0 as FloatType).ln()) * periods_per_year).sqrt())
+    }
+
+    /// Garman-Klass estimator using open, high, low and close.
+    /// `sigma^2 = sum(0.5 ln(H/L)^2 - (2 ln2 - 1) ln(C/O)^2) / n`
+    pub fn garman_klass_vol(&self, periods_per_year: FloatType) -> Result<FloatType> {
+        ensure!(!self.0.is_empty(), "TimeSeries is empty");
+        let n = self.0.len() as FloatType;
+        let k = 2. * (2.0 as FloatType).ln() - 1.;
+        let sum: FloatType = self
+            .0
+            .iter()
+            .map(|x| 0.5 * (x.high / x.low).ln().powi(2) - k * (x.close / x.open).ln().powi(2))
+            .sum();
+        Ok((sum / n * periods_per_year).sqrt())
+    }
+
+    /// Yang-Zhang estimator, which combines overnight (close-to-open), open-to-close and Rogers-Satchell variances.
+    /// It is unbiased in the presence of opening jumps and drift.
+    pub fn yang_zhang_vol(&self, periods_per_year: FloatType) -> Result<FloatType> {
+        ensure!(self.0.len() >= 3, "At least 3 periods are required");
+        let bars = &self.0[1..];
+        let n = bars.len() as FloatType;
+
+        let overnight: Vec<FloatType> = self
+            .0
+            .windows(2)
+            .map(|w| (w[1].open / w[0].close).ln())
+            .collect();
+        let open_to_close: Vec<FloatType> = bars.iter().map(|x| (x.close / x.open).ln()).collect();
+        let rogers_satchell: FloatType = bars
+            .iter()
+            .map(|x| {
+                (x.high / x.close).ln() * (x.high / x.open).ln()
+                    + (x.low / x.close).ln() * (x.low / x.open).ln()
+            })
+            .sum::<FloatType>()
+            / n;
+
+        let k = 0.34 / (1.34 + (n + 1.) / (n - 1.));
+        let variance = sample_variance(&overnight)
+            + k * sample_variance(&open_to_close)
+            + (1. - k) * rogers_satchell;
+        Ok((variance * periods_per_year).sqrt())
+    }
+}
+
+fn sample_variance(x: &[FloatType]) -> FloatType {
+    let n = x.len() as FloatType;
+    let mean = x.iter().sum::<FloatType>() / n;
+    x.iter().map(|v| (v - mean).powi(2)).sum::<FloatType>() / (n - 1.)
+}
@@ -0,0 +1,272 @@
+//! Regime detection on volatility time series.
+//!
+//! Two complementary tools are provided:
+//! * Change-point detection by binary segmentation of the mean, which finds the times when the level of the series shifts.
+//! * A two-state Gaussian hidden Markov model (HMM) fitted by Baum-Welch, which labels each observation as the low (0) or high (1) volatility regime.
+//!
+//! Both return labels as a TimeSeries, so they can be combined with other indicators for regime-conditional backtests.
+//!
+//! # Example
+//! ```ignore
+//! let fit = atm_iv_ts.fit_hmm(&HmmConfig::builder().build())?;
+//! let high_vol = fit.labels.map(|s| *s == 1);
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct HmmConfig {
+    #[builder(default = 200)]
+    pub max_iter: usize,
+    /// Convergence threshold on the log-likelihood improvement
+    #[builder(default = 1e-8)]
+    pub tolerance: FloatType,
+}
+
+/// Result of fitting a two-state Gaussian HMM.
+/// State 0 is always the regime with the lower mean.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HmmFit {
+    pub means: [FloatType; 2],
+    pub variances: [FloatType; 2],
+    /// transition[i][j] is the probability of moving from state i to state j
+    pub transition: [[FloatType; 2]; 2],
+    pub initial: [FloatType; 2],
+    pub log_likelihood: FloatType,
+    /// Most likely state path (Viterbi)
+    pub labels: TimeSeries<usize>,
+    /// Smoothed probability of being in the high regime (state 1)
+    pub high_regime_probability: TimeSeries<FloatType>,
+}
+
+impl TimeSeries<FloatType> {
+    /// Detects shifts of the mean by binary segmentation.
+    /// A segment is split at the point that most reduces the sum of squared deviations, as long as the reduction exceeds `penalty` and both parts have at least `min_segment` observations.
+    /// Returns the sorted indices where a new segment starts. A segment containing a NaN is not split.
+    pub fn change_points(&self, min_segment: usize, penalty: FloatType) -> Vec<usize> {
+        let min_segment = min_segment.max(1);
+        let mut points = Vec::new();
+        let mut stack = vec![(0, self.0.len())];
+
+        while let Some((start, end)) = stack.pop() {
+            let segment = &self.0[start..end];
+            if segment.len() < 2 * min_segment {
+                continue;
+            }
+            let total = sum_squared_deviation(segment);
+            let best = (min_segment..=segment.len() - min_segment)
+                .map(|i| {
+                    let gain = total
+                        - sum_squared_deviation(&segment[..i])
+                        - sum_squared_deviation(&segment[i..]);
+                    (i, gain)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((i, gain)) = best {
+                if gain > penalty {
+                    points.push(start + i);
+                    stack.push((start, start + i));
+                    stack.push((start + i, end));
+                }
+            }
+        }
+        points.sort_unstable();
+        points
+    }
+
+    /// Labels each observation with the index of the segment it belongs to.
+    pub fn segment_labels(&self, min_segment: usize, penalty: FloatType) -> TimeSeries<usize> {
+        let points = self.change_points(min_segment, penalty);
+        TimeSeries(
+            (0..self.0.len())
+                .map(|t| points.iter().filter(|p| **p <= t).count())
+                .collect(),
+        )
+    }
+
+    /// Fits a two-state Gaussian HMM by Baum-Welch and returns the parameters and the Viterbi state path.
+    pub fn fit_hmm(&self, config: &HmmConfig) -> Result<HmmFit> {
+        let y = &self.0;
+        let n = y.len();
+        ensure!(n >= 4, "At least 4 observations are required");
+        ensure!(y.iter().all(|x| x.is_finite()), "TimeSeries contains non-finite values");
+
+        // initialize with the lower and upper halves of the data
+        let mut sorted = y.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (low, high) = sorted.split_at(n / 2);
+        let mut means = [mean(low), mean(high)];
+        let overall_var = variance(y).max(FloatType::EPSILON);
+        let mut variances = [overall_var, overall_var];
+        let mut transition = [[0.95, 0.05], [0.05, 0.95]];
+        let mut initial = [0.5, 0.5];
+        let mut log_likelihood = FloatType::NEG_INFINITY;
+        let mut gamma = vec![[0.; 2]; n];
+
+        for _ in 0..config.max_iter {
+            // densities relative to the larger of the two at each time, so that they cannot both underflow far in the tails
+            let log_emission: Vec<[FloatType; 2]> = y
+                .iter()
+                .map(|x| [log_normal_pdf(*x, means[0], variances[0]), log_normal_pdf(*x, means[1], variances[1])])
+                .collect();
+            let log_offset: FloatType = log_emission.iter().map(|l| l[0].max(l[1])).sum();
+            let emission: Vec<[FloatType; 2]> = log_emission
+                .iter()
+                .map(|l| {
+                    let m = l[0].max(l[1]);
+                    [(l[0] - m).exp(), (l[1] - m).exp()]
+                })
+                .collect();
+
+            // scaled forward pass
+            let mut alpha = vec![[0.; 2]; n];
+            let mut scale = vec![0.; n];
+            for t in 0..n {
+                for j in 0..2 {
+                    let prior = if t == 0 {
+                        initial[j]
+                    } else {
+                        alpha[t - 1][0] * transition[0][j] + alpha[t - 1][1] * transition[1][j]
+                    };
+                    alpha[t][j] = prior * emission[t][j];
+                }
+                scale[t] = (alpha[t][0] + alpha[t][1]).max(FloatType::MIN_POSITIVE);
+                alpha[t][0] /= scale[t];
+                alpha[t][1] /= scale[t];
+            }
+
+            // scaled backward pass
+            let mut beta = vec![[1.; 2]; n];
+            for t in (0..n - 1).rev() {
+                for i in 0..2 {
+                    beta[t][i] = (0..2)
+                        .map(|j| transition[i][j] * emission[t + 1][j] * beta[t + 1][j])
+                        .sum::<FloatType>()
+                        / scale[t + 1];
+                }
+            }
+
+            // E step
+            let mut xi_sum = [[0.; 2]; 2];
+            for t in 0..n {
+                let g = [alpha[t][0] * beta[t][0], alpha[t][1] * beta[t][1]];
+                let s = g[0] + g[1];
+                gamma[t] = if s > 0. { [g[0] / s, g[1] / s] } else { [0.5, 0.5] };
+                if t + 1 < n {
+                    for i in 0..2 {
+                        for j in 0..2 {
+                            xi_sum[i][j] += alpha[t][i] * transition[i][j] * emission[t + 1][j]
+                                * beta[t + 1][j]
+                                / scale[t + 1];
+                        }
+                    }
+                }
+            }
+
+            // M step
+            initial = gamma[0];
+            for i in 0..2 {
+                let row = xi_sum[i][0] + xi_sum[i][1];
+                if row > 0. {
+                    transition[i] = [xi_sum[i][0] / row, xi_sum[i][1] / row];
+                }
+                let weight: FloatType = gamma.iter().map(|g| g[i]).sum();
+                if weight > 0. {
+                    means[i] = gamma.iter().zip(y).map(|(g, x)| g[i] * x).sum::<FloatType>() / weight;
+                    variances[i] = (gamma
+                        .iter()
+                        .zip(y)
+                        .map(|(g, x)| g[i] * (x - means[i]).powi(2))
+                        .sum::<FloatType>()
+                        / weight)
+                        .max(overall_var * 1e-6);
+                }
+            }
+
+            let new_log_likelihood: FloatType = scale.iter().map(|s| s.ln()).sum::<FloatType>() + log_offset;
+            let converged = (new_log_likelihood - log_likelihood).abs() < config.tolerance;
+            log_likelihood = new_log_likelihood;
+            if converged {
+                break;
+            }
+        }
+
+        // order the states so that state 0 is the low regime
+        if means[0] > means[1] {
+            means.swap(0, 1);
+            variances.swap(0, 1);
+            initial.swap(0, 1);
+            transition = [
+                [transition[1][1], transition[1][0]],
+                [transition[0][1], transition[0][0]],
+            ];
+            gamma.iter_mut().for_each(|g| g.swap(0, 1));
+        }
+
+        let labels = viterbi(y, &means, &variances, &transition, &initial);
+        Ok(HmmFit {
+            means,
+            variances,
+            transition,
+            initial,
+            log_likelihood,
+            labels: TimeSeries(labels),
+            high_regime_probability: TimeSeries(gamma.iter().map(|g| g[1]).collect()),
+        })
+    }
+}
+
+fn viterbi(
+    y: &[FloatType],
+    means: &[FloatType; 2],
+    variances: &[FloatType; 2],
+    transition: &[[FloatType; 2]; 2],
+    initial: &[FloatType; 2],
+) -> Vec<usize> {
+    let n = y.len();
+    let log_emission = |x: FloatType, j: usize| log_normal_pdf(x, means[j], variances[j]);
+    let mut delta = vec![[0.; 2]; n];
+    let mut backpointer = vec![[0usize; 2]; n];
+    for j in 0..2 {
+        delta[0][j] = initial[j].max(FloatType::MIN_POSITIVE).ln() + log_emission(y[0], j);
+    }
+    for t in 1..n {
+        for j in 0..2 {
+            let candidates = [
+                delta[t - 1][0] + transition[0][j].max(FloatType::MIN_POSITIVE).ln(),
+                delta[t - 1][1] + transition[1][j].max(FloatType::MIN_POSITIVE).ln(),
+            ];
+            let i = usize::from(candidates[1] > candidates[0]);
+            backpointer[t][j] = i;
+            delta[t][j] = candidates[i] + log_emission(y[t], j);
+        }
+    }
+    let mut path = vec![0; n];
+    path[n - 1] = usize::from(delta[n - 1][1] > delta[n - 1][0]);
+    for t in (0..n - 1).rev() {
+        path[t] = backpointer[t + 1][path[t + 1]];
+    }
+    path
+}
+
+fn log_normal_pdf(x: FloatType, mean: FloatType, variance: FloatType) -> FloatType {
+    -(x - mean).powi(2) / (2. * variance) - (2. * std::f64::consts::PI * variance).ln() / 2.
+}
+
+fn mean(x: &[FloatType]) -> FloatType {
+    x.iter().sum::<FloatType>() / x.len() as FloatType
+}
+
+fn variance(x: &[FloatType]) -> FloatType {
+    let m = mean(x);
+    x.iter().map(|v| (v - m).powi(2)).sum::<FloatType>() / x.len() as FloatType
+}
+
+fn sum_squared_deviation(x: &[FloatType]) -> FloatType {
+    let m = mean(x);
+    x.iter().map(|v| (v - m).powi(2)).sum()
+}
@@ -0,0 +1,187 @@
+//! Value-at-Risk and expected shortfall of option portfolios.
+//!
+//! Both measures are reported as positive losses over a horizon in days at a confidence level such as 0.99.
+//! - DeltaGamma approximates the P&L by delta * dS + gamma * dS^2 / 2 + theta * dt with normal log returns of the underlying and
+//!   takes the quantile with the Cornish-Fisher expansion, which accounts for the skew introduced by gamma.
+//! - Historical revalues every position in full under each historical move of the spot and the IV. The moves are one-period moves
+//!   (e.g. daily) and are scaled to the horizon by the square root of time.
+//!
+//! # Example
+//! ```ignore
+//! let moves = TimeSeries(history.iter().map(|d| RiskFactorMove::new(d.spot_return, d.iv_change)).collect());
+//! let var = portfolio.var(0.99, 1., &VarMethod::Historical(moves))?;
+//! let es = portfolio.expected_shortfall(0.99, 1., &VarMethod::DeltaGamma { spot_vol: 0.6 })?;
+//! ```
+
+use crate::greeks::EuropeanGreeks;
+use crate::math::norm_inv_cdf;
+use crate::models::*;
+use crate::portfolio::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// Number of quantiles averaged to compute the expected shortfall of the DeltaGamma method.
+const ES_QUANTILES: usize = 1000;
+
+/// One-period move of the risk factors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RiskFactorMove {
+    /// Relative return of the underlying (0.01 = +1%)
+    pub spot_return: FloatType,
+    /// Absolute change of every IV (0.01 = +1 vol point)
+    pub iv_change: FloatType,
+}
+
+impl RiskFactorMove {
+    pub fn new(spot_return: FloatType, iv_change: FloatType) -> Self {
+        Self {
+            spot_return,
+            iv_change,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VarMethod {
+    /// Delta-gamma parametric VaR with the annualized volatility of the underlying.
+    DeltaGamma { spot_vol: FloatType },
+    /// Historical simulation over one-period moves of the spot and the IV.
+    Historical(TimeSeries<RiskFactorMove>),
+}
+
+impl Portfolio {
+    /// Value-at-Risk at `confidence` over `horizon` days, as a positive loss.
+    pub fn var(
+        &self,
+        confidence: FloatType,
+        horizon: FloatType,
+        method: &VarMethod,
+    ) -> Result<FloatType> {
+        ensure!(
+            0. < confidence && confidence < 1.,
+            "confidence must be in (0, 1)"
+        );
+        ensure!(horizon >= 0., "horizon must not be negative");
+        match method {
+            VarMethod::DeltaGamma { spot_vol } => Ok(self
+                .delta_gamma_moments(*spot_vol, horizon)
+                .loss_quantile(confidence)),
+            VarMethod::Historical(moves) => {
+                let losses = self.historical_losses(moves, horizon)?;
+                Ok(losses[tail_index(losses.len(), confidence)])
+            }
+        }
+    }
+
+    /// Expected shortfall (conditional VaR) at `confidence` over `horizon` days: the mean loss beyond the VaR.
+    pub fn expected_shortfall(
+        &self,
+        confidence: FloatType,
+        horizon: FloatType,
+        method: &VarMethod,
+    ) -> Result<FloatType> {
+        ensure!(
+            0. < confidence && confidence < 1.,
+            "confidence must be in (0, 1)"
+        );
+        ensure!(horizon >= 0., "horizon must not be negative");
+        match method {
+            VarMethod::DeltaGamma { spot_vol } => {
+                let moments = self.delta_gamma_moments(*spot_vol, horizon);
+                let step = (1. - confidence) / ES_QUANTILES as FloatType;
+                Ok((0..ES_QUANTILES)
+                    .map(|i| moments.loss_quantile(confidence + (i as FloatType + 0.5) * step))
+                    .sum::<FloatType>()
+                    / ES_QUANTILES as FloatType)
+            }
+            VarMethod::Historical(moves) => {
+                let losses = self.historical_losses(moves, horizon)?;
+                let tail = &losses[tail_index(losses.len(), confidence)..];
+                Ok(tail.iter().sum::<FloatType>() / tail.len() as FloatType)
+            }
+        }
+    }
+
+    /// Moments of the delta-gamma P&L. Each position uses its own asset or underlying price as the spot.
+    fn delta_gamma_moments(&self, spot_vol: FloatType, horizon: FloatType) -> PnlMoments {
+        let h = horizon / 365.;
+        let s2 = spot_vol * spot_vol * h;
+        // P&L = a x + b x^2 + theta h with x ~ N(0, s2) the log return
+        let (mut a, mut b, mut theta) = (0., 0., 0.);
+        for p in &self.positions {
+            let tick = p.with_iv();
+            let spot = tick.asset_price;
+            a += p.quantity * tick.delta() * spot;
+            b += 0.5 * p.quantity * tick.gamma() * spot * spot;
+            theta += p.quantity * tick.theta() * h;
+        }
+        a += self.underlyings.iter().map(|p| p.value()).sum::<FloatType>();
+        let variance = a * a * s2 + 2. * b * b * s2 * s2;
+        let third = 6. * a * a * b * s2 * s2 + 8. * b * b * b * s2 * s2 * s2;
+        PnlMoments {
+            mean: b * s2 + theta,
+            std_dev: variance.sqrt(),
+            skew: if variance > 0. {
+                third / variance.powf(1.5)
+            } else {
+                0.
+            },
+        }
+    }
+
+    /// Losses under each historical move, in ascending order.
+    fn historical_losses(
+        &self,
+        moves: &TimeSeries<RiskFactorMove>,
+        horizon: FloatType,
+    ) -> Result<Vec<FloatType>> {
+        ensure!(
+            !moves.0.is_empty(),
+            "At least one historical move is required"
+        );
+        let scale = horizon.sqrt();
+        let value = self.value();
+        let mut losses: Vec<FloatType> = moves
+            .0
+            .iter()
+            .map(|m| {
+                let shocked: FloatType = self
+                    .positions
+                    .iter()
+                    .map(|p| {
+                        let spot = p.tick.asset_price * (1. + m.spot_return * scale);
+                        p.value_at(spot, m.iv_change * scale, horizon)
+                    })
+                    .sum();
+                let underlying: FloatType = self
+                    .underlyings
+                    .iter()
+                    .map(|p| p.value_at(p.tick.price * (1. + m.spot_return * scale)))
+                    .sum();
+                value - shocked - underlying
+            })
+            .collect();
+        losses.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Ok(losses)
+    }
+}
+
+struct PnlMoments {
+    mean: FloatType,
+    std_dev: FloatType,
+    skew: FloatType,
+}
+
+impl PnlMoments {
+    /// Loss exceeded with probability 1 - `confidence`, from the Cornish-Fisher quantile of the P&L.
+    fn loss_quantile(&self, confidence: FloatType) -> FloatType {
+        let z = norm_inv_cdf(1. - confidence);
+        let z = z + (z * z - 1.) * self.skew / 6.;
+        -(self.mean + z * self.std_dev)
+    }
+}
+
+/// Index of the VaR in losses sorted in ascending order.
+fn tail_index(n: usize, confidence: FloatType) -> usize {
+    ((confidence * n as FloatType).ceil() as usize).clamp(1, n) - 1
+}
@@ -0,0 +1,144 @@
+//! Scenario engine (risk slide) over spot, volatility and time.
+//!
+//! A ScenarioGrid is the cartesian product of relative spot shocks, absolute IV shocks and elapsed days.
+//! Each grid point re-prices every tick with the asset price multiplied by (1 + spot shock), the IV shifted and the valuation time moved forward.
+//!
+//! # Example
+//! ```ignore
+//! let grid = ScenarioGrid::builder()
+//!     .spot_shocks(vec![-0.1, -0.05, 0., 0.05, 0.1])
+//!     .vol_shocks(vec![-0.05, 0., 0.05])
+//!     .days(vec![0., 7.])
+//!     .build();
+//! let slide = grid.risk_slide(&portfolio);
+//! println!("pnl if spot -10%, vol +5pt, 7 days later: {}", slide.get(0, 2, 1).pnl);
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use crate::portfolio::*;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct ScenarioGrid {
+    /// Relative shocks applied to the asset price (0.05 = +5%).
+    #[builder(default = vec![0.])]
+    pub spot_shocks: Vec<FloatType>,
+    /// Absolute shocks added to the IV (0.01 = +1 vol point).
+    #[builder(default = vec![0.])]
+    pub vol_shocks: Vec<FloatType>,
+    /// Number of days elapsed.
+    #[builder(default = vec![0.])]
+    pub days: Vec<FloatType>,
+}
+
+/// Results of a ScenarioGrid, stored with the spot shock as the outermost and the days as the innermost dimension.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScenarioMatrix<T> {
+    pub spot_shocks: Vec<FloatType>,
+    pub vol_shocks: Vec<FloatType>,
+    pub days: Vec<FloatType>,
+    pub values: Vec<T>,
+}
+
+/// Value and greeks of a portfolio at one grid point.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScenarioValue {
+    pub value: FloatType,
+    /// Value minus the current value of the portfolio.
+    pub pnl: FloatType,
+    pub delta: FloatType,
+    pub gamma: FloatType,
+    pub vega: FloatType,
+    pub theta: FloatType,
+}
+
+impl<T> ScenarioMatrix<T> {
+    /// Returns the result at (spot shock index, vol shock index, days index).
+    pub fn get(&self, spot: usize, vol: usize, day: usize) -> &T {
+        &self.values[(spot * self.vol_shocks.len() + vol) * self.days.len() + day]
+    }
+
+    /// Returns the 2D slice over spot (rows) and vol (columns) shocks for a days index.
+    pub fn spot_vol_slice(&self, day: usize) -> Vec<Vec<&T>> {
+        (0..self.spot_shocks.len())
+            .map(|i| (0..self.vol_shocks.len()).map(|j| self.get(i, j, day)).collect())
+            .collect()
+    }
+}
+
+impl ScenarioGrid {
+    /// Grid points as (spot shock, vol shock, days) in the storage order of ScenarioMatrix.
+    pub fn points(&self) -> Vec<(FloatType, FloatType, FloatType)> {
+        let mut points = Vec::new();
+        for spot in self.spot_shocks.iter() {
+            for vol in self.vol_shocks.iter() {
+                for day in self.days.iter() {
+                    points.push((*spot, *vol, *day));
+                }
+            }
+        }
+        points
+    }
+
+    fn evaluate<T>(&self, f: impl Fn(FloatType, FloatType, FloatType) -> T) -> ScenarioMatrix<T> {
+        ScenarioMatrix {
+            spot_shocks: self.spot_shocks.clone(),
+            vol_shocks: self.vol_shocks.clone(),
+            days: self.days.clone(),
+            values: self.points().into_iter().map(|(s, v, d)| f(s, v, d)).collect(),
+        }
+    }
+
+    /// Shocked copies of the chain at every grid point.
+    /// The ticks are valued by implied volatility, so prices and greeks can be taken with the usual methods.
+    /// Ticks that have expired in a scenario are left with a non-positive tau.
+    pub fn reprice_chain(
+        &self,
+        chain: &OptionChain<OptionTick>,
+    ) -> ScenarioMatrix<OptionChain<OptionTick>> {
+        self.evaluate(|spot_shock, vol_shock, days| {
+            chain.map(|tick| {
+                shocked_tick(tick, tick.asset_price * (1. + spot_shock), vol_shock, days)
+            })
+        })
+    }
+
+    /// Value, PnL and main greeks of the portfolio at every grid point.
+    /// Positions that have expired in a scenario contribute their intrinsic value and no greeks other than delta.
+    /// Underlying positions contribute their value and their quantity to the delta.
+    pub fn risk_slide(&self, portfolio: &Portfolio) -> ScenarioMatrix<ScenarioValue> {
+        let base = portfolio.value();
+        self.evaluate(|spot_shock, vol_shock, days| {
+            let mut result = ScenarioValue::default();
+            for position in portfolio.positions.iter() {
+                let spot = position.tick.asset_price * (1. + spot_shock);
+                let tick = shocked_tick(&position.tick, spot, vol_shock, days);
+                let q = position.quantity;
+                if tick.tau() <= 0. {
+                    let itm = intrinsic_value(&tick, spot) > 0.;
+                    result.value += q * intrinsic_value(&tick, spot);
+                    result.delta += match (&tick.option_type, itm) {
+                        (OptionType::Call, true) => q,
+                        (OptionType::Put, true) => -q,
+                        _ => 0.,
+                    };
+                    continue;
+                }
+                result.value += q * tick.get_theoretical_price().get_value();
+                result.delta += q * tick.delta();
+                result.gamma += q * tick.gamma();
+                result.vega += q * tick.vega();
+                result.theta += q * tick.theta();
+            }
+            for position in portfolio.underlyings.iter() {
+                result.value += position.value_at(position.tick.price * (1. + spot_shock));
+                result.delta += position.quantity;
+            }
+            result.pnl = result.value - base;
+            result
+        })
+    }
+}
@@ -0,0 +1,157 @@
+//! Cross-sectional screening of an OptionUniverse.
+//!
+//! A Criterion computes a metric for each underlying and keeps the underlying when the predicate on that metric holds.
+//! Underlyings passing every criterion are returned ranked by the metric of the first criterion (highest first).
+//!
+//! # Example
+//! ```ignore
+//! let results = universe.screen(&[
+//!     Criterion::term_inversion(0.02),
+//!     Criterion::parity_violation(0.005),
+//! ]);
+//! for r in results {
+//!     println!("{} {:?}", r.symbol, r.metrics);
+//! }
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+type Metric = Box<dyn Fn(&str, &OptionBoard<OptionTick>) -> Result<FloatType>>;
+type Predicate = Box<dyn Fn(FloatType) -> bool>;
+
+pub struct Criterion {
+    pub name: String,
+    metric: Metric,
+    predicate: Predicate,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenResult {
+    pub symbol: String,
+    /// (criterion name, metric value) in the order of the criteria
+    pub metrics: Vec<(String, FloatType)>,
+}
+
+impl Criterion {
+    /// Creates a criterion from a metric, which receives the symbol and its OptionBoard, and a predicate on the metric.
+    pub fn new(
+        name: &str,
+        metric: impl Fn(&str, &OptionBoard<OptionTick>) -> Result<FloatType> + 'static,
+        predicate: impl Fn(FloatType) -> bool + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            metric: Box::new(metric),
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// IV rank (0-100) of the front-month ATM IV within the history supplied per symbol.
+    /// Keeps underlyings whose IV rank is at least `min_rank`.
+    pub fn iv_rank(history: BTreeMap<String, Vec<FloatType>>, min_rank: FloatType) -> Self {
+        Self::new(
+            "iv_rank",
+            move |symbol, board| {
+                let iv = board.try_get_front_month()?.try_atm()?.try_iv()?;
+                let past = history.get(symbol).map(|h| h.as_slice()).unwrap_or(&[]);
+                let min = past.iter().copied().fold(iv, FloatType::min);
+                let max = past.iter().copied().fold(iv, FloatType::max);
+                ensure!(max > min, "Not enough IV history for {}", symbol);
+                Ok((iv - min) / (max - min) * 100.)
+            },
+            move |rank| rank >= min_rank,
+        )
+    }
+
+    /// Front-month ATM IV minus second-month ATM IV.
+    /// Keeps underlyings whose term structure is inverted by at least `min_spread`.
+    pub fn term_inversion(min_spread: FloatType) -> Self {
+        Self::new(
+            "term_inversion",
+            |_, board| {
+                let atm_iv = |index| -> Result<FloatType> { Ok(board.try_get(index)?.try_atm()?.try_iv()?) };
+                Ok(atm_iv(0)? - atm_iv(1)?)
+            },
+            move |spread| spread >= min_spread,
+        )
+    }
+
+    /// Largest put-call parity violation on the board, relative to the asset price.
+    /// Keeps underlyings whose violation is at least `min_violation`.
+    pub fn parity_violation(min_violation: FloatType) -> Self {
+        Self::new(
+            "parity_violation",
+            |_, board| {
+                board
+                    .0
+                    .iter()
+                    .filter_map(max_parity_violation)
+                    .reduce(FloatType::max)
+                    .ok_or_else(|| anyhow!("No call/put pairs on the board"))
+            },
+            move |violation| violation >= min_violation,
+        )
+    }
+}
+
+/// Returns max |C - P - (S e^{-q tau} - K e^{-r tau})| / S over strikes with both a call and a put.
+fn max_parity_violation(chain: &OptionChain<OptionTick>) -> Option<FloatType> {
+    let mut pairs: BTreeMap<DecimalType, (Option<OptionTick>, Option<OptionTick>)> = BTreeMap::new();
+    for tick in chain.0.iter() {
+        let entry = pairs.entry(tick.strike).or_insert((None, None));
+        let priced = tick.get_theoretical_price();
+        match tick.option_type {
+            OptionType::Call => entry.0 = Some(priced),
+            OptionType::Put => entry.1 = Some(priced),
+        }
+    }
+
+    pairs
+        .iter()
+        .filter_map(|(strike, pair)| match pair {
+            (Some(call), Some(put)) => {
+                let s = call.asset_price;
+                let k = strike.to_f64().unwrap();
+                let parity = s * call.carry_discount_factor() - k * call.discount_factor();
+                Some((call.get_value() - put.get_value() - parity).abs() / s)
+            }
+            _ => None,
+        })
+        .filter(|v| v.is_finite())
+        .reduce(FloatType::max)
+}
+
+impl OptionUniverse<OptionTick> {
+    /// Evaluates all criteria on every underlying and returns those that pass, ranked by the first criterion in descending order.
+    /// An underlying for which a metric cannot be calculated is excluded.
+    pub fn screen(&self, criteria: &[Criterion]) -> Vec<ScreenResult> {
+        let mut results: Vec<ScreenResult> = self
+            .0
+            .iter()
+            .filter_map(|(symbol, board)| {
+                let metrics = criteria
+                    .iter()
+                    .map(|c| {
+                        let value = (c.metric)(symbol, board).ok()?;
+                        (value.is_finite() && (c.predicate)(value)).then(|| (c.name.clone(), value))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ScreenResult {
+                    symbol: symbol.clone(),
+                    metrics,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            let key = |r: &ScreenResult| r.metrics.first().map(|m| m.1).unwrap_or(0.);
+            key(b).partial_cmp(&key(a)).unwrap()
+        });
+        results
+    }
+}
@@ -0,0 +1,89 @@
+//! Intraday seasonality of implied volatility.
+//!
+//! IV typically follows a time-of-day pattern (e.g. elevated at the open, drifting lower into the close), so raw intraday IV changes mix genuine repricing with the daily cycle.
+//! IntradaySeasonality estimates the average deviation of IV from its daily mean for each time-of-day bucket and removes it from new observations.
+//!
+//! # Example
+//! ```ignore
+//! let seasonality = IntradaySeasonality::estimate(&history, 30)?;
+//! let adjusted = seasonality.deseasonalize(&today);
+//! ```
+
+use crate::models::*;
+use anyhow::{ensure, Result};
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IntradaySeasonality {
+    /// Width of a time-of-day bucket in minutes. Buckets are measured in UTC.
+    pub bucket_minutes: u32,
+    /// Average additive deviation from the daily mean per bucket. None if the bucket was never observed.
+    pub curve: Vec<Option<FloatType>>,
+}
+
+impl IntradaySeasonality {
+    /// Estimates the time-of-day curve from a history of (timestamp, value) observations spanning several days.
+    /// For each day, the daily mean is subtracted from every observation, and the deviations are averaged per bucket across days.
+    pub fn estimate(
+        history: &TimeSeries<(DateTime<Utc>, FloatType)>,
+        bucket_minutes: u32,
+    ) -> Result<Self> {
+        ensure!(bucket_minutes > 0 && 1440 % bucket_minutes == 0, "bucket_minutes must divide a day");
+        let n_buckets = (1440 / bucket_minutes) as usize;
+
+        let mut days: BTreeMap<NaiveDate, Vec<(usize, FloatType)>> = BTreeMap::new();
+        for (timestamp, value) in history.0.iter().filter(|(_, v)| v.is_finite()) {
+            days.entry(timestamp.date_naive())
+                .or_default()
+                .push((bucket_of(timestamp, bucket_minutes), *value));
+        }
+        ensure!(days.len() >= 2, "At least two days of history are required");
+
+        let mut sum = vec![0.; n_buckets];
+        let mut count = vec![0usize; n_buckets];
+        for observations in days.values() {
+            let daily_mean = observations.iter().map(|(_, v)| v).sum::<FloatType>()
+                / observations.len() as FloatType;
+            for (bucket, value) in observations {
+                sum[*bucket] += value - daily_mean;
+                count[*bucket] += 1;
+            }
+        }
+
+        Ok(Self {
+            bucket_minutes,
+            curve: sum
+                .iter()
+                .zip(count.iter())
+                .map(|(s, c)| (*c > 0).then(|| s / *c as FloatType))
+                .collect(),
+        })
+    }
+
+    /// Returns the seasonal deviation for the time of day of `timestamp` (0 if the bucket was never observed).
+    pub fn factor(&self, timestamp: &DateTime<Utc>) -> FloatType {
+        self.curve[bucket_of(timestamp, self.bucket_minutes)].unwrap_or(0.)
+    }
+
+    /// Removes the seasonal component from the observations.
+    pub fn deseasonalize(
+        &self,
+        series: &TimeSeries<(DateTime<Utc>, FloatType)>,
+    ) -> TimeSeries<(DateTime<Utc>, FloatType)> {
+        series.map(|(t, v)| (*t, v - self.factor(t)))
+    }
+
+    /// Adds the seasonal component back, e.g. to turn a deseasonalized forecast into an expected raw IV.
+    pub fn reseasonalize(
+        &self,
+        series: &TimeSeries<(DateTime<Utc>, FloatType)>,
+    ) -> TimeSeries<(DateTime<Utc>, FloatType)> {
+        series.map(|(t, v)| (*t, v + self.factor(t)))
+    }
+}
+
+fn bucket_of(timestamp: &DateTime<Utc>, bucket_minutes: u32) -> usize {
+    ((timestamp.hour() * 60 + timestamp.minute()) / bucket_minutes) as usize
+}
@@ -0,0 +1,84 @@
+//! Time series of smile metrics per tenor bucket, built from a TimeSeries of OptionBoards.
+//!
+//! For every snapshot and every target tenor, the expiry whose days to expiry (DTE) is closest to the tenor is selected.
+//! Expiries with fewer than `roll_days` days left are skipped, so each bucket rolls to the next expiry before the noisy last days of a contract.
+//! When no expiry is available for a bucket, the value is NaN so that all series stay aligned with the input.
+//!
+//! # Example
+//! ```ignore
+//! let buckets = TenorBuckets::builder().tenors_days(vec![7., 30., 90.]).roll_days(2.).build();
+//! for (tenor, rr_ts) in boards_ts.skew_term_structure(&buckets) {
+//!     println!("{}d: {:?}", tenor, rr_ts);
+//! }
+//! ```
+
+use crate::models::*;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(Clone, Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TenorBuckets {
+    /// Target tenors in days
+    pub tenors_days: Vec<FloatType>,
+    /// Expiries with fewer days left than this are not used
+    #[builder(default = 0.)]
+    pub roll_days: FloatType,
+}
+
+impl TenorBuckets {
+    /// Selects the chain whose DTE is closest to `tenor_days`, ignoring chains that are about to be rolled.
+    pub fn select<'a>(
+        &self,
+        board: &'a OptionBoard<OptionTick>,
+        tenor_days: FloatType,
+    ) -> Option<&'a OptionChain<OptionTick>> {
+        board
+            .0
+            .iter()
+            .filter(|chain| !chain.0.is_empty())
+            .map(|chain| (chain, chain.0[0].tau() * 365.))
+            .filter(|(_, dte)| *dte >= self.roll_days && *dte > 0.)
+            .min_by(|a, b| {
+                (a.1 - tenor_days)
+                    .abs()
+                    .partial_cmp(&(b.1 - tenor_days).abs())
+                    .unwrap()
+            })
+            .map(|(chain, _)| chain)
+    }
+}
+
+impl TimeSeries<OptionBoard<OptionTick>> {
+    /// Returns (tenor, TimeSeries of metric) for every tenor bucket.
+    /// The metric is evaluated on the chain selected for the bucket at each snapshot.
+    pub fn tenor_series<F>(
+        &self,
+        buckets: &TenorBuckets,
+        metric: F,
+    ) -> Vec<(FloatType, TimeSeries<FloatType>)>
+    where
+        F: Fn(&OptionChain<OptionTick>) -> FloatType,
+    {
+        buckets
+            .tenors_days
+            .iter()
+            .map(|tenor| {
+                let ts = self.map(|board| {
+                    buckets
+                        .select(board, *tenor)
+                        .map(&metric)
+                        .unwrap_or(FloatType::NAN)
+                });
+                (*tenor, ts)
+            })
+            .collect()
+    }
+
+    /// Returns the 25 delta risk reversal per tenor bucket.
+    pub fn skew_term_structure(
+        &self,
+        buckets: &TenorBuckets,
+    ) -> Vec<(FloatType, TimeSeries<FloatType>)> {
+        self.tenor_series(buckets, |chain| chain.risk_reversal_25d().unwrap_or(FloatType::NAN))
+    }
+}
@@ -0,0 +1,98 @@
+//! Smile dynamics and the smile-adjusted (shadow) delta.
+//!
+//! The Black-Scholes delta holds the IV of the option fixed when the underlying moves. How the smile actually moves with the
+//! underlying is an assumption:
+//! * StickyStrike: the IV of each strike stays the same, so the Black-Scholes delta is the hedge ratio
+//! * StickyMoneyness: the smile is a function of K / F and moves with the forward
+//! * StickyDelta: the smile is a function of delta
+//!
+//! When the smile moves with the underlying, the IV of a fixed strike changes by dσ/dS = -(K / S) dσ/dK, and the hedge ratio
+//! picks up a vega term: `delta + vega * dσ/dS`. Since the Black-Scholes delta of a given maturity is itself a function of
+//! K / F and σ, sticky delta and sticky moneyness lead to the same first-order correction.
+//! With a negative skew (dσ/dK < 0), the smile-adjusted delta of both calls and puts is lower than the Black-Scholes delta.
+//!
+//! # Example
+//! ```ignore
+//! let delta = chain.smile_adjusted_delta(&tick, SmileDynamics::StickyDelta)?;
+//! let delta = tick.smile_adjusted_delta(SmileDynamics::StickyMoneyness, -0.002);
+//! ```
+
+use crate::greeks::EuropeanGreeks;
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmileDynamics {
+    #[default]
+    StickyStrike,
+    StickyDelta,
+    StickyMoneyness,
+}
+
+impl OptionTick {
+    /// Change of the IV of this strike per unit move of the underlying, given the slope of the smile dσ/dK at the strike.
+    pub fn smile_vol_sensitivity(
+        &self,
+        dynamics: SmileDynamics,
+        dvol_dstrike: FloatType,
+    ) -> FloatType {
+        match dynamics {
+            SmileDynamics::StickyStrike => 0.,
+            SmileDynamics::StickyDelta | SmileDynamics::StickyMoneyness => {
+                -self.strike_f64() / self.asset_price * dvol_dstrike
+            }
+        }
+    }
+
+    /// Delta including the change of the IV implied by the smile dynamics: delta + vega * dσ/dS.
+    /// `dvol_dstrike` is the slope of the smile at the strike, in IV per unit of strike.
+    pub fn smile_adjusted_delta(
+        &self,
+        dynamics: SmileDynamics,
+        dvol_dstrike: FloatType,
+    ) -> FloatType {
+        self.delta() + self.vega() * self.smile_vol_sensitivity(dynamics, dvol_dstrike)
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Slope dσ/dK of the smile of the OTM options at the strike, from the neighbouring listed strikes:
+    /// a central difference at a listed strike, and the slope of the segment between two listed strikes otherwise.
+    pub fn smile_slope(&self, strike: FloatType) -> Result<FloatType> {
+        let (strikes, ivs) = self.smile_curve_with(SmileSource::OtmOnly);
+        ensure!(
+            strikes.len() >= 2,
+            "At least two strikes with a valid IV are required"
+        );
+        ensure!(
+            strike >= strikes[0] && strike <= strikes[strikes.len() - 1],
+            "Strike {} is outside the range of listed strikes",
+            strike
+        );
+        let slope = |i: usize, j: usize| (ivs[j] - ivs[i]) / (strikes[j] - strikes[i]);
+        let upper = strikes
+            .iter()
+            .position(|k| *k >= strike)
+            .ok_or_else(|| anyhow!("Strike {} is outside the range of listed strikes", strike))?;
+        let last = strikes.len() - 1;
+        Ok(if strikes[upper] == strike {
+            slope(upper.saturating_sub(1), (upper + 1).min(last))
+        } else {
+            slope(upper - 1, upper)
+        })
+    }
+
+    /// Smile-adjusted delta of the tick, with the slope of the smile of the chain at its strike.
+    pub fn smile_adjusted_delta(
+        &self,
+        tick: &OptionTick,
+        dynamics: SmileDynamics,
+    ) -> Result<FloatType> {
+        let slope = match dynamics {
+            SmileDynamics::StickyStrike => 0.,
+            _ => self.smile_slope(tick.strike_f64())?,
+        };
+        Ok(tick.smile_adjusted_delta(dynamics, slope))
+    }
+}
@@ -0,0 +1,206 @@
+//! Smoothing of the raw IV smile.
+//!
+//! smile_curve() returns the IV of every tick, so quotes of different quality at neighbouring strikes make the curve jagged,
+//! and quantities that depend on its derivatives (GEX, implied densities) become noisy. smile_smoothed() fits a smooth curve instead:
+//! * SmoothingSpline: cubic smoothing spline minimizing `sum w_i (iv_i - f(x_i))^2 + lambda * int f''(x)^2 dx` (Reinsch algorithm)
+//! * Kernel: Nadaraya-Watson regression with a Gaussian kernel
+//! * Kalman: local level Kalman smoother run across the strikes in ascending order, interpolated by a natural cubic spline
+//!
+//! The fit is done in log-strike x = ln(K), so lambda and the bandwidth do not depend on the price level of the underlying
+//! (a bandwidth of 0.05 is about 5% of the strike). IVs quoted at the same strike (e.g. a call and a put) are averaged first,
+//! with a weight equal to the number of quotes. smile_smoothed() fits every quote; smile_smoothed_with() fits the quotes of a
+//! SmileSource, e.g. the OTM side only.
+//!
+//! # Example
+//! ```ignore
+//! let smile = chain.smile_smoothed(&SmileSmoothing::SmoothingSpline { lambda: 1e-4 })?;
+//! println!("fitted: {:?}", smile.fitted_ivs);
+//! println!("iv at 105: {}", smile.iv(105.));
+//! let otm_smile = chain.smile_smoothed_with(&SmileSmoothing::Kernel { bandwidth: 0.05 }, SmileSource::OtmOnly)?;
+//! ```
+
+use crate::kalman::KalmanParams;
+use crate::math::solve_dense;
+use crate::math::CubicSpline;
+use crate::models::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SmileSmoothing {
+    /// Roughness penalty of the spline; 0 interpolates the raw IVs
+    SmoothingSpline { lambda: FloatType },
+    /// Standard deviation of the Gaussian kernel, in log-strike
+    Kernel { bandwidth: FloatType },
+    Kalman(KalmanParams),
+}
+
+#[derive(Clone, Debug)]
+enum SmileFunction {
+    Spline(CubicSpline),
+    Kernel {
+        xs: Vec<FloatType>,
+        ys: Vec<FloatType>,
+        weights: Vec<FloatType>,
+        bandwidth: FloatType,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SmoothedSmile {
+    /// Distinct strikes of the chain in ascending order
+    pub strikes: Vec<FloatType>,
+    /// IV quoted at each strike, averaged over the quotes at that strike
+    pub raw_ivs: Vec<FloatType>,
+    /// Smoothed IV at each strike
+    pub fitted_ivs: Vec<FloatType>,
+    function: SmileFunction,
+}
+
+impl SmoothedSmile {
+    /// Smoothed IV at any strike. The spline based fits are extrapolated linearly in log-strike.
+    pub fn iv(&self, strike: FloatType) -> FloatType {
+        let x = strike.ln();
+        match &self.function {
+            SmileFunction::Spline(spline) => spline.eval(x),
+            SmileFunction::Kernel {
+                xs,
+                ys,
+                weights,
+                bandwidth,
+            } => kernel_regression(xs, ys, weights, *bandwidth, x),
+        }
+    }
+
+    /// (strike, fitted IV) pairs
+    pub fn fitted(&self) -> Vec<(FloatType, FloatType)> {
+        self.strikes.iter().cloned().zip(self.fitted_ivs.iter().cloned()).collect()
+    }
+}
+
+fn kernel_regression(
+    xs: &[FloatType],
+    ys: &[FloatType],
+    weights: &[FloatType],
+    bandwidth: FloatType,
+    x: FloatType,
+) -> FloatType {
+    let (mut num, mut den) = (0., 0.);
+    for i in 0..xs.len() {
+        let u = (x - xs[i]) / bandwidth;
+        let k = weights[i] * (-0.5 * u * u).exp();
+        num += k * ys[i];
+        den += k;
+    }
+    num / den
+}
+
+/// Fitted values of the cubic smoothing spline at the knots (Green & Silverman, section 2.3.3).
+/// Solves (R + lambda Q^T W^{-1} Q) gamma = Q^T y, then g = y - lambda W^{-1} Q gamma.
+fn smoothing_spline(xs: &[FloatType], ys: &[FloatType], weights: &[FloatType], lambda: FloatType) -> Vec<FloatType> {
+    let n = xs.len();
+    if n < 3 || lambda <= 0. {
+        return ys.to_vec();
+    }
+    let h: Vec<FloatType> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let m = n - 2;
+    // Column j of Q has its non-zero entries in rows j, j+1 and j+2
+    let q = |j: usize| [1. / h[j], -1. / h[j] - 1. / h[j + 1], 1. / h[j + 1]];
+
+    // The system matrix is symmetric and pentadiagonal; it is small, so it is solved densely
+    let mut a = vec![vec![0.; m]; m];
+    let mut b = vec![0.; m];
+    for j in 0..m {
+        a[j][j] += (h[j] + h[j + 1]) / 3.;
+        if j + 1 < m {
+            a[j][j + 1] += h[j + 1] / 6.;
+            a[j + 1][j] += h[j + 1] / 6.;
+        }
+        let qj = q(j);
+        b[j] = (0..3).map(|r| qj[r] * ys[j + r]).sum();
+        for k in j..(j + 3).min(m) {
+            let qk = q(k);
+            // Rows shared by columns j and k of Q
+            let value: FloatType = (k..j + 3)
+                .map(|row| qj[row - j] * qk[row - k] / weights[row])
+                .sum();
+            a[j][k] += lambda * value;
+            if k != j {
+                a[k][j] += lambda * value;
+            }
+        }
+    }
+    let gamma = solve_dense(a, b);
+
+    let mut fitted = ys.to_vec();
+    for (j, g) in gamma.iter().enumerate() {
+        let qj = q(j);
+        for r in 0..3 {
+            fitted[j + r] -= lambda * qj[r] * g / weights[j + r];
+        }
+    }
+    fitted
+}
+
+impl OptionChain<OptionTick> {
+    pub fn smile_smoothed(&self, method: &SmileSmoothing) -> Result<SmoothedSmile> {
+        self.smile_smoothed_with(method, SmileSource::Blend)
+    }
+
+    /// Smoothed smile of the quotes of the source.
+    pub fn smile_smoothed_with(
+        &self,
+        method: &SmileSmoothing,
+        source: SmileSource,
+    ) -> Result<SmoothedSmile> {
+        let (raw_strikes, raw_ivs) = self.smile_ticks(source).smile_curve();
+        // smile_curve() is sorted by strike, so quotes at the same strike are adjacent
+        let (mut strikes, mut ivs, mut weights): (Vec<FloatType>, Vec<FloatType>, Vec<FloatType>) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for (k, iv) in raw_strikes.into_iter().zip(raw_ivs) {
+            if strikes.last() == Some(&k) {
+                let i = strikes.len() - 1;
+                ivs[i] = (ivs[i] * weights[i] + iv) / (weights[i] + 1.);
+                weights[i] += 1.;
+            } else {
+                strikes.push(k);
+                ivs.push(iv);
+                weights.push(1.);
+            }
+        }
+        ensure!(strikes.len() >= 2, "At least two strikes with a valid IV are required");
+        ensure!(strikes[0] > 0., "Strikes must be positive");
+        let xs: Vec<FloatType> = strikes.iter().map(|k| k.ln()).collect();
+
+        let (fitted_ivs, function) = match method {
+            SmileSmoothing::SmoothingSpline { lambda } => {
+                let fitted = smoothing_spline(&xs, &ivs, &weights, *lambda);
+                (fitted.clone(), SmileFunction::Spline(CubicSpline::new(xs, fitted)?))
+            }
+            SmileSmoothing::Kernel { bandwidth } => {
+                ensure!(*bandwidth > 0., "The bandwidth must be positive");
+                let fitted = xs
+                    .iter()
+                    .map(|x| kernel_regression(&xs, &ivs, &weights, *bandwidth, *x))
+                    .collect();
+                let function = SmileFunction::Kernel {
+                    xs,
+                    ys: ivs.clone(),
+                    weights,
+                    bandwidth: *bandwidth,
+                };
+                (fitted, function)
+            }
+            SmileSmoothing::Kalman(params) => {
+                let fitted = TimeSeries(ivs.clone()).kalman_smooth(params).0;
+                (fitted.clone(), SmileFunction::Spline(CubicSpline::new(xs, fitted)?))
+            }
+        };
+        Ok(SmoothedSmile {
+            strikes,
+            raw_ivs: ivs,
+            fitted_ivs,
+            function,
+        })
+    }
+}
@@ -0,0 +1,225 @@
+//! Two-asset spread options and basket options.
+//!
+//! A spread option pays max(phi (S1(T) - S2(T) - K), 0). The first asset is the underlying of the tick (asset_price, IV and carry),
+//! the second is an AssetSpec, and the two are correlated with `correlation`. Two approximations are available:
+//! * Kirk: the second forward plus the strike is treated as lognormal, giving a Black-76 formula on F1 / (F2 + K)
+//! * Bjerksund-Stensland (2011): a lower bound that is usually closer to the exact value than Kirk, especially far from K = 0
+//!
+//! A basket option pays max(phi (sum w_i S_i(T) - K), 0) on several correlated assets and is priced by Monte Carlo
+//! with correlated terminal values (Cholesky factor of the correlation matrix). For a basket, only the strike, maturity, option type
+//! and rates of the tick are used.
+//!
+//! # Example
+//! ```ignore
+//! let gasoline = AssetSpec::new(2.5, 0.35, 0.);
+//! let spread = SpreadOption::new(crude_tick, gasoline, 0.8, SpreadMethod::BjerksundStensland);
+//! println!("{} delta on the first leg: {}", spread.price_closed_form()?, spread.numerical_delta());
+//! let basket = BasketOption::new(tick, assets, weights, correlation)?;
+//! let result = basket.monte_carlo(&McConfig::builder().n_paths(100000).build())?;
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::math::norm_cdf;
+use crate::models::*;
+use crate::monte_carlo::{standard_normals, McConfig, McResult};
+use anyhow::{ensure, Result};
+use probability::prelude::*;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Spot, volatility and continuous dividend yield of an asset other than the underlying of the tick
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssetSpec {
+    pub spot: FloatType,
+    pub volatility: FloatType,
+    pub dividend_yield: FloatType,
+}
+
+impl AssetSpec {
+    pub fn new(spot: FloatType, volatility: FloatType, dividend_yield: FloatType) -> Self {
+        Self {
+            spot,
+            volatility,
+            dividend_yield,
+        }
+    }
+
+    /// The underlying of the tick, with its escrowed spot, IV and carry yield.
+    pub fn from_tick(tick: &OptionTick) -> Self {
+        Self::new(tick.escrowed_spot(), tick.get_implied_volatility().get_value(), tick.carry_yield())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SpreadMethod {
+    Kirk,
+    BjerksundStensland,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpreadOption {
+    /// Contract terms and first asset
+    pub tick: OptionTick,
+    pub second: AssetSpec,
+    pub correlation: FloatType,
+    pub method: SpreadMethod,
+}
+
+impl SpreadOption {
+    pub fn new(tick: OptionTick, second: AssetSpec, correlation: FloatType, method: SpreadMethod) -> Self {
+        Self {
+            tick,
+            second,
+            correlation,
+            method,
+        }
+    }
+
+    pub fn price_closed_form(&self) -> Result<FloatType> {
+        let first = AssetSpec::from_tick(&self.tick);
+        let t = self.tick.tau();
+        let r = self.tick.rate();
+        let k = self.tick.strike.to_f64().unwrap();
+        ensure!(t > 0., "The option has expired");
+        ensure!(
+            first.volatility > 0. && self.second.volatility > 0.,
+            "Both volatilities must be positive"
+        );
+        ensure!((-1. ..=1.).contains(&self.correlation), "The correlation must be in [-1, 1]");
+        let f1 = first.spot * ((r - first.dividend_yield) * t).exp();
+        let f2 = self.second.spot * ((r - self.second.dividend_yield) * t).exp();
+        ensure!(f2 + k > 0., "F2 + K must be positive");
+        let (s1, s2, rho) = (first.volatility, self.second.volatility, self.correlation);
+        let discount = (-r * t).exp();
+
+        let a = f2 + k;
+        let b = f2 / a;
+        let sigma = (s1 * s1 - 2. * b * rho * s1 * s2 + b * b * s2 * s2).sqrt();
+        let vol = sigma * t.sqrt();
+        let m = (f1 / a).ln();
+        let call = match self.method {
+            SpreadMethod::Kirk => {
+                let d1 = (m + 0.5 * vol * vol) / vol;
+                discount * (f1 * norm_cdf(d1) - a * norm_cdf(d1 - vol))
+            }
+            SpreadMethod::BjerksundStensland => {
+                let d1 = (m + (0.5 * s1 * s1 - b * rho * s1 * s2 + 0.5 * b * b * s2 * s2) * t) / vol;
+                let d2 = (m + (-0.5 * s1 * s1 + rho * s1 * s2 + (0.5 * b * b - b) * s2 * s2) * t) / vol;
+                let d3 = (m + (-0.5 * s1 * s1 + 0.5 * b * b * s2 * s2) * t) / vol;
+                discount * (f1 * norm_cdf(d1) - f2 * norm_cdf(d2) - k * norm_cdf(d3))
+            }
+        };
+        Ok(match self.tick.option_type {
+            OptionType::Call => call,
+            // Put-call parity of the spread
+            OptionType::Put => call - discount * (f1 - f2 - k),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BasketOption {
+    /// Contract terms; the asset_price and IV of the tick are not used
+    pub tick: OptionTick,
+    pub assets: Vec<AssetSpec>,
+    pub weights: Vec<FloatType>,
+    /// Correlation matrix of the assets
+    pub correlation: Vec<Vec<FloatType>>,
+    /// Lower triangular Cholesky factor of the correlation matrix
+    cholesky: Vec<Vec<FloatType>>,
+}
+
+fn cholesky(matrix: &[Vec<FloatType>]) -> Result<Vec<Vec<FloatType>>> {
+    let n = matrix.len();
+    let mut l = vec![vec![0.; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: FloatType = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] - sum;
+                ensure!(diagonal > 0., "The correlation matrix is not positive definite");
+                l[i][j] = diagonal.sqrt();
+            } else {
+                l[i][j] = (matrix[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Ok(l)
+}
+
+impl BasketOption {
+    pub fn new(
+        tick: OptionTick,
+        assets: Vec<AssetSpec>,
+        weights: Vec<FloatType>,
+        correlation: Vec<Vec<FloatType>>,
+    ) -> Result<Self> {
+        let n = assets.len();
+        ensure!(n > 0, "The basket is empty");
+        ensure!(weights.len() == n, "There must be one weight per asset");
+        ensure!(
+            correlation.len() == n && correlation.iter().all(|row| row.len() == n),
+            "The correlation matrix must be n x n"
+        );
+        ensure!(
+            (0..n).all(|i| (0..n).all(|j| (correlation[i][j] - correlation[j][i]).abs() < 1e-12)),
+            "The correlation matrix must be symmetric"
+        );
+        let cholesky = cholesky(&correlation)?;
+        Ok(Self {
+            tick,
+            assets,
+            weights,
+            correlation,
+            cholesky,
+        })
+    }
+
+    /// Monte Carlo price from the correlated terminal values of the assets. n_steps of the config is not used.
+    pub fn monte_carlo(&self, config: &McConfig) -> Result<McResult> {
+        ensure!(config.n_paths >= 2, "n_paths must be at least 2");
+        let t = self.tick.tau();
+        ensure!(t > 0., "The option has expired");
+        let r = self.tick.rate();
+        let k = self.tick.strike.to_f64().unwrap();
+        let n = self.assets.len();
+        let payoff = |basket: FloatType| match self.tick.option_type {
+            OptionType::Call => (basket - k).max(0.),
+            OptionType::Put => (k - basket).max(0.),
+        };
+        let basket = |normals: &[FloatType]| -> FloatType {
+            (0..n)
+                .map(|i| {
+                    let asset = &self.assets[i];
+                    let z: FloatType = (0..=i).map(|j| self.cholesky[i][j] * normals[j]).sum();
+                    let drift = (r - asset.dividend_yield - 0.5 * asset.volatility * asset.volatility) * t;
+                    self.weights[i] * asset.spot * (drift + asset.volatility * t.sqrt() * z).exp()
+                })
+                .sum()
+        };
+
+        let mut source = source::default(config.seed);
+        let n_samples = if config.antithetic { config.n_paths / 2 } else { config.n_paths };
+        let samples: Vec<FloatType> = (0..n_samples)
+            .map(|_| {
+                let normals = standard_normals(&mut source, n);
+                let value = payoff(basket(&normals));
+                if config.antithetic {
+                    let mirrored: Vec<FloatType> = normals.iter().map(|z| -z).collect();
+                    0.5 * (value + payoff(basket(&mirrored)))
+                } else {
+                    value
+                }
+            })
+            .collect();
+
+        let discount = (-r * t).exp();
+        let count = samples.len() as FloatType;
+        let mean = samples.iter().sum::<FloatType>() / count;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<FloatType>() / (count - 1.);
+        Ok(McResult {
+            price: discount * mean,
+            std_error: discount * (variance / count).sqrt(),
+        })
+    }
+}
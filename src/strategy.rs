@@ -0,0 +1,103 @@
+//! Constructors of standard option strategies from the ticks listed on an OptionChain.
+//!
+//! Each constructor selects the ticks with the requested strikes and returns a Portfolio of one unit of the strategy, long the structure
+//! except for short_iron_condor, which is named after the side it is usually traded.
+//! Use Portfolio::scaled() to change the size or to sell the strategy.
+//!
+//! # Example
+//! ```ignore
+//! let short_straddle = chain.straddle(dec!(27000))?.scaled(-10.);
+//! println!("vega: {}, payoff at 26000: {}", short_straddle.vega(), short_straddle.payoff_at_expiry(26000.));
+//! ```
+
+use crate::models::*;
+use crate::portfolio::*;
+use anyhow::{anyhow, Result};
+
+impl OptionChain<OptionTick> {
+    /// Returns the tick listed with the given strike and option type.
+    pub fn find(&self, strike: DecimalType, option_type: OptionType) -> Result<OptionTick> {
+        self.0
+            .iter()
+            .find(|t| t.strike == strike && t.option_type == option_type)
+            .cloned()
+            .ok_or_else(|| anyhow!("No {:?} with strike {} in the OptionChain", option_type, strike))
+    }
+
+    fn legs(&self, legs: &[(DecimalType, OptionType, FloatType)]) -> Result<Portfolio> {
+        let mut portfolio = Portfolio::new();
+        for (strike, option_type, quantity) in legs.iter() {
+            portfolio.add(self.find(*strike, option_type.clone())?, *quantity);
+        }
+        Ok(portfolio)
+    }
+
+    /// Long call and long put at the same strike.
+    pub fn straddle(&self, strike: DecimalType) -> Result<Portfolio> {
+        self.legs(&[(strike, OptionType::Call, 1.), (strike, OptionType::Put, 1.)])
+    }
+
+    /// Long put at put_strike and long call at call_strike (put_strike < call_strike).
+    pub fn strangle(&self, put_strike: DecimalType, call_strike: DecimalType) -> Result<Portfolio> {
+        self.legs(&[(put_strike, OptionType::Put, 1.), (call_strike, OptionType::Call, 1.)])
+    }
+
+    /// Long call at long_strike and short call at short_strike.
+    /// long_strike < short_strike gives a bull call spread, the opposite gives a bear call spread.
+    pub fn vertical_call_spread(
+        &self,
+        long_strike: DecimalType,
+        short_strike: DecimalType,
+    ) -> Result<Portfolio> {
+        self.legs(&[
+            (long_strike, OptionType::Call, 1.),
+            (short_strike, OptionType::Call, -1.),
+        ])
+    }
+
+    /// Long put at long_strike and short put at short_strike.
+    /// long_strike > short_strike gives a bear put spread, the opposite gives a bull put spread.
+    pub fn vertical_put_spread(
+        &self,
+        long_strike: DecimalType,
+        short_strike: DecimalType,
+    ) -> Result<Portfolio> {
+        self.legs(&[
+            (long_strike, OptionType::Put, 1.),
+            (short_strike, OptionType::Put, -1.),
+        ])
+    }
+
+    /// Long butterfly: long one option at lower and upper, short two at middle.
+    pub fn butterfly(
+        &self,
+        lower: DecimalType,
+        middle: DecimalType,
+        upper: DecimalType,
+        option_type: OptionType,
+    ) -> Result<Portfolio> {
+        self.legs(&[
+            (lower, option_type.clone(), 1.),
+            (middle, option_type.clone(), -2.),
+            (upper, option_type, 1.),
+        ])
+    }
+
+    /// Short iron condor, the premium-collecting structure:
+    /// long put at long_put, short put at short_put, short call at short_call and long call at long_call,
+    /// with long_put < short_put < short_call < long_call.
+    pub fn short_iron_condor(
+        &self,
+        long_put: DecimalType,
+        short_put: DecimalType,
+        short_call: DecimalType,
+        long_call: DecimalType,
+    ) -> Result<Portfolio> {
+        self.legs(&[
+            (long_put, OptionType::Put, 1.),
+            (short_put, OptionType::Put, -1.),
+            (short_call, OptionType::Call, -1.),
+            (long_call, OptionType::Call, 1.),
+        ])
+    }
+}
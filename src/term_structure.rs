@@ -0,0 +1,136 @@
+//! ATM volatility term structure and forward volatilities.
+//!
+//! atm_term_structure() collects the ATM IV of every expiry of a board. Between expiries, total variance sigma^2 tau is
+//! interpolated linearly in tau; before the first expiry the total variance is interpolated from 0 at tau = 0, i.e. the first ATM IV is held flat.
+//! The forward volatility between two times t1 < t2 is the volatility implied for the period between them:
+//! `sigma_fwd = sqrt((sigma_2^2 tau_2 - sigma_1^2 tau_1) / (tau_2 - tau_1))`
+//!
+//! constant_maturity_iv() gives a synthetic ATM IV at a fixed number of days from the two expiries bracketing it,
+//! so that a series of snapshots does not jump when the front expiry rolls.
+//!
+//! # Example
+//! ```ignore
+//! let term_structure = board.atm_term_structure();
+//! for (expiry, iv) in term_structure.pairs() {
+//!     println!("{}: {}", expiry, iv);
+//! }
+//! let fwd = board.forward_vol(front_expiry, back_expiry)?;
+//! let vix_like = boards_ts.constant_maturity_iv(30.);
+//! ```
+
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TermStructurePoint {
+    pub maturity: DateTime<Utc>,
+    /// Time to maturity in years
+    pub tau: FloatType,
+    pub atm_iv: FloatType,
+}
+
+impl TermStructurePoint {
+    pub fn total_variance(&self) -> FloatType {
+        self.atm_iv * self.atm_iv * self.tau
+    }
+}
+
+/// ATM IV per expiry, in ascending order of maturity
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TermStructure(pub Vec<TermStructurePoint>);
+
+impl TermStructure {
+    /// (expiry, ATM IV) pairs
+    pub fn pairs(&self) -> Vec<(DateTime<Utc>, FloatType)> {
+        self.0.iter().map(|p| (p.maturity, p.atm_iv)).collect()
+    }
+
+    /// Total ATM variance at `tau` years, interpolated linearly in tau. None beyond the last expiry.
+    pub fn total_variance(&self, tau: FloatType) -> Option<FloatType> {
+        let last = self.0.last()?;
+        if tau > last.tau || tau < 0. {
+            return None;
+        }
+        let i = self.0.partition_point(|p| p.tau < tau);
+        let (t1, v1) = match i {
+            0 => (0., 0.),
+            _ => (self.0[i - 1].tau, self.0[i - 1].total_variance()),
+        };
+        let (t2, v2) = (self.0[i].tau, self.0[i].total_variance());
+        if t2 == t1 {
+            return Some(v2);
+        }
+        Some(v1 + (v2 - v1) * (tau - t1) / (t2 - t1))
+    }
+
+    /// ATM IV at `tau` years, interpolated in total variance. None beyond the last expiry.
+    pub fn iv(&self, tau: FloatType) -> Option<FloatType> {
+        if tau <= 0. {
+            return self.0.first().map(|p| p.atm_iv);
+        }
+        Some((self.total_variance(tau)? / tau).sqrt())
+    }
+
+    /// ATM IV at `tau` years interpolated in total variance between the two expiries bracketing it.
+    /// None if tau is before the first or after the last expiry.
+    pub fn constant_maturity_iv(&self, tau: FloatType) -> Option<FloatType> {
+        if tau < self.0.first()?.tau {
+            return None;
+        }
+        self.iv(tau)
+    }
+
+    /// Forward volatility between tau1 and tau2 years.
+    /// Fails if the total variance decreases between them, which is a calendar arbitrage.
+    pub fn forward_vol_between(&self, tau1: FloatType, tau2: FloatType) -> Result<FloatType> {
+        ensure!(tau1 < tau2, "tau1 must be before tau2");
+        let out_of_range = || anyhow!("Time is beyond the last expiry of the term structure");
+        let v1 = self.total_variance(tau1).ok_or_else(out_of_range)?;
+        let v2 = self.total_variance(tau2).ok_or_else(out_of_range)?;
+        ensure!(v2 >= v1, "Total variance decreases between tau1 and tau2 (calendar arbitrage)");
+        Ok(((v2 - v1) / (tau2 - tau1)).sqrt())
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// ATM IV of every non-empty expiry whose maturity has not passed.
+    pub fn atm_term_structure(&self) -> TermStructure {
+        TermStructure(
+            self.sort_by_maturity()
+                .0
+                .iter()
+                .filter(|chain| !chain.0.is_empty())
+                .map(|chain| TermStructurePoint {
+                    maturity: chain.0[0].maturity,
+                    tau: chain.0[0].tau(),
+                    atm_iv: chain.atm().iv(),
+                })
+                .filter(|p| p.tau > 0. && p.atm_iv.is_finite())
+                .collect(),
+        )
+    }
+
+    /// Implied forward volatility between the expiries t1 and t2 (or any times up to the last expiry).
+    pub fn forward_vol(&self, t1: DateTime<Utc>, t2: DateTime<Utc>) -> Result<FloatType> {
+        let term_structure = self.atm_term_structure();
+        let first = self.0.iter().find_map(|chain| chain.0.first()).ok_or_else(|| anyhow!("The board is empty"))?;
+        let now = first.valuation_time.unwrap_or_else(Utc::now);
+        let tau = |t: DateTime<Utc>| first.day_count.year_fraction(now, t);
+        term_structure.forward_vol_between(tau(t1), tau(t2))
+    }
+
+    /// Synthetic ATM IV `days` calendar days ahead, e.g. 30 for a VIX-like index. NaN if no two expiries bracket it.
+    pub fn constant_maturity_iv(&self, days: FloatType) -> FloatType {
+        self.atm_term_structure()
+            .constant_maturity_iv(days / 365.)
+            .unwrap_or(FloatType::NAN)
+    }
+}
+
+impl TimeSeries<OptionBoard<OptionTick>> {
+    pub fn constant_maturity_iv(&self, days: FloatType) -> TimeSeries<FloatType> {
+        self.map(|board| board.constant_maturity_iv(days))
+    }
+}
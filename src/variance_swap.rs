@@ -0,0 +1,126 @@
+//! Model-free variance from the strip of OTM options, following the CBOE VIX white paper.
+//!
+//! For a single expiry the fair variance is
+//! `sigma^2 = 2/T * sum(dK_i / K_i^2 * e^(RT) * Q(K_i)) - 1/T * (F/K_0 - 1)^2`
+//! where F is the forward implied by put-call parity (OptionChain::implied_forward()), K_0 is the first strike at or below F and Q(K_i) is the OTM premium at K_i (the average of call and put at K_0).
+//!
+//! A VIX-style index interpolates the total variance of the two expiries around the target maturity and quotes the result as a volatility in percent.
+//!
+//! # Example
+//! ```ignore
+//! let fair_vol = chain.variance_swap_rate()?.sqrt();
+//! let vix = board.vix_style_index(30.)?;
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::models::*;
+use anyhow::{anyhow, ensure, Result};
+use rust_decimal::prelude::*;
+use std::collections::BTreeMap;
+
+impl OptionChain<OptionTick> {
+    /// Returns the annualized fair variance of a variance swap maturing at the expiry of the chain.
+    /// Take the square root to get the variance swap strike in volatility terms.
+    pub fn variance_swap_rate(&self) -> Result<FloatType> {
+        ensure!(!self.0.is_empty(), "OptionChain is empty");
+        let tau = self.0[0].tau();
+        ensure!(tau > 0., "OptionChain has already expired");
+        let rate = self.risk_free_rate()?;
+        let growth = (rate * tau).exp();
+
+        // strike -> (call premium, put premium)
+        let mut premiums: BTreeMap<DecimalType, (Option<FloatType>, Option<FloatType>)> =
+            BTreeMap::new();
+        for tick in self.0.iter() {
+            let premium = tick.get_theoretical_price().get_value();
+            if !premium.is_finite() || premium <= 0. {
+                continue;
+            }
+            let entry = premiums.entry(tick.strike).or_insert((None, None));
+            match tick.option_type {
+                OptionType::Call => entry.0 = Some(premium),
+                OptionType::Put => entry.1 = Some(premium),
+            }
+        }
+
+        let forward = self.implied_forward()?;
+
+        let k0 = premiums
+            .keys()
+            .rev()
+            .map(|k| k.to_f64().unwrap())
+            .find(|k| *k <= forward)
+            .ok_or_else(|| anyhow!("No strike below the forward price"))?;
+
+        // OTM strip: puts below K0, calls above K0 and the average at K0
+        let strip: Vec<(FloatType, FloatType)> = premiums
+            .iter()
+            .filter_map(|(k, (call, put))| {
+                let k = k.to_f64().unwrap();
+                let q = if k < k0 {
+                    (*put)?
+                } else if k > k0 {
+                    (*call)?
+                } else {
+                    match (call, put) {
+                        (Some(c), Some(p)) => (c + p) / 2.,
+                        (Some(v), None) | (None, Some(v)) => *v,
+                        (None, None) => return None,
+                    }
+                };
+                Some((k, q))
+            })
+            .collect();
+        ensure!(strip.len() >= 2, "Not enough OTM options to replicate variance");
+
+        let n = strip.len();
+        let contribution: FloatType = (0..n)
+            .map(|i| {
+                let dk = if i == 0 {
+                    strip[1].0 - strip[0].0
+                } else if i == n - 1 {
+                    strip[n - 1].0 - strip[n - 2].0
+                } else {
+                    (strip[i + 1].0 - strip[i - 1].0) / 2.
+                };
+                let (k, q) = strip[i];
+                dk / (k * k) * growth * q
+            })
+            .sum();
+
+        Ok(2. / tau * contribution - (forward / k0 - 1.).powi(2) / tau)
+    }
+}
+
+impl OptionBoard<OptionTick> {
+    /// Returns a VIX-style volatility index (in percent) for a constant maturity of `target_days`.
+    /// The total variance of the expiries immediately before and after the target is linearly interpolated in time.
+    /// If every expiry lies on one side of the target, the two closest expiries are used for extrapolation.
+    pub fn vix_style_index(&self, target_days: FloatType) -> Result<FloatType> {
+        let terms: Vec<(FloatType, FloatType)> = self
+            .sort_by_maturity()
+            .0
+            .iter()
+            .filter(|chain| !chain.0.is_empty())
+            .filter_map(|chain| Some((chain.0[0].tau(), chain.variance_swap_rate().ok()?)))
+            .collect();
+        ensure!(
+            terms.len() >= 2,
+            "At least two expiries with a variance are required"
+        );
+
+        let target = target_days / 365.;
+        let next = terms
+            .iter()
+            .position(|(tau, _)| *tau > target)
+            .unwrap_or(terms.len() - 1)
+            .max(1);
+        let (t1, var1) = terms[next - 1];
+        let (t2, var2) = terms[next];
+
+        let w1 = (t2 - target) / (t2 - t1);
+        let variance = (t1 * var1 * w1 + t2 * var2 * (1. - w1)) / target;
+        ensure!(variance >= 0., "Interpolated variance is negative");
+        Ok(100. * variance.sqrt())
+    }
+}
@@ -0,0 +1,107 @@
+//! Volatility cones: the distribution of realized volatility per horizon.
+//!
+//! For each window length, the realized volatility is computed over every window of that many bars of the history (overlapping),
+//! and the cone reports its minimum, quartiles, median and maximum along with the latest value. Overlaying the ATM IV of the same
+//! horizons from a TermStructure shows whether implied volatility is high or low relative to what has been realized.
+//!
+//! # Example
+//! ```ignore
+//! let cone = vol_cone(&ohlc_ts, &[5, 10, 21, 63, 126], RealizedVolEstimator::CloseToClose, 252.)?
+//!     .with_implied(&board.atm_term_structure());
+//! for point in cone.points.iter() {
+//!     println!("{} bars: median {:.2}, implied {:?}", point.window, point.median, point.implied);
+//! }
+//! ```
+
+use crate::models::*;
+use crate::realized_vol::*;
+use crate::term_structure::TermStructure;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VolConePoint {
+    /// Window length in bars
+    pub window: usize,
+    /// Window length in years
+    pub tau: FloatType,
+    pub min: FloatType,
+    pub p25: FloatType,
+    pub median: FloatType,
+    pub p75: FloatType,
+    pub max: FloatType,
+    /// Realized volatility of the latest window
+    pub current: FloatType,
+    /// ATM IV at tau, set by VolCone::with_implied
+    pub implied: Option<FloatType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VolCone {
+    /// One point per window, in the order of the windows given to vol_cone
+    pub points: Vec<VolConePoint>,
+}
+
+impl VolCone {
+    /// Sets the implied volatility of each horizon from the ATM term structure (None beyond its last expiry).
+    pub fn with_implied(mut self, term_structure: &TermStructure) -> Self {
+        for point in self.points.iter_mut() {
+            point.implied = term_structure.iv(point.tau);
+        }
+        self
+    }
+}
+
+/// Linear interpolation between the order statistics of sorted values.
+fn quantile(sorted: &[FloatType], q: FloatType) -> FloatType {
+    let position = q * (sorted.len() - 1) as FloatType;
+    let (i, w) = (position.floor() as usize, position.fract());
+    match sorted.get(i + 1) {
+        Some(next) => sorted[i] + w * (next - sorted[i]),
+        None => sorted[i],
+    }
+}
+
+/// Volatility cone of the OHLC history for the given window lengths in bars.
+/// Each window needs at least 2 bars and the history at least as many bars as the longest window.
+pub fn vol_cone(
+    history: &TimeSeries<Ohlc>,
+    windows: &[usize],
+    estimator: RealizedVolEstimator,
+    periods_per_year: FloatType,
+) -> Result<VolCone> {
+    let points = windows
+        .iter()
+        .map(|&window| {
+            ensure!(window >= 2, "A window needs at least 2 bars");
+            ensure!(
+                history.0.len() >= window,
+                "The history has {} bars, fewer than the window of {}",
+                history.0.len(),
+                window
+            );
+            let vols = history
+                .0
+                .windows(window)
+                .map(|bars| {
+                    TimeSeries(bars.to_vec()).realized_vol(estimator.clone(), periods_per_year)
+                })
+                .collect::<Result<Vec<FloatType>>>()?;
+            let current = *vols.last().unwrap();
+            let mut sorted = vols;
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            Ok(VolConePoint {
+                window,
+                tau: window as FloatType / periods_per_year,
+                min: sorted[0],
+                p25: quantile(&sorted, 0.25),
+                median: quantile(&sorted, 0.5),
+                p75: quantile(&sorted, 0.75),
+                max: sorted[sorted.len() - 1],
+                current,
+                implied: None,
+            })
+        })
+        .collect::<Result<Vec<VolConePoint>>>()?;
+    Ok(VolCone { points })
+}
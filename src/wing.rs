@@ -0,0 +1,174 @@
+//! Wing model: a smile quadratic in delta with separate put and call wings.
+//!
+//! With x = call delta - 0.5 (0 at the money, negative on the put side), the IV is
+//! `atm_vol + skew * x + put_wing * x^2` for x < 0 and `atm_vol + skew * x + call_wing * x^2` for x >= 0.
+//! The model is linear in its four parameters once the delta of each quote is fixed by its market IV, so the fit is a
+//! least-squares solve without iterations or starting values. It is less flexible than a stochastic volatility fit,
+//! which makes it robust on illiquid chains with few or noisy strikes.
+//!
+//! fit_wing_model_weighted() minimizes the weighted squared IV errors with per-strike SmileWeights. On a chain of
+//! StrikeBoards, fit_wing_model() fits the mids with the default liquidity weights of LiquidityReport::smile_weights(),
+//! so that wide wings do not dominate the fit.
+//!
+//! # Example
+//! ```ignore
+//! let fit = chain.fit_wing_model()?;
+//! println!("{:?} rmse: {}", fit.params, fit.rmse);
+//! let model_iv = fit.params.iv(&tick);
+//! let fit = chain.fit_wing_model_weighted(&weights)?;
+//! let fit = board_chain.fit_wing_model()?;
+//! ```
+
+use crate::black_scholes::BlackScholes;
+use crate::greeks::EuropeanGreeks;
+use crate::liquidity::SmileWeights;
+use crate::math::solve_dense;
+use crate::models::*;
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// Number of fixed-point iterations between IV and delta in WingParams::iv
+const MAX_ITER: usize = 50;
+/// Lowest IV returned by the model
+const MIN_IV: FloatType = 1e-4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WingParams {
+    /// IV at 50 call delta
+    pub atm_vol: FloatType,
+    /// Slope of the IV in call delta at the money
+    pub skew: FloatType,
+    /// Curvature below 50 call delta
+    pub put_wing: FloatType,
+    /// Curvature above 50 call delta
+    pub call_wing: FloatType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WingFit {
+    pub params: WingParams,
+    /// Root mean squared IV error of the fitted model
+    pub rmse: FloatType,
+    pub n_options: usize,
+}
+
+/// Delta of the call with the strike, maturity and IV of the tick.
+fn call_delta(tick: &OptionTick, iv: FloatType) -> FloatType {
+    let mut call = tick.clone();
+    call.option_type = OptionType::Call;
+    call.option_value = OptionValue::ImpliedVolatility(iv);
+    call.delta()
+}
+
+fn basis(x: FloatType) -> [FloatType; 4] {
+    let square = x * x;
+    if x < 0. {
+        [1., x, square, 0.]
+    } else {
+        [1., x, 0., square]
+    }
+}
+
+impl WingParams {
+    /// IV at the given call delta.
+    pub fn iv_at_delta(&self, call_delta: FloatType) -> FloatType {
+        let b = basis(call_delta - 0.5);
+        (self.atm_vol * b[0] + self.skew * b[1] + self.put_wing * b[2] + self.call_wing * b[3])
+            .max(MIN_IV)
+    }
+
+    /// IV at the strike and maturity of the tick, solving IV = iv_at_delta(delta(IV)) by fixed-point iteration.
+    pub fn iv(&self, tick: &OptionTick) -> FloatType {
+        let mut iv = self.atm_vol.max(MIN_IV);
+        for _ in 0..MAX_ITER {
+            let next = self.iv_at_delta(call_delta(tick, iv));
+            if (next - iv).abs() < 1e-10 {
+                return next;
+            }
+            iv = next;
+        }
+        iv
+    }
+}
+
+impl OptionChain<OptionTick> {
+    /// Fits the wing model to the IVs of the OTM options of the chain by least squares.
+    /// Ticks whose IV or delta is not finite are skipped.
+    pub fn fit_wing_model(&self) -> Result<WingFit> {
+        self.fit_wing_model_weighted(&SmileWeights::default())
+    }
+
+    /// Fits the wing model by weighted least squares, with the weight of each tick from `weights`.
+    /// The rmse of the fit is not weighted.
+    pub fn fit_wing_model_weighted(&self, weights: &SmileWeights) -> Result<WingFit> {
+        let points: Vec<([FloatType; 4], FloatType, FloatType)> = self
+            .otm()
+            .0
+            .iter()
+            .filter(|tick| tick.tau() > 0.)
+            .map(|tick| {
+                let iv = tick.get_implied_volatility().get_value();
+                (basis(call_delta(tick, iv) - 0.5), iv, weights.weight(tick))
+            })
+            .filter(|(b, iv, w)| iv.is_finite() && b[1].is_finite() && *w > 0.)
+            .collect();
+        ensure!(
+            points.len() >= 4,
+            "At least four valid options are required to fit the wing model"
+        );
+        let has_puts = points.iter().any(|(b, _, _)| b[2] != 0.);
+        let has_calls = points.iter().any(|(b, _, _)| b[3] != 0.);
+        ensure!(
+            has_puts && has_calls,
+            "The wing model needs options on both sides of 50 delta"
+        );
+
+        let mut a = vec![vec![0.; 4]; 4];
+        let mut rhs = vec![0.; 4];
+        for (b, iv, w) in points.iter() {
+            for i in 0..4 {
+                rhs[i] += w * b[i] * iv;
+                for j in 0..4 {
+                    a[i][j] += w * b[i] * b[j];
+                }
+            }
+        }
+        let x = solve_dense(a, rhs);
+        ensure!(
+            x.iter().all(|v| v.is_finite()),
+            "The wing model fit is degenerate"
+        );
+        let params = WingParams {
+            atm_vol: x[0],
+            skew: x[1],
+            put_wing: x[2],
+            call_wing: x[3],
+        };
+        let mse = points
+            .iter()
+            .map(|(b, iv, _)| {
+                (b.iter()
+                    .zip(x.iter())
+                    .map(|(b, x)| b * x)
+                    .sum::<FloatType>()
+                    - iv)
+                    .powi(2)
+            })
+            .sum::<FloatType>()
+            / points.len() as FloatType;
+        Ok(WingFit {
+            params,
+            rmse: mse.sqrt(),
+            n_options: points.len(),
+        })
+    }
+}
+
+impl OptionChain<StrikeBoard> {
+    /// Fits the wing model to the mids of the strikes, weighted by the liquidity of each strike.
+    pub fn fit_wing_model(&self) -> Result<WingFit> {
+        let weights = self.liquidity_report().smile_weights();
+        let mids = OptionChain(self.0.iter().filter_map(|board| board.mid().ok()).collect());
+        mids.fit_wing_model_weighted(&weights)
+    }
+}